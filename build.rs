@@ -1,29 +1,57 @@
-extern crate bindgen;
-
-use bindgen::Builder;
-
-use std::env;
-use std::path::Path;
-
 fn main() {
-    // for (var, value) in env::vars() {
-    //     eprintln!("{var}={value}", var=var, value=value);
-    // }
-
-    // println!("cargo:rustc-link-search=all=C:\\Users\\grani\\Documents\\Rust\\rust_olc_pge\\lib");
-    // println!("cargo:rustc-link-lib=static=phonon");
-
-    // let builder = Builder::default()
-    //     .layout_tests(false)
-    //     .size_t_is_usize(true)
-    //     .header("headers/phonon.h")
-    //     .rustified_enum("IPL(.*)");
-
-    // let out_dir = ".";//env::var("OUT_DIR").unwrap();
-    // let dest_path = Path::new("src/steam_audio_bindgen.rs");
-
-    // // let bindings = builder.generate().unwrap();
-    // // bindings.write_to_file(&dest_path).unwrap();
+    #[cfg(feature = "steam-audio")]
+    steam_audio::generate();
+}
 
-    // eprintln!("Generated Bindings => {:?}", dest_path);
+//Bindings to the Steam Audio SDK, regenerated from its header. Point
+//STEAM_AUDIO_SDK at the unpacked SDK, the folder with include/ and lib/
+#[cfg(feature = "steam-audio")]
+mod steam_audio {
+    use std::env;
+    use std::path::PathBuf;
+
+    pub fn generate() {
+        println!("cargo:rerun-if-env-changed=STEAM_AUDIO_SDK");
+        let sdk = PathBuf::from(
+            env::var("STEAM_AUDIO_SDK").expect("Set STEAM_AUDIO_SDK to the Steam Audio SDK folder"),
+        );
+        let header = sdk.join("include").join("phonon.h");
+        println!("cargo:rerun-if-changed={}", header.display());
+
+        let platform = match (
+            env::var("CARGO_CFG_TARGET_OS").unwrap().as_str(),
+            env::var("CARGO_CFG_TARGET_ARCH").unwrap().as_str(),
+        ) {
+            ("linux", "x86") => "linux-x86",
+            ("linux", _) => "linux-x64",
+            ("windows", "x86") => "windows-x86",
+            ("windows", _) => "windows-x64",
+            ("macos", _) => "osx",
+            ("android", "aarch64") => "android-armv8",
+            ("android", _) => "android-armv7",
+            (os, _) => panic!("Steam Audio doesn't support {}", os),
+        };
+        println!(
+            "cargo:rustc-link-search=native={}",
+            sdk.join("lib").join(platform).display()
+        );
+        println!("cargo:rustc-link-lib=dylib=phonon");
+
+        let bindings = bindgen::Builder::default()
+            .header(header.to_string_lossy())
+            .clang_arg(format!("-I{}", sdk.join("include").display()))
+            .layout_tests(false)
+            .size_t_is_usize(true)
+            .allowlist_function("ipl.*")
+            .allowlist_type("IPL.*")
+            .allowlist_var("STEAMAUDIO_.*")
+            .bitfield_enum("IPL.*Flags")
+            .rustified_enum("IPL.*")
+            .generate()
+            .expect("Couldn't generate Steam Audio bindings");
+        let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("phonon.rs");
+        bindings
+            .write_to_file(&out)
+            .expect("Couldn't write Steam Audio bindings");
+    }
 }
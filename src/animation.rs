@@ -0,0 +1,518 @@
+use super::{
+    decal::Decal,
+    engine::OGEngine,
+    og_engine::{OGData, Rcode},
+    pixel::Pixel,
+    renderer::Renderer,
+    sprite::{Sprite, SpriteFlip},
+    util::{Vf2d, Vi2d},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// One frame of a sheet, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SheetFrame {
+    pub pos: Vi2d,
+    pub size: Vi2d,
+    /// Seconds, used by clips made with `SpriteSheet::clip_from_range`.
+    pub duration: f32,
+    /// Where the frame goes in the untrimmed image, for sheets packed with
+    /// transparent borders trimmed off.
+    pub offset: Vi2d,
+    /// Size of the untrimmed image.
+    pub source_size: Vi2d,
+}
+
+impl SheetFrame {
+    /// An untrimmed frame.
+    pub fn new(pos: Vi2d, size: Vi2d, duration: f32) -> Self {
+        Self {
+            pos,
+            size,
+            duration,
+            offset: Vi2d::new(0, 0),
+            source_size: size,
+        }
+    }
+
+    //Offset of the trimmed frame once the untrimmed image is flipped
+    fn flipped_offset(&self, flip_x: bool, flip_y: bool) -> Vi2d {
+        Vi2d::new(
+            if flip_x { self.source_size.x - self.offset.x - self.size.x } else { self.offset.x },
+            if flip_y { self.source_size.y - self.offset.y - self.size.y } else { self.offset.y },
+        )
+    }
+}
+
+/// How a clip plays once it reaches the last frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationMode {
+    Loop,
+    /// Plays forwards, then backwards, then forwards again.
+    PingPong,
+    /// Stops on the last frame.
+    OneShot,
+}
+
+impl Default for AnimationMode {
+    fn default() -> Self {
+        AnimationMode::Loop
+    }
+}
+
+/// Frames of a sheet played in order, each for its own time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnimationClip {
+    /// Indices into the sheet's frames.
+    pub frames: Vec<usize>,
+    /// Seconds for each frame.
+    pub durations: Vec<f32>,
+    pub mode: AnimationMode,
+}
+
+impl AnimationClip {
+    /// Every frame shown for `frame_duration` seconds.
+    pub fn new(frames: Vec<usize>, frame_duration: f32, mode: AnimationMode) -> Self {
+        Self {
+            durations: vec![frame_duration; frames.len()],
+            frames,
+            mode,
+        }
+    }
+
+    pub fn with_durations(mut self, durations: Vec<f32>) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    /// Seconds for one pass through the frames.
+    pub fn duration(&self) -> f32 {
+        (0..self.frames.len()).map(|i| self.frame_duration(i)).sum()
+    }
+
+    fn frame_duration(&self, index: usize) -> f32 {
+        self.durations.get(index).or_else(|| self.durations.last()).copied().unwrap_or(0.1)
+    }
+}
+
+/// An image cut into frames, with named clips. Share it between
+/// `AnimatedSprite`s with an `Arc`.
+#[derive(Default)]
+pub struct SpriteSheet {
+    pub sprite: Sprite,
+    frames: Vec<SheetFrame>,
+    clips: HashMap<String, AnimationClip>,
+    decal: Option<Decal>,
+}
+
+impl SpriteSheet {
+    /// Frames of `frame_size` left to right, top to bottom.
+    pub fn from_grid(sprite: Sprite, frame_size: Vi2d) -> Self {
+        Self::from_grid_with_spacing(sprite, frame_size, Vi2d::new(0, 0), Vi2d::new(0, 0))
+    }
+
+    /// Like `from_grid` for sheets with a border of `margin` and `spacing`
+    /// pixels between frames.
+    pub fn from_grid_with_spacing(sprite: Sprite, frame_size: Vi2d, margin: Vi2d, spacing: Vi2d) -> Self {
+        let mut frames = vec![];
+        if frame_size.x > 0 && frame_size.y > 0 {
+            let mut y = margin.y;
+            while y + frame_size.y <= sprite.height as i32 {
+                let mut x = margin.x;
+                while x + frame_size.x <= sprite.width as i32 {
+                    frames.insert(frames.len(), SheetFrame::new(Vi2d::new(x, y), frame_size, 0.1));
+                    x += frame_size.x + spacing.x;
+                }
+                y += frame_size.y + spacing.y;
+            }
+        }
+        Self::from_frames(sprite, frames)
+    }
+
+    /// Frames from `(position, size)` rects.
+    pub fn from_rects(sprite: Sprite, rects: &[(Vi2d, Vi2d)]) -> Self {
+        let frames = rects
+            .iter()
+            .map(|(pos, size)| SheetFrame::new(*pos, *size, 0.1))
+            .collect();
+        Self::from_frames(sprite, frames)
+    }
+
+    pub fn from_frames(sprite: Sprite, frames: Vec<SheetFrame>) -> Self {
+        Self {
+            sprite,
+            frames,
+            clips: HashMap::new(),
+            decal: None,
+        }
+    }
+
+    /// Reads the JSON Aseprite exports next to a sheet, array or hash
+    /// style. Frame tags become clips.
+    pub fn from_aseprite_json(sprite: Sprite, json: &str) -> Result<Self, Rcode> {
+        let data: AsepriteData = serde_json::from_str(json).map_err(|e| {
+            log::error!("Bad Aseprite json: {}", e);
+            Rcode::Fail
+        })?;
+        let frames = match data.frames {
+            AsepriteFrames::Array(frames) => frames,
+            AsepriteFrames::Hash(frames) => frames
+                .into_iter()
+                .map(|(_, f)| serde_json::from_value(f))
+                .collect::<Result<Vec<AsepriteFrame>, _>>()
+                .map_err(|e| {
+                    log::error!("Bad Aseprite frame: {}", e);
+                    Rcode::Fail
+                })?,
+        };
+        let frames = frames
+            .iter()
+            .map(|f| {
+                let mut frame = SheetFrame::new(
+                    Vi2d::new(f.frame.x, f.frame.y),
+                    Vi2d::new(f.frame.w, f.frame.h),
+                    f.duration as f32 / 1000.0,
+                );
+                if f.trimmed {
+                    if let (Some(placed), Some(source)) = (&f.sprite_source_size, &f.source_size) {
+                        frame.offset = Vi2d::new(placed.x, placed.y);
+                        frame.source_size = Vi2d::new(source.w, source.h);
+                    }
+                }
+                frame
+            })
+            .collect();
+        let mut sheet = Self::from_frames(sprite, frames);
+        for tag in data.meta.frame_tags {
+            let mode = match tag.direction.as_str() {
+                "pingpong" | "pingpong_reverse" => AnimationMode::PingPong,
+                _ => AnimationMode::Loop,
+            };
+            let mut clip = sheet.clip_from_range(tag.from, tag.to, mode);
+            if tag.direction.ends_with("reverse") {
+                clip.frames.reverse();
+                clip.durations.reverse();
+            }
+            sheet.add_clip(&tag.name, clip);
+        }
+        Ok(sheet)
+    }
+
+    pub fn load_aseprite(image_path: &str, json_path: &str) -> Result<Self, Rcode> {
+        let sprite = Sprite::load_from_file::<super::util::AutoLoader>(image_path).ok_or(Rcode::NoFile)?;
+        let json = std::fs::read_to_string(json_path).map_err(|_| Rcode::NoFile)?;
+        Self::from_aseprite_json(sprite, &json)
+    }
+
+    /// Frames `from` to `to` inclusive, with their own durations. Frames past
+    /// the end of the sheet are left out, so an empty sheet gives an empty clip.
+    pub fn clip_from_range(&self, from: usize, to: usize, mode: AnimationMode) -> AnimationClip {
+        let (frames, durations) = (from..=to)
+            .map_while(|i| self.frames.get(i).map(|frame| (i, frame.duration)))
+            .unzip();
+        AnimationClip {
+            frames,
+            durations,
+            mode,
+        }
+    }
+
+    pub fn with_clip(mut self, name: &str, clip: AnimationClip) -> Self {
+        self.add_clip(name, clip);
+        self
+    }
+
+    pub fn add_clip(&mut self, name: &str, clip: AnimationClip) {
+        self.clips.insert(name.to_string(), clip);
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&AnimationClip> {
+        self.clips.get(name)
+    }
+
+    pub fn clips(&self) -> impl Iterator<Item = (&String, &AnimationClip)> {
+        self.clips.iter()
+    }
+
+    pub fn frames(&self) -> &[SheetFrame] {
+        &self.frames
+    }
+
+    pub fn frame(&self, index: usize) -> Option<&SheetFrame> {
+        self.frames.get(index)
+    }
+
+    /// Uploads the sheet so it can be drawn as a decal.
+    pub fn with_decal(mut self, renderer: &mut Renderer) -> Self {
+        self.create_decal(renderer);
+        self
+    }
+
+    pub fn create_decal(&mut self, renderer: &mut Renderer) {
+        self.decal = Some(Decal::create(Some(self.sprite.clone()), renderer));
+    }
+
+    pub fn decal(&self) -> Option<&Decal> {
+        self.decal.as_ref()
+    }
+}
+
+#[derive(Deserialize)]
+struct AsepriteRect {
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteSize {
+    w: i32,
+    h: i32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteFrame {
+    frame: AsepriteRect,
+    #[serde(default)]
+    trimmed: bool,
+    #[serde(default, rename = "spriteSourceSize")]
+    sprite_source_size: Option<AsepriteRect>,
+    #[serde(default, rename = "sourceSize")]
+    source_size: Option<AsepriteSize>,
+    #[serde(default = "default_duration")]
+    duration: u32,
+}
+
+fn default_duration() -> u32 {
+    100
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AsepriteFrames {
+    Array(Vec<AsepriteFrame>),
+    //Keeps file order, serde_json is built with preserve_order
+    Hash(serde_json::Map<String, serde_json::Value>),
+}
+
+#[derive(Deserialize)]
+struct AsepriteTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String,
+}
+
+#[derive(Default, Deserialize)]
+struct AsepriteMeta {
+    #[serde(default, rename = "frameTags")]
+    frame_tags: Vec<AsepriteTag>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteData {
+    frames: AsepriteFrames,
+    #[serde(default)]
+    meta: AsepriteMeta,
+}
+
+/// Plays clips of a `SpriteSheet`. Call `update` every frame with the
+/// elapsed time and draw it with `draw_decal` or `draw`.
+#[derive(Clone)]
+pub struct AnimatedSprite {
+    sheet: Arc<SpriteSheet>,
+    clip: String,
+    //Index into the clip's frames
+    index: usize,
+    time: f32,
+    forward: bool,
+    finished: bool,
+    /// 2 plays twice as fast.
+    pub speed: f32,
+    pub paused: bool,
+    pub flip: SpriteFlip,
+}
+
+impl AnimatedSprite {
+    pub fn new(sheet: Arc<SpriteSheet>, clip: &str) -> Self {
+        Self {
+            sheet,
+            clip: clip.to_string(),
+            index: 0,
+            time: 0.0,
+            forward: true,
+            finished: false,
+            speed: 1.0,
+            paused: false,
+            flip: SpriteFlip::None,
+        }
+    }
+
+    pub fn sheet(&self) -> &Arc<SpriteSheet> {
+        &self.sheet
+    }
+
+    /// Switches clip, starting it over unless it is already playing.
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            self.clip = clip.to_string();
+            self.restart();
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.index = 0;
+        self.time = 0.0;
+        self.forward = true;
+        self.finished = false;
+    }
+
+    pub fn clip_name(&self) -> &str {
+        &self.clip
+    }
+
+    /// A `OneShot` clip reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn update(&mut self, elapsed_time: f64) {
+        if self.paused || self.finished {
+            return;
+        }
+        let sheet = Arc::clone(&self.sheet);
+        let clip = match sheet.clip(&self.clip) {
+            Some(clip) if !clip.frames.is_empty() => clip,
+            _ => return,
+        };
+        self.index = self.index.min(clip.frames.len() - 1);
+        self.time += elapsed_time as f32 * self.speed;
+        //Bounded so a zero length clip can't hang
+        for _ in 0..clip.frames.len() * 4 {
+            let duration = clip.frame_duration(self.index);
+            if self.time < duration {
+                break;
+            }
+            self.time -= duration;
+            self.step(clip);
+            if self.finished {
+                break;
+            }
+        }
+        //After a long hitch the clip jumps on instead of racing to catch up
+        if self.time >= clip.frame_duration(self.index) {
+            self.time = 0.0;
+        }
+    }
+
+    fn step(&mut self, clip: &AnimationClip) {
+        let last = clip.frames.len() - 1;
+        match clip.mode {
+            AnimationMode::Loop => self.index = if self.index >= last { 0 } else { self.index + 1 },
+            AnimationMode::OneShot => {
+                if self.index >= last {
+                    self.finished = true;
+                    self.time = 0.0;
+                } else {
+                    self.index += 1;
+                }
+            }
+            AnimationMode::PingPong => {
+                if last == 0 {
+                    return;
+                }
+                if self.forward && self.index >= last {
+                    self.forward = false;
+                } else if !self.forward && self.index == 0 {
+                    self.forward = true;
+                }
+                if self.forward {
+                    self.index += 1;
+                } else {
+                    self.index -= 1;
+                }
+            }
+        }
+    }
+
+    /// Index of the sheet frame showing now.
+    pub fn current_frame(&self) -> Option<usize> {
+        let clip = self.sheet.clip(&self.clip)?;
+        clip.frames.get(self.index.min(clip.frames.len().saturating_sub(1))).copied()
+    }
+
+    fn sheet_frame(&self) -> Option<SheetFrame> {
+        self.current_frame().and_then(|i| self.sheet.frame(i)).copied()
+    }
+
+    /// Draws the current frame as a decal. Needs `SpriteSheet::create_decal`.
+    pub fn draw_decal<D: OGData>(&self, engine: &mut OGEngine<D>, pos: Vf2d, scale: Vf2d, tint: Pixel) {
+        let (frame, decal) = match (self.sheet_frame(), self.sheet.decal()) {
+            (Some(frame), Some(decal)) => (frame, decal.get()),
+            _ => return,
+        };
+        let size = Vf2d::new(frame.size.x as f32, frame.size.y as f32);
+        let (flip_x, flip_y) = flip_axes(self.flip);
+        let offset = frame.flipped_offset(flip_x, flip_y);
+        let pos = Vf2d::new(pos.x + offset.x as f32 * scale.x, pos.y + offset.y as f32 * scale.y);
+        //A negative scale mirrors the quad, so it's moved back over pos
+        let pos = Vf2d::new(
+            if flip_x { pos.x + size.x * scale.x } else { pos.x },
+            if flip_y { pos.y + size.y * scale.y } else { pos.y },
+        );
+        let scale = Vf2d::new(
+            if flip_x { -scale.x } else { scale.x },
+            if flip_y { -scale.y } else { scale.y },
+        );
+        let source = Vf2d::new(frame.pos.x as f32, frame.pos.y as f32);
+        engine.draw_partial_decal(pos, decal, source, size, scale, tint);
+    }
+
+    /// Draws the current frame into the Image layer being drawn to.
+    pub fn draw<D: OGData>(&self, engine: &mut OGEngine<D>, pos: Vi2d, scale: u32) {
+        if let Some(frame) = self.sheet_frame() {
+            let (flip_x, flip_y) = flip_axes(self.flip);
+            let offset = frame.flipped_offset(flip_x, flip_y);
+            let pos = Vi2d::new(pos.x + offset.x * scale as i32, pos.y + offset.y * scale as i32);
+            engine.draw_partial_sprite(pos, &self.sheet.sprite, frame.pos, frame.size, scale, self.flip);
+        }
+    }
+}
+
+//Whether a flip mirrors x and y
+pub(crate) fn flip_axes(flip: SpriteFlip) -> (bool, bool) {
+    match flip {
+        SpriteFlip::None => (false, false),
+        SpriteFlip::Horiz => (true, false),
+        SpriteFlip::Vert => (false, true),
+        SpriteFlip::Both => (true, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clip_from_range_stops_at_the_last_frame() {
+        let frames = (0..3)
+            .map(|i| SheetFrame::new(Vi2d::new(i * 8, 0), Vi2d::new(8, 8), (i + 1) as f32))
+            .collect();
+        let sheet = SpriteSheet::from_frames(Sprite::default(), frames);
+        let clip = sheet.clip_from_range(1, 10, AnimationMode::Loop);
+        assert_eq!(clip.frames, vec![1, 2]);
+        assert_eq!(clip.durations, vec![2.0, 3.0]);
+        assert!(sheet.clip_from_range(5, 6, AnimationMode::Loop).frames.is_empty());
+    }
+
+    #[test]
+    fn clip_from_range_on_empty_sheet_is_empty() {
+        let clip = SpriteSheet::default().clip_from_range(0, 3, AnimationMode::OneShot);
+        assert!(clip.frames.is_empty());
+        assert!(clip.durations.is_empty());
+    }
+}
@@ -0,0 +1,865 @@
+use super::{
+    audio::{Sound, SAMPLE_RATE},
+    audio_decode::{self, EncodedSound},
+    game_object::GameObject,
+    geometry::Mesh,
+    gltf_ext,
+    og_engine::Rcode,
+    sprite::Sprite,
+    util::{AutoLoader, ImageLoader, ResourcePack},
+};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Mutex, RwLock, Weak,
+    },
+    time::{Duration, SystemTime},
+};
+
+pub type AssetId = u64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AssetError {
+    /// The file could not be found.
+    NotFound(String),
+    /// The file exists but could not be read.
+    Io(String),
+    /// The bytes were read but are not a valid asset.
+    Decode(String),
+    /// One of the assets this asset depends on failed to load.
+    Dependency(String),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::NotFound(path) => write!(f, "asset not found: {}", path),
+            AssetError::Io(message) => write!(f, "could not read asset: {}", message),
+            AssetError::Decode(message) => write!(f, "could not decode asset: {}", message),
+            AssetError::Dependency(path) => write!(f, "dependency failed to load: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+impl From<AssetError> for Rcode {
+    fn from(error: AssetError) -> Self {
+        match error {
+            AssetError::NotFound(_) => Rcode::NoFile,
+            _ => Rcode::Fail,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadState {
+    Loading,
+    Loaded,
+    Failed(AssetError),
+}
+
+/// Sent when an asset has been reloaded because its file changed on disk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetChanged {
+    pub id: AssetId,
+    pub path: String,
+    type_id: TypeId,
+}
+
+impl AssetChanged {
+    pub fn is<T: Asset>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+}
+
+/// Anything the `AssetServer` can build from the bytes of a file.
+pub trait Asset: Sized + Send + Sync + 'static {
+    fn load(bytes: &[u8], ctx: &mut LoadContext) -> Result<Self, AssetError>;
+
+    /// Files `load` reads with `LoadContext::read`, relative to the asset.
+    /// On wasm, where reads can't block, they are fetched before `load`.
+    fn preload(_bytes: &[u8]) -> Vec<String> {
+        vec![]
+    }
+}
+
+struct Slot<T> {
+    state: Mutex<LoadState>,
+    value: RwLock<Option<Arc<T>>>,
+    dependencies: Mutex<Vec<Arc<dyn Dependency>>>,
+}
+
+impl<T> Slot<T> {
+    fn new(state: LoadState, value: Option<Arc<T>>) -> Self {
+        Self {
+            state: Mutex::new(state),
+            value: RwLock::new(value),
+            dependencies: Mutex::new(vec![]),
+        }
+    }
+
+    fn finish(&self, result: Result<T, AssetError>, dependencies: Vec<Arc<dyn Dependency>>) {
+        *self.dependencies.lock().unwrap() = dependencies;
+        match result {
+            Ok(value) => {
+                *self.value.write().unwrap() = Some(Arc::new(value));
+                *self.state.lock().unwrap() = LoadState::Loaded;
+            }
+            Err(e) => *self.state.lock().unwrap() = LoadState::Failed(e),
+        }
+    }
+}
+
+//Type erased view of a slot so an asset can track dependencies of any type
+trait Dependency: Send + Sync {
+    fn path(&self) -> &str;
+    fn recursive_load_state(&self) -> LoadState;
+}
+
+struct DependencyHandle<T> {
+    path: Arc<str>,
+    slot: Arc<Slot<T>>,
+}
+
+impl<T: Send + Sync> Dependency for DependencyHandle<T> {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn recursive_load_state(&self) -> LoadState {
+        recursive_load_state(&self.slot)
+    }
+}
+
+fn recursive_load_state<T>(slot: &Slot<T>) -> LoadState {
+    let state = slot.state.lock().unwrap().clone();
+    if state != LoadState::Loaded {
+        return state;
+    }
+    for dep in slot.dependencies.lock().unwrap().iter() {
+        match dep.recursive_load_state() {
+            LoadState::Loaded => {}
+            LoadState::Loading => return LoadState::Loading,
+            LoadState::Failed(_) => {
+                return LoadState::Failed(AssetError::Dependency(dep.path().to_string()))
+            }
+        }
+    }
+    LoadState::Loaded
+}
+
+/// A reference counted pointer to an asset that may still be loading.
+/// Handles to the same path share one copy of the asset, and the asset is
+/// freed once the last handle is dropped.
+pub struct Handle<T> {
+    id: AssetId,
+    path: Arc<str>,
+    slot: Arc<Slot<T>>,
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            path: self.path.clone(),
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("id", &self.id)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.slot, &other.slot)
+    }
+}
+
+impl<T: Send + Sync + 'static> Handle<T> {
+    pub fn id(&self) -> AssetId {
+        self.id
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the asset if it has finished loading.
+    pub fn get(&self) -> Option<Arc<T>> {
+        self.slot.value.read().unwrap().clone()
+    }
+
+    /// The state of this asset only, ignoring its dependencies.
+    pub fn load_state(&self) -> LoadState {
+        self.slot.state.lock().unwrap().clone()
+    }
+
+    /// The state of this asset and everything it depends on.
+    pub fn recursive_load_state(&self) -> LoadState {
+        recursive_load_state(&self.slot)
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.recursive_load_state() == LoadState::Loaded
+    }
+
+    /// Number of live handles to this asset.
+    pub fn ref_count(&self) -> usize {
+        Arc::strong_count(&self.slot)
+    }
+
+    fn as_dependency(&self) -> Arc<dyn Dependency> {
+        Arc::new(DependencyHandle {
+            path: self.path.clone(),
+            slot: self.slot.clone(),
+        })
+    }
+}
+
+/// Passed to `Asset::load` so loaders can pull in the assets they depend on.
+pub struct LoadContext<'a> {
+    server: &'a AssetServer,
+    path: &'a str,
+    dependencies: Vec<Arc<dyn Dependency>>,
+    //Files fetched ahead for `preload`
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl<'a> LoadContext<'a> {
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    pub fn extension(&self) -> Option<String> {
+        Path::new(self.path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+    }
+
+    /// Loads `path` relative to the asset currently being loaded and records
+    /// it as a dependency.
+    pub fn load<T: Asset>(&mut self, path: &str) -> Handle<T> {
+        let dir = Path::new(self.path).parent().unwrap_or_else(|| Path::new(""));
+        let handle = self.server.load::<T>(&dir.join(path).to_string_lossy());
+        self.dependencies.insert(self.dependencies.len(), handle.as_dependency());
+        handle
+    }
+
+    /// Reads `path` relative to the asset being loaded, from the mounted packs
+    /// or the server's root the same way the asset itself was read.
+    pub fn read(&mut self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let path = relative_to(self.path, path);
+        match self.files.remove(&path) {
+            Some(bytes) => Ok(bytes),
+            None => self.server.read_file(&path),
+        }
+    }
+
+    /// Registers an asset that is embedded in the one being loaded, under
+    /// `<path>#<label>`.
+    pub fn add_labeled<T: Asset>(&mut self, label: &str, value: T) -> Handle<T> {
+        let handle = self
+            .server
+            .insert(&format!("{}#{}", self.path, label), value);
+        self.dependencies.insert(self.dependencies.len(), handle.as_dependency());
+        handle
+    }
+}
+
+//Lets the watcher reload entries without knowing their type
+trait ErasedHandle: Send {
+    fn reload(self: Box<Self>, server: &AssetServer);
+}
+
+impl<T: Asset> ErasedHandle for Handle<T> {
+    fn reload(self: Box<Self>, server: &AssetServer) {
+        server.spawn_load(*self, true);
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct ThreadPool {
+    sender: Mutex<mpsc::Sender<Job>>,
+}
+
+impl ThreadPool {
+    fn new(threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..threads {
+            let receiver = receiver.clone();
+            //Workers are detached, they exit once the sender is dropped
+            std::thread::Builder::new()
+                .name(format!("og_asset_{}", i))
+                .spawn(move || loop {
+                    let job = match receiver.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => break,
+                    };
+                    //Keep the worker alive for the jobs after a panicking one
+                    if std::panic::catch_unwind(std::panic::AssertUnwindSafe(job)).is_err() {
+                        log::error!("Asset load job panicked");
+                    }
+                })
+                .expect("Couldn't spawn asset loader thread");
+        }
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    fn execute(&self, job: Job) {
+        //Only fails if every worker has died, in which case nothing will load anyway
+        let _ = self.sender.lock().unwrap().send(job);
+    }
+}
+
+struct Entry {
+    id: AssetId,
+    slot: Box<dyn Any + Send + Sync>,
+    upgrade: fn(&(dyn Any + Send + Sync), AssetId, &str) -> Option<Box<dyn ErasedHandle>>,
+    //Only set for assets that were read from a file
+    modified: Option<SystemTime>,
+}
+
+struct ServerInner {
+    root: PathBuf,
+    entries: Mutex<HashMap<(TypeId, String), Entry>>,
+    next_id: AtomicU64,
+    pool: Option<ThreadPool>,
+    events: Mutex<Vec<AssetChanged>>,
+    packs: RwLock<Vec<ResourcePack>>,
+}
+
+/// Loads assets in the background and hands out `Handle`s to them. Loading
+/// the same path twice returns the same asset for as long as it is alive.
+#[derive(Clone)]
+pub struct AssetServer {
+    inner: Arc<ServerInner>,
+}
+
+impl Default for AssetServer {
+    fn default() -> Self {
+        Self::new(".")
+    }
+}
+
+impl AssetServer {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get().clamp(1, 4))
+            .unwrap_or(1);
+        Self::with_threads(root, threads)
+    }
+
+    /// With `threads` set to 0 every load happens on the calling thread.
+    /// Threads are ignored on wasm, where loads are driven by fetch.
+    pub fn with_threads<P: AsRef<Path>>(root: P, threads: usize) -> Self {
+        let pool = if cfg!(target_arch = "wasm32") || threads == 0 {
+            None
+        } else {
+            Some(ThreadPool::new(threads))
+        };
+        Self {
+            inner: Arc::new(ServerInner {
+                root: root.as_ref().to_path_buf(),
+                entries: Mutex::new(HashMap::new()),
+                next_id: AtomicU64::new(0),
+                pool,
+                events: Mutex::new(vec![]),
+                packs: RwLock::new(vec![]),
+            }),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.inner.root
+    }
+
+    /// Serves loads from `pack` before falling back to the file system.
+    /// Packs mounted later take priority.
+    pub fn mount(&self, pack: ResourcePack) {
+        let mut packs = self.inner.packs.write().unwrap();
+        packs.insert(0, pack);
+    }
+
+    fn read_from_pack(&self, path: &str) -> Option<Result<Vec<u8>, AssetError>> {
+        let packs = self.inner.packs.read().unwrap();
+        let pack = packs.iter().find(|p| p.contains(path))?;
+        Some(
+            pack.get_file_buffer(path)
+                .map(|b| b.into_bytes())
+                .map_err(|_| AssetError::Io(path.to_string())),
+        )
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_file(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        self.read_from_pack(path).unwrap_or_else(|| {
+            std::fs::read(self.root().join(path)).map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => AssetError::NotFound(path.to_string()),
+                _ => AssetError::Io(format!("{}: {}", path, e)),
+            })
+        })
+    }
+
+    //Only packs can be read without blocking, anything else has to be
+    // fetched ahead through `Asset::preload`
+    #[cfg(target_arch = "wasm32")]
+    fn read_file(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        self.read_from_pack(path)
+            .unwrap_or_else(|| Err(AssetError::NotFound(path.to_string())))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn fetch_file(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        if let Some(result) = self.read_from_pack(path) {
+            return result;
+        }
+        let url = self.root().join(path);
+        super::util::get_file_as_u8(&url.to_string_lossy())
+            .await
+            .map_err(|_| AssetError::NotFound(path.to_string()))
+    }
+
+    /// Starts loading `path` (relative to the root) unless it is already
+    /// loaded or loading, and returns a handle to it straight away.
+    pub fn load<T: Asset>(&self, path: &str) -> Handle<T> {
+        let path = normalize(path);
+        let modified = self.file_modified(&path);
+        let (handle, is_new) =
+            self.get_or_create::<T>(&path, modified, || Slot::new(LoadState::Loading, None));
+        if is_new {
+            self.spawn_load(handle.clone(), false);
+        }
+        handle
+    }
+
+    /// Adds an asset that was created at runtime. Replaces whatever was
+    /// stored under `path` for any new handles.
+    pub fn insert<T: Asset>(&self, path: &str, value: T) -> Handle<T> {
+        let path = normalize(path);
+        let mut entries = self.inner.entries.lock().unwrap();
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let slot = Arc::new(Slot::new(LoadState::Loaded, Some(Arc::new(value))));
+        entries.insert(
+            (TypeId::of::<T>(), path.clone()),
+            Entry {
+                id,
+                slot: Box::new(Arc::downgrade(&slot)),
+                upgrade: upgrade_entry::<T>,
+                modified: None,
+            },
+        );
+        Handle {
+            id,
+            path: path.into(),
+            slot,
+        }
+    }
+
+    /// Returns a handle to `path` if something is still holding on to it.
+    pub fn get_handle<T: Asset>(&self, path: &str) -> Option<Handle<T>> {
+        let path = normalize(path);
+        let entries = self.inner.entries.lock().unwrap();
+        let entry = entries.get(&(TypeId::of::<T>(), path.clone()))?;
+        let slot = entry.slot.downcast_ref::<Weak<Slot<T>>>()?.upgrade()?;
+        Some(Handle {
+            id: entry.id,
+            path: path.into(),
+            slot,
+        })
+    }
+
+    pub fn load_state<T: Asset>(&self, path: &str) -> Option<LoadState> {
+        self.get_handle::<T>(path).map(|h| h.recursive_load_state())
+    }
+
+    /// Drops bookkeeping for assets that no longer have any handles.
+    pub fn free_unused(&self) {
+        self.inner
+            .entries
+            .lock()
+            .unwrap()
+            .retain(|(_, path), entry| (entry.upgrade)(entry.slot.as_ref(), entry.id, path).is_some());
+    }
+
+    /// Reloads every asset whose file changed since it was last loaded.
+    /// Handles keep pointing at the same asset and see the new data once the
+    /// reload finishes, which is reported through `drain_events`.
+    pub fn check_for_changes(&self) {
+        let mut changed: Vec<Box<dyn ErasedHandle>> = vec![];
+        {
+            let mut entries = self.inner.entries.lock().unwrap();
+            for ((_, path), entry) in entries.iter_mut() {
+                let (last, now) = match (entry.modified, self.file_modified(path)) {
+                    (Some(last), Some(now)) => (last, now),
+                    _ => continue,
+                };
+                if now > last {
+                    entry.modified = Some(now);
+                    if let Some(handle) = (entry.upgrade)(entry.slot.as_ref(), entry.id, path) {
+                        changed.insert(changed.len(), handle);
+                    }
+                }
+            }
+        }
+        //Reloading may load dependencies, so the entries can't be locked here
+        for handle in changed {
+            handle.reload(self);
+        }
+    }
+
+    /// Calls `check_for_changes` every `interval` on a background thread for
+    /// as long as the server is alive. Does nothing on wasm.
+    pub fn watch(&self, interval: Duration) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let inner = Arc::downgrade(&self.inner);
+            std::thread::Builder::new()
+                .name("og_asset_watcher".to_string())
+                .spawn(move || loop {
+                    std::thread::sleep(interval);
+                    match inner.upgrade() {
+                        Some(inner) => AssetServer { inner }.check_for_changes(),
+                        None => break,
+                    }
+                })
+                .expect("Couldn't spawn asset watcher thread");
+        }
+    }
+
+    /// Returns the assets that were reloaded since the last call.
+    pub fn drain_events(&self) -> Vec<AssetChanged> {
+        std::mem::take(&mut *self.inner.events.lock().unwrap())
+    }
+
+    fn file_modified(&self, path: &str) -> Option<SystemTime> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::fs::metadata(self.root().join(path))
+                .and_then(|m| m.modified())
+                .ok()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            None
+        }
+    }
+
+    fn get_or_create<T: Asset>(
+        &self,
+        path: &str,
+        modified: Option<SystemTime>,
+        create: impl FnOnce() -> Slot<T>,
+    ) -> (Handle<T>, bool) {
+        let mut entries = self.inner.entries.lock().unwrap();
+        let key = (TypeId::of::<T>(), path.to_string());
+        if let Some(entry) = entries.get(&key) {
+            if let Some(slot) = entry
+                .slot
+                .downcast_ref::<Weak<Slot<T>>>()
+                .and_then(|w| w.upgrade())
+            {
+                return (
+                    Handle {
+                        id: entry.id,
+                        path: path.into(),
+                        slot,
+                    },
+                    false,
+                );
+            }
+        }
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let slot = Arc::new(create());
+        entries.insert(
+            key,
+            Entry {
+                id,
+                slot: Box::new(Arc::downgrade(&slot)),
+                upgrade: upgrade_entry::<T>,
+                modified,
+            },
+        );
+        (
+            Handle {
+                id,
+                path: path.into(),
+                slot,
+            },
+            true,
+        )
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn spawn_load<T: Asset>(&self, handle: Handle<T>, reload: bool) {
+        let server = self.clone();
+        let job = move || {
+            let result = server.read_file(handle.path());
+            server.finish_load(&handle, result, HashMap::new(), reload);
+        };
+        match &self.inner.pool {
+            Some(pool) => pool.execute(Box::new(job)),
+            None => job(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn spawn_load<T: Asset>(&self, handle: Handle<T>, reload: bool) {
+        let server = self.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = server.fetch_file(handle.path()).await;
+            let mut files = HashMap::new();
+            if let Ok(bytes) = &result {
+                for path in T::preload(bytes) {
+                    let path = relative_to(handle.path(), &path);
+                    //A missing file is reported by the loader when it reads it
+                    if let Ok(data) = server.fetch_file(&path).await {
+                        files.insert(path, data);
+                    }
+                }
+            }
+            server.finish_load(&handle, result, files, reload);
+        });
+    }
+
+    fn finish_load<T: Asset>(
+        &self,
+        handle: &Handle<T>,
+        bytes: Result<Vec<u8>, AssetError>,
+        files: HashMap<String, Vec<u8>>,
+        reload: bool,
+    ) {
+        let mut ctx = LoadContext {
+            server: self,
+            path: handle.path(),
+            dependencies: vec![],
+            files,
+        };
+        //Malformed files can make a loader panic, that only fails this asset
+        let result = bytes.and_then(|bytes| {
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| T::load(&bytes, &mut ctx)))
+                .unwrap_or_else(|_| Err(AssetError::Decode(format!("{}: loader panicked", handle.path()))))
+        });
+        let dependencies = ctx.dependencies;
+        if !reload {
+            handle.slot.finish(result, dependencies);
+            return;
+        }
+        //A broken save shouldn't take down an asset that is already in use
+        match result {
+            Ok(value) => {
+                handle.slot.finish(Ok(value), dependencies);
+                let mut events = self.inner.events.lock().unwrap();
+                events.insert(
+                    events.len(),
+                    AssetChanged {
+                        id: handle.id(),
+                        path: handle.path().to_string(),
+                        type_id: TypeId::of::<T>(),
+                    },
+                );
+            }
+            Err(e) => log::warn!("Couldn't reload {}: {}", handle.path(), e),
+        }
+    }
+}
+
+fn upgrade_entry<T: Asset>(
+    slot: &(dyn Any + Send + Sync),
+    id: AssetId,
+    path: &str,
+) -> Option<Box<dyn ErasedHandle>> {
+    let slot = slot.downcast_ref::<Weak<Slot<T>>>()?.upgrade()?;
+    Some(Box::new(Handle {
+        id,
+        path: path.into(),
+        slot,
+    }))
+}
+
+//`path` relative to the directory of `asset_path`
+fn relative_to(asset_path: &str, path: &str) -> String {
+    let dir = Path::new(asset_path).parent().unwrap_or_else(|| Path::new(""));
+    normalize(&dir.join(path).to_string_lossy())
+}
+
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = vec![];
+    for part in path.split(|c| c == '/' || c == '\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.insert(parts.len(), p),
+        }
+    }
+    parts.join("/")
+}
+
+impl Asset for Sprite {
+    fn load(bytes: &[u8], ctx: &mut LoadContext) -> Result<Self, AssetError> {
+        AutoLoader::load_image_from_bytes(bytes)
+            .map_err(|_| AssetError::Decode(ctx.path().to_string()))
+    }
+}
+
+impl Asset for Mesh {
+    fn load(bytes: &[u8], _ctx: &mut LoadContext) -> Result<Self, AssetError> {
+        let text = std::str::from_utf8(bytes).map_err(|e| AssetError::Decode(e.to_string()))?;
+        let has_texture = text.lines().any(|l| l.starts_with("vt "));
+        Ok(Mesh::load_from_string(text, has_texture))
+    }
+}
+
+impl Asset for Sound {
+    fn load(bytes: &[u8], ctx: &mut LoadContext) -> Result<Self, AssetError> {
+        audio_decode::decode_bytes(bytes, ctx.extension().as_deref())
+            .map(|sound| sound.resampled(SAMPLE_RATE))
+            .map_err(|_| AssetError::Decode(ctx.path().to_string()))
+    }
+}
+
+impl Asset for EncodedSound {
+    fn load(bytes: &[u8], ctx: &mut LoadContext) -> Result<Self, AssetError> {
+        Ok(EncodedSound {
+            bytes: bytes.into(),
+            extension: ctx.extension(),
+        })
+    }
+}
+
+/// A glTF scene. `PBRTexture` indices on the meshes point into `images`,
+/// which are loaded as dependencies of the scene.
+pub struct GltfScene {
+    pub objects: Vec<GameObject>,
+    pub images: Vec<Handle<Sprite>>,
+}
+
+impl Asset for GltfScene {
+    fn load(bytes: &[u8], ctx: &mut LoadContext) -> Result<Self, AssetError> {
+        let gltf::Gltf { document, mut blob } =
+            gltf::Gltf::from_slice(bytes).map_err(|e| AssetError::Decode(e.to_string()))?;
+
+        let mut buffers = vec![];
+        for buffer in document.buffers() {
+            let mut data = match buffer.source() {
+                gltf::buffer::Source::Bin => blob
+                    .take()
+                    .ok_or_else(|| AssetError::Decode(format!("missing binary chunk in {}", ctx.path())))?,
+                gltf::buffer::Source::Uri(uri) if uri.starts_with("data:") => uri
+                    .split_once(";base64,")
+                    .and_then(|(_, data)| decode_base64(data))
+                    .ok_or_else(|| AssetError::Decode(format!("bad buffer uri in {}", ctx.path())))?,
+                gltf::buffer::Source::Uri(uri) => ctx.read(uri).map_err(|e| match e {
+                    AssetError::NotFound(path) => AssetError::Dependency(path),
+                    e => e,
+                })?,
+            };
+            if data.len() < buffer.length() {
+                return Err(AssetError::Decode(format!(
+                    "buffer {} of {} is too short",
+                    buffer.index(),
+                    ctx.path()
+                )));
+            }
+            //The binary chunk may be padded past the declared length
+            data.truncate(buffer.length());
+            buffers.insert(buffers.len(), gltf::buffer::Data(data));
+        }
+
+        let mut images = vec![];
+        for image in document.images() {
+            let handle = match image.source() {
+                gltf::image::Source::Uri { uri, .. } if !uri.starts_with("data:") => {
+                    ctx.load::<Sprite>(uri)
+                }
+                gltf::image::Source::Uri { uri, .. } => {
+                    let data = uri
+                        .split_once(";base64,")
+                        .and_then(|(_, data)| decode_base64(data))
+                        .ok_or_else(|| AssetError::Decode(format!("bad image uri in {}", ctx.path())))?;
+                    let spr = Sprite::load(&data, ctx)?;
+                    ctx.add_labeled(&format!("image{}", image.index()), spr)
+                }
+                gltf::image::Source::View { view, .. } => {
+                    let data = buffers
+                        .get(view.buffer().index())
+                        .and_then(|buffer| buffer.get(view.offset()..view.offset() + view.length()))
+                        .ok_or_else(|| {
+                            AssetError::Decode(format!("image {} of {} is out of bounds", image.index(), ctx.path()))
+                        })?;
+                    let spr = Sprite::load(data, ctx)?;
+                    ctx.add_labeled(&format!("image{}", image.index()), spr)
+                }
+            };
+            images.insert(images.len(), handle);
+        }
+
+        let children: Vec<usize> = document
+            .nodes()
+            .flat_map(|node| node.children().map(|c| c.index()).collect::<Vec<usize>>())
+            .collect();
+        let objects = document
+            .nodes()
+            .filter(|node| !children.contains(&node.index()))
+            .map(|node| gltf_ext::process_node(node, &buffers, &[]))
+            .collect();
+        Ok(GltfScene { objects, images })
+    }
+
+    fn preload(bytes: &[u8]) -> Vec<String> {
+        match gltf::Gltf::from_slice(bytes) {
+            Ok(gltf) => gltf
+                .document
+                .buffers()
+                .filter_map(|buffer| match buffer.source() {
+                    gltf::buffer::Source::Uri(uri) if !uri.starts_with("data:") => Some(uri.to_string()),
+                    _ => None,
+                })
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
+}
+
+fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' | b'-' => Some(62),
+            b'/' | b'_' => Some(63),
+            _ => None,
+        }
+    };
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for c in data.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        acc = (acc << 6) | value(c)?;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
@@ -0,0 +1,154 @@
+use super::{
+    decal::{Decal, SmallD},
+    renderer::Renderer,
+    sprite::Sprite,
+    util::Vf2d,
+};
+use std::sync::Arc;
+
+pub const ATLAS_PAGE_SIZE: u32 = 2048;
+
+/// Packs rectangles into rows stacked top to bottom. Each row is as tall as
+/// the first rectangle placed in it (rounded up), rectangles are placed left
+/// to right.
+#[derive(Clone, Debug, Default)]
+pub struct ShelfPacker {
+    width: u32,
+    height: u32,
+    //(y, height, used width)
+    shelves: Vec<(u32, u32, u32)>,
+}
+
+impl ShelfPacker {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            shelves: vec![],
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn clear(&mut self) {
+        self.shelves.clear();
+    }
+
+    /// Top left corner of a free `w` x `h` rectangle, None when full.
+    pub fn pack(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        if w > self.width || h > self.height {
+            return None;
+        }
+        //Best fit: the shortest shelf that is tall enough and has room
+        let best = self
+            .shelves
+            .iter_mut()
+            .filter(|(_, shelf_h, used)| *shelf_h >= h && self.width - *used >= w)
+            .min_by_key(|(_, shelf_h, _)| *shelf_h);
+        if let Some((y, _, used)) = best {
+            let pos = (*used, *y);
+            *used += w;
+            return Some(pos);
+        }
+        let y = self.shelves.last().map(|(y, h, _)| y + h).unwrap_or(0);
+        //Round shelf heights up so rectangles of similar sizes can share them
+        let shelf_h = ((h + 3) & !3).min(self.height - y);
+        if shelf_h < h {
+            return None;
+        }
+        self.shelves.insert(self.shelves.len(), (y, shelf_h, w));
+        Some((0, y))
+    }
+}
+
+struct AtlasPage {
+    decal: Decal,
+    packer: ShelfPacker,
+}
+
+/// Sprites packed into shared textures, so decals made from them can be
+/// drawn together. Decals from `add` point into the atlas' pages, keep the
+/// atlas alive while they are used.
+pub struct TextureAtlas {
+    page_size: u32,
+    /// Empty pixels around every sprite.
+    pub padding: u32,
+    pages: Vec<AtlasPage>,
+}
+
+impl Default for TextureAtlas {
+    fn default() -> Self {
+        Self::new(ATLAS_PAGE_SIZE)
+    }
+}
+
+impl TextureAtlas {
+    pub fn new(page_size: u32) -> Self {
+        Self {
+            page_size,
+            padding: 1,
+            pages: vec![],
+        }
+    }
+
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The whole texture of a page.
+    pub fn page(&self, index: usize) -> Option<Arc<SmallD>> {
+        self.pages.get(index).map(|page| page.decal.get())
+    }
+
+    /// Copies `sprite` into a page and returns a decal for it. Sprites that
+    /// don't fit in a page get a texture of their own.
+    pub fn add(&mut self, renderer: &mut Renderer, sprite: &Sprite) -> Decal {
+        let (w, h) = (sprite.width + self.padding * 2, sprite.height + self.padding * 2);
+        if w > self.page_size || h > self.page_size {
+            return Decal::create(Some(sprite.clone()), renderer);
+        }
+        let found = self
+            .pages
+            .iter_mut()
+            .enumerate()
+            .find_map(|(i, page)| page.packer.pack(w, h).map(|pos| (i, pos)));
+        let (index, (x, y)) = match found {
+            Some(found) => found,
+            None => {
+                let mut page = self.new_page(renderer);
+                let pos = page.packer.pack(w, h).unwrap_or((0, 0));
+                self.pages.insert(self.pages.len(), page);
+                (self.pages.len() - 1, pos)
+            }
+        };
+        let (x, y) = (x + self.padding, y + self.padding);
+        let id = self.pages[index].decal.get().id;
+        renderer.update_texture_region(id as u32, x, y, sprite.width, sprite.height, &sprite.col_data);
+        let inv_size = 1.0 / self.page_size as f32;
+        Decal::shared(SmallD {
+            id,
+            sprite: sprite.clone(),
+            uv_scale: Vf2d::new(inv_size, inv_size),
+            uv_offset: Vf2d::new(x as f32 * inv_size, y as f32 * inv_size),
+        })
+    }
+
+    fn new_page(&self, renderer: &mut Renderer) -> AtlasPage {
+        let sprite = Sprite::new(self.page_size, self.page_size);
+        AtlasPage {
+            decal: Decal::create(Some(sprite), renderer),
+            packer: ShelfPacker::new(self.page_size, self.page_size),
+        }
+    }
+}
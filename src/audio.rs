@@ -1,431 +1,885 @@
-use crate::steam_audio::utils::interleave;
-use crate::steam_audio_bindgen::{IPLAmbisonicsBinauralEffectParams, IPLAmbisonicsBinauralEffectSettings, IPLAmbisonicsDecodeEffectSettings, IPLAmbisonicsEncodeEffectParams, IPLAmbisonicsEncodeEffectSettings, IPLAudioEffectState, IPLDirectEffectFlags, IPLDirectEffectParams, IPLDirectEffectSettings, IPLPanningEffectParams, IPLPanningEffectSettings, IPLSpeakerLayout};
-
-use super::math_3d::*;
-use super::steam_audio::*;
-use super::transform::Transform3;
-use itertools::Itertools;
-use rodio::dynamic_mixer::{DynamicMixer, DynamicMixerController};
-use rodio::{buffer::SamplesBuffer, Sink, Source};
-use std::collections::{HashMap, VecDeque};
+use super::{
+    audio_bus::{Biquad, BusGraph, SFX_BUS},
+    audio_decode::{self, SoundStream},
+    audio_occlusion::{Occlusion, OcclusionGeometry, OcclusionSettings},
+    camera::Camera,
+    math_3d::Vector3,
+    og_engine::Rcode,
+    transform::Transform3,
+};
+#[cfg(feature = "steam-audio")]
+use super::steam_audio::{SpatialMode, SteamAudioSpatializer};
+use std::collections::HashMap;
 use std::ops::Range;
+use std::sync::Arc;
 use std::time::Duration;
-use std::vec::IntoIter as VecIntoIter;
 
-pub const MAXSINKS: usize = 32;
+/// Frames mixed per block.
+pub const FRAME_SIZE: usize = 1024;
+/// Blocks kept queued on a device output.
 pub const FRAMES_TO_BUFFER: usize = 2;
-pub const FRAME_SIZE: i32 = 1024;
+/// Mixer rate for outputs that don't have one of their own.
 pub const SAMPLE_RATE: u32 = 44100;
+/// Meters per second, for Doppler.
+pub const SPEED_OF_SOUND: f32 = 343.0;
+/// Low-pass cutoff of an emitter nothing is in front of.
+pub const OPEN_CUTOFF: f32 = 20000.0;
 
-pub struct AudioSystem {
-    pub audio_processor: AudioProcessor,
-    stream: rodio::OutputStream,
-    stream_handle: rodio::OutputStreamHandle,
-    emitters: HashMap<u32, Emitter>,
-    listeners: HashMap<u32, Listener>,
-    library: SoundLibrary,
-    sink: Sink,
-}
-
-pub struct Emitter {
-    pub transform: Transform3,
-    sounds: Vec<Sound>,
-    sound_playback: Option<SoundPlayback>,
-    sink: Sink,
-    ambisonics_encode: AmbisonicsEncode,
-    ambisonics_decode: AmbisonicsDecode,
-    ambisonics_binaural: AmbisonicsBinaural,
-    panning: Panning,
-    direct: Direct,
-}
-
-/*
-Effect Stack:
-  Ambisonics Rotation
-  Ambisonics Binaural
-  Path
-*/
-pub struct Listener {
-    pub transform: Transform3,
-    frame_count: usize,
-    audio_queue: VecDeque<SoundFrame>,
-    mono_buffer: Buffer,
-    stereo_buffer: Buffer,
-    ambisonics2_buffer: Buffer,
-    ambisonics1_buffer: Buffer,
-}
+pub type EmitterId = u32;
+pub type ListenerId = u32;
 
-#[derive(Clone)]
+/// Decoded audio, shared between every emitter that plays it.
+#[derive(Clone, Debug)]
 pub struct Sound {
-    frames: VecIntoIter<SoundFrame>,
+    samples: Arc<[f32]>,
     channels: u16,
     sample_rate: u32,
-    duration: Duration,
 }
 
-#[derive(Debug, Clone)]
-pub struct SoundFrame {
-    data: VecIntoIter<f32>,
-    duration: Duration,
-    channels: u16,
-    sample_rate: u32,
-    size: usize,
+impl Sound {
+    /// `data` is interleaved when `channels` is more than 1.
+    pub fn new(data: Vec<f32>, channels: u16, sample_rate: u32) -> Self {
+        assert!(channels > 0, "Sound without channels");
+        Self {
+            samples: data.into(),
+            channels,
+            sample_rate,
+        }
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// Length in frames, one sample per channel.
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames() as f64 / self.sample_rate as f64)
+    }
+
+    /// A copy converted to `sample_rate`, so voices don't have to convert it
+    /// while mixing.
+    pub fn resampled(&self, sample_rate: u32) -> Sound {
+        if sample_rate == self.sample_rate {
+            return self.clone();
+        }
+        let samples = audio_decode::resample(&self.samples, self.channels, self.sample_rate, sample_rate);
+        Sound::new(samples, self.channels, sample_rate)
+    }
+
+    //Left and right at frame `index`, mono is copied to both
+    fn frame(&self, index: usize) -> (f32, f32) {
+        let channels = self.channels as usize;
+        let start = index * channels;
+        match self.samples.get(start..start + channels) {
+            Some([mono]) => (*mono, *mono),
+            Some([l, r, ..]) => (*l, *r),
+            _ => (0.0, 0.0),
+        }
+    }
+
+    //Linear interpolation between the frames around `pos`
+    fn frame_at(&self, pos: f64) -> (f32, f32) {
+        let index = pos.floor().max(0.0) as usize;
+        let t = (pos - index as f64) as f32;
+        let (l0, r0) = self.frame(index);
+        if t <= 0.0 {
+            return (l0, r0);
+        }
+        let (l1, r1) = self.frame((index + 1).min(self.frames().saturating_sub(1)));
+        (l0 + (l1 - l0) * t, r0 + (r1 - r0) * t)
+    }
 }
 
 #[derive(Default)]
 pub struct SoundLibrary {
-    sounds: HashMap<&'static str, Sound>,
+    sounds: HashMap<String, Sound>,
 }
 
+impl SoundLibrary {
+    pub fn create_library() -> Self {
+        Self::default()
+    }
+
+    /// Loads raw 32 bit float mono samples at `SAMPLE_RATE`, named after the
+    /// file.
+    pub fn with_raw_sound_file(mut self, path: &str) -> Self {
+        if let Ok(input_audio) = std::fs::read(path) {
+            let samples = input_audio
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            let name = std::path::Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string());
+            self.sounds.insert(name, Sound::new(samples, 1, SAMPLE_RATE));
+        }
+        self
+    }
+
+    /// Decodes a WAV, OGG Vorbis, FLAC or MP3 file and converts it to
+    /// `SAMPLE_RATE`, named after the file.
+    pub fn with_sound_file(mut self, path: &str) -> Self {
+        match audio_decode::decode_file(path) {
+            Ok(sound) => {
+                let name = std::path::Path::new(path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.to_string());
+                self.sounds.insert(name, sound.resampled(SAMPLE_RATE));
+            }
+            Err(_) => log::error!("Couldn't load sound {}", path),
+        }
+        self
+    }
+
+    /// Like `with_sound_file` for encoded bytes, e.g. from `include_bytes!`.
+    pub fn with_sound_bytes(mut self, name: &str, bytes: &[u8], extension: Option<&str>) -> Self {
+        match audio_decode::decode_bytes(bytes, extension) {
+            Ok(sound) => self.add_sound(name, sound.resampled(SAMPLE_RATE)),
+            Err(_) => log::error!("Couldn't decode sound {}", name),
+        }
+        self
+    }
+
+    pub fn with_sound(mut self, name: &str, sound: Sound) -> Self {
+        self.add_sound(name, sound);
+        self
+    }
+
+    pub fn add_sound(&mut self, name: &str, sound: Sound) {
+        self.sounds.insert(name.to_string(), sound);
+    }
+
+    pub fn get_sound(&self, name: &str) -> Option<&Sound> {
+        self.sounds.get(name)
+    }
+}
+
+/// How a sound plays once started.
+#[derive(Clone, Debug, PartialEq)]
 pub enum SoundPlayback {
     Once,
     Loop,
+    /// Plays forwards, then backwards, then forwards again. Not for streams.
     PingPong,
+    /// Plays the part between two times in seconds once.
     Partial(Range<f32>),
 }
 
-impl Sound {
-    #[inline]
-    pub fn new(mut data: Vec<f32>, channels: u16, sample_rate: u32) -> Self {
-        assert!(!data.is_empty(), "Empty Data Vector");
-
-        let duration_ms = 1_000u64
-            .checked_mul(data.len() as u64 * FRAME_SIZE as u64)
-            .unwrap()
-            / sample_rate as u64
-            / channels as u64;
-
-        let duration = Duration::from_millis(duration_ms);
-
-        let duration_ns =
-            (1_000_000_000u64 * FRAME_SIZE as u64) / sample_rate as u64 / channels as u64;
-        let frame_duration = Duration::new(
-            duration_ns / 1_000_000_000,
-            (duration_ns % 1_000_000_000) as u32,
-        );
-        let frames: VecIntoIter<SoundFrame> = data
-            .chunks(FRAME_SIZE.max(1) as usize)
-            .map(|c| {
-                let mut pad: Vec<f32> = vec![0.0; FRAME_SIZE as usize - c.len()];
-                let mut v = c.to_vec();
-                v.append(&mut pad);
-                SoundFrame {
-                    size: v.len(),
-                    data: v.into_iter(),
-                    channels,
-                    sample_rate,
-                    duration: frame_duration,
+impl Default for SoundPlayback {
+    fn default() -> Self {
+        SoundPlayback::Once
+    }
+}
+
+//What a voice reads frames from
+enum VoiceSource {
+    Buffer(Sound),
+    Stream(Box<SoundStream>),
+}
+
+/// One playing sound.
+pub struct Voice {
+    source: VoiceSource,
+    playback: SoundPlayback,
+    pub gain: f32,
+    pos: f64,
+    forward: bool,
+    finished: bool,
+}
+
+impl Voice {
+    pub fn new(sound: Sound, playback: SoundPlayback) -> Self {
+        Self::with_source(VoiceSource::Buffer(sound), playback)
+    }
+
+    /// Decodes while it plays. Streams can't play backwards, so `PingPong`
+    /// fails.
+    pub fn streamed(stream: SoundStream, playback: SoundPlayback) -> Result<Self, Rcode> {
+        if let SoundPlayback::PingPong = playback {
+            log::error!("PingPong playback isn't supported for streamed sounds");
+            return Err(Rcode::Fail);
+        }
+        let mut voice = Self::with_source(VoiceSource::Stream(Box::new(stream)), playback);
+        voice.restart();
+        Ok(voice)
+    }
+
+    fn with_source(source: VoiceSource, playback: SoundPlayback) -> Self {
+        let mut voice = Self {
+            source,
+            playback,
+            gain: 1.0,
+            pos: 0.0,
+            forward: true,
+            finished: false,
+        };
+        voice.pos = voice.bounds().0;
+        voice.finished = voice.bounds().0 >= voice.bounds().1;
+        voice
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Playback position in seconds.
+    pub fn position(&self) -> f64 {
+        self.pos / self.sample_rate() as f64
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        match &self.source {
+            VoiceSource::Buffer(sound) => sound.sample_rate,
+            VoiceSource::Stream(stream) => stream.sample_rate(),
+        }
+    }
+
+    //First and one past the last frame that plays
+    fn bounds(&self) -> (f64, f64) {
+        let frames = match &self.source {
+            VoiceSource::Buffer(sound) => sound.frames() as f64,
+            //Not every format knows its length, the stream ending stops it then
+            VoiceSource::Stream(stream) => stream.frames().map_or(f64::INFINITY, |f| f as f64),
+        };
+        match &self.playback {
+            SoundPlayback::Partial(range) => {
+                let rate = self.sample_rate() as f64;
+                let start = (range.start.max(0.0) as f64 * rate).min(frames);
+                let end = (range.end.max(0.0) as f64 * rate).min(frames);
+                (start, end.max(start))
+            }
+            _ => (0.0, frames),
+        }
+    }
+
+    //Back to the first frame, seeking a stream
+    fn restart(&mut self) {
+        let start = self.bounds().0;
+        self.pos = start;
+        self.forward = true;
+        if let VoiceSource::Stream(stream) = &mut self.source {
+            if stream.seek(start as u64).is_err() {
+                self.finished = true;
+            }
+        }
+    }
+
+    /// Next frame, moving `step` source frames on.
+    fn next_frame(&mut self, step: f64) -> (f32, f32) {
+        if self.finished {
+            return (0.0, 0.0);
+        }
+        let frame = match &mut self.source {
+            VoiceSource::Buffer(sound) => Some(sound.frame_at(self.pos)),
+            VoiceSource::Stream(stream) => stream.frame_at(self.pos),
+        };
+        match frame {
+            Some((l, r)) => {
+                self.advance(step);
+                (l * self.gain, r * self.gain)
+            }
+            //The stream ended before the length it reported
+            None => {
+                match self.playback {
+                    SoundPlayback::Loop => self.restart(),
+                    _ => self.finished = true,
                 }
-            })
-            .collect::<Vec<SoundFrame>>()
-            .into_iter();
+                (0.0, 0.0)
+            }
+        }
+    }
+
+    fn advance(&mut self, step: f64) {
+        let (start, end) = self.bounds();
+        let length = end - start;
+        if let VoiceSource::Stream(_) = self.source {
+            self.pos += step;
+            match self.playback {
+                SoundPlayback::Loop if self.pos >= end => self.restart(),
+                SoundPlayback::Once | SoundPlayback::Partial(_) => self.finished = self.pos >= end,
+                _ => {}
+            }
+            return;
+        }
+        match self.playback {
+            SoundPlayback::Once | SoundPlayback::Partial(_) => {
+                self.pos += step;
+                self.finished = self.pos >= end;
+            }
+            SoundPlayback::Loop => {
+                self.pos += step;
+                if self.pos >= end {
+                    self.pos = start + (self.pos - start) % length;
+                }
+            }
+            SoundPlayback::PingPong => {
+                //Turn on the last frame so it isn't interpolated past the end
+                let last = (end - 1.0).max(start);
+                if last <= start {
+                    return;
+                }
+                if self.forward {
+                    self.pos += step;
+                    if self.pos >= last {
+                        self.pos = (2.0 * last - self.pos).max(start);
+                        self.forward = false;
+                    }
+                } else {
+                    self.pos -= step;
+                    if self.pos <= start {
+                        self.pos = (2.0 * start - self.pos).min(last);
+                        self.forward = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How gain falls off with distance, with the same formulas as OpenAL.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DistanceModel {
+    /// No attenuation.
+    None,
+    /// `min / (min + rolloff * (d - min))`
+    Inverse,
+    /// `1 - rolloff * (d - min) / (max - min)`
+    Linear,
+    /// `(d / min) ^ -rolloff`
+    Exponential,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attenuation {
+    pub model: DistanceModel,
+    /// Full volume up to this distance.
+    pub min_distance: f32,
+    /// No further attenuation past this distance.
+    pub max_distance: f32,
+    pub rolloff: f32,
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
         Self {
-            frames,
-            channels,
-            sample_rate,
-            duration,
+            model: DistanceModel::Inverse,
+            min_distance: 1.0,
+            max_distance: 100.0,
+            rolloff: 1.0,
         }
     }
 }
 
+impl Attenuation {
+    pub fn gain(&self, distance: f32) -> f32 {
+        let min = self.min_distance.max(f32::EPSILON);
+        let max = self.max_distance.max(min);
+        let d = distance.clamp(min, max);
+        let gain = match self.model {
+            DistanceModel::None => 1.0,
+            DistanceModel::Inverse => min / (min + self.rolloff * (d - min)),
+            DistanceModel::Linear if max > min => 1.0 - self.rolloff * (d - min) / (max - min),
+            DistanceModel::Linear => 1.0,
+            DistanceModel::Exponential => (d / min).powf(-self.rolloff),
+        };
+        gain.clamp(0.0, 1.0)
+    }
+}
+
+/// A source of sound in the world.
+pub struct Emitter {
+    pub transform: Transform3,
+    pub gain: f32,
+    pub pitch: f32,
+    /// Positional sounds are attenuated, panned and Doppler shifted. Turn it
+    /// off for music and interface sounds.
+    pub spatial: bool,
+    pub attenuation: Attenuation,
+    /// Scales the Doppler shift, 0 turns it off.
+    pub doppler_factor: f32,
+    pub paused: bool,
+    /// Bus the emitter plays into, `SFX_BUS` unless set.
+    pub bus: String,
+    /// Muffled by geometry between it and the listener, if spatial.
+    pub occludable: bool,
+    voices: Vec<Voice>,
+    sound_playback: Option<SoundPlayback>,
+    //Started by play_sound, removed when done
+    one_shot: bool,
+    velocity: Vector3,
+    last_position: Option<Vector3>,
+    occlusion: Occlusion,
+    occlusion_target: Occlusion,
+    low_pass: Biquad,
+}
+
 impl Emitter {
-    pub fn new(
-        transform: Transform3,
-        sound_playback: Option<SoundPlayback>,
-        audio_system: &mut AudioSystem,
-    ) -> Self {
-        let audio_processor = &mut audio_system.audio_processor;
-        let ambisonics_encode = AmbisonicsEncode::create(
-            audio_processor,
-            IPLAmbisonicsEncodeEffectSettings { maxOrder: 2 },
-        );
-        let ambisonics_decode = AmbisonicsDecode::create(audio_processor, IPLAmbisonicsDecodeEffectSettings{
-            maxOrder: 2,
-            speakerLayout: IPLSpeakerLayout {
-                type_: crate::steam_audio_bindgen::IPLSpeakerLayoutType::IPL_SPEAKERLAYOUTTYPE_STEREO,
-                numSpeakers: 2,
-                speakers: std::ptr::null_mut()
-            },
-            hrtf: audio_processor.hrtf(),
-        });
-        let ambisonics_binaural = AmbisonicsBinaural::create(
-            audio_processor,
-            IPLAmbisonicsBinauralEffectSettings {
-                hrtf: audio_processor.hrtf(),
-                maxOrder: 2,
-            },
-        );
-        let panning = Panning::create(
-            audio_processor,
-            IPLPanningEffectSettings {
-                speakerLayout: IPLSpeakerLayout {
-                    type_: crate::steam_audio_bindgen::IPLSpeakerLayoutType::IPL_SPEAKERLAYOUTTYPE_STEREO,
-                    numSpeakers: 2,
-                    speakers: std::ptr::null_mut()
-                },
-            },
-        );
-        let direct = Direct::create(audio_processor, IPLDirectEffectSettings { numChannels: 9 });
-        let sink = Sink::try_new(&audio_system.stream_handle).expect("Failed to create sink");
+    /// `sound_playback` is used for sounds started with `play`, `Once` if
+    /// `None`.
+    pub fn new(transform: Transform3, sound_playback: Option<SoundPlayback>) -> Self {
         Self {
             transform,
-            sounds: vec![],
-            sink,
+            gain: 1.0,
+            pitch: 1.0,
+            spatial: true,
+            attenuation: Attenuation::default(),
+            doppler_factor: 1.0,
+            paused: false,
+            bus: SFX_BUS.to_string(),
+            occludable: true,
+            voices: vec![],
             sound_playback,
-            ambisonics_encode,
-            ambisonics_decode,
-            ambisonics_binaural,
-            panning,
-            direct,
+            one_shot: false,
+            velocity: Vector3::default(),
+            last_position: None,
+            occlusion: Occlusion::default(),
+            occlusion_target: Occlusion::default(),
+            low_pass: Biquad::low_pass(OPEN_CUTOFF),
         }
     }
 
-    pub fn with_sound(mut self, sound_name: &'static str, library: &SoundLibrary) -> Self {
+    /// Not attenuated or panned, for music.
+    pub fn ambient(sound_playback: Option<SoundPlayback>) -> Self {
+        Self {
+            spatial: false,
+            ..Self::new(Transform3::default(), sound_playback)
+        }
+    }
+
+    pub fn with_bus(mut self, bus: &str) -> Self {
+        self.bus = bus.to_string();
+        self
+    }
+
+    pub fn with_sound(mut self, sound_name: &str, library: &SoundLibrary) -> Self {
         if let Some(sound) = library.get_sound(sound_name) {
-            self.sounds.insert(self.sounds.len(), sound.clone());
+            self.play(sound);
         }
         self
     }
 
-    #[inline]
-    pub fn get_frames(&mut self) -> Option<Vec<SoundFrame>> {
-        let frames: Vec<SoundFrame> = self
-            .sounds
-            .iter_mut()
-            .filter_map(|s| s.frames.next())
-            .collect();
+    pub fn play(&mut self, sound: &Sound) {
+        let playback = self.sound_playback.clone().unwrap_or_default();
+        self.play_with(sound, playback);
+    }
 
-        if frames.is_empty() {
-            None
-        } else {
-            Some(frames)
-        }
+    pub fn play_with(&mut self, sound: &Sound, playback: SoundPlayback) {
+        self.voices.insert(self.voices.len(), Voice::new(sound.clone(), playback));
     }
 
-    pub fn pause(&self) {
-        self.sink.pause();
+    /// Plays a sound decoded while it plays, for music.
+    pub fn play_stream(&mut self, stream: SoundStream, playback: SoundPlayback) -> Result<(), Rcode> {
+        let voice = Voice::streamed(stream, playback)?;
+        self.voices.insert(self.voices.len(), voice);
+        Ok(())
     }
 
-    #[inline]
-    fn play_frame(&mut self, frame: SoundFrame) {
-        self.sink.set_volume(0.2);
-        self.sink.append(frame);
+    pub fn stop(&mut self) {
+        self.voices.clear();
     }
-}
 
-impl Iterator for Sound {
-    type Item = f32;
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
 
-    #[inline]
-    fn next(&mut self) -> Option<f32> {
-        (&mut self.frames)
-            .peekable()
-            .peek_mut()
-            .and_then(|mut sf| sf.data.next())
+    pub fn resume(&mut self) {
+        self.paused = false;
     }
 
-    #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.frames.size_hint()
+    pub fn is_playing(&self) -> bool {
+        !self.paused && !self.voices.is_empty()
     }
-}
 
-impl Iterator for SoundFrame {
-    type Item = f32;
+    pub fn voices(&self) -> &[Voice] {
+        &self.voices
+    }
 
-    #[inline]
-    fn next(&mut self) -> Option<f32> {
-        self.data.next()
+    /// Meters per second, measured from how the transform moved.
+    pub fn velocity(&self) -> Vector3 {
+        self.velocity
     }
 
-    #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.data.size_hint()
+    /// As last measured by `AudioSystem::update_occlusion`, smoothed.
+    pub fn occlusion(&self) -> Occlusion {
+        self.occlusion
     }
 }
 
-impl Source for SoundFrame {
-    fn current_frame_len(&self) -> Option<usize> {
-        None
+/// Where sound is heard from, usually following the camera.
+pub struct Listener {
+    pub transform: Transform3,
+    pub gain: f32,
+    /// Copy the camera transform every update.
+    pub follow_camera: bool,
+    velocity: Vector3,
+    last_position: Option<Vector3>,
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Self::new(Transform3::default())
     }
+}
 
-    fn channels(&self) -> u16 {
-        self.channels
+impl Listener {
+    pub fn new(transform: Transform3) -> Self {
+        Self {
+            transform,
+            gain: 1.0,
+            follow_camera: true,
+            velocity: Vector3::default(),
+            last_position: None,
+        }
     }
 
-    fn sample_rate(&self) -> u32 {
-        self.sample_rate
+    pub fn follow(&mut self, camera: &Camera) {
+        self.transform.pos = camera.transform.pos;
+        self.transform.rot = camera.transform.rot;
     }
 
-    fn total_duration(&self) -> Option<std::time::Duration> {
-        Some(self.duration)
+    pub fn velocity(&self) -> Vector3 {
+        self.velocity
+    }
+
+    /// `pos` in the listener's frame: x right, y up, z forward.
+    pub fn to_local(&self, pos: Vector3) -> Vector3 {
+        let d = pos - self.transform.pos;
+        let rot = self.transform.rot;
+        Vector3::new(d.dot(rot.right()), d.dot(rot.up()), d.dot(rot.forward()))
     }
 }
 
-impl Source for Sound {
-    fn current_frame_len(&self) -> Option<usize> {
-        None
+/// Where an emitter is relative to the listener for one mix block.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpatialParams {
+    /// Unit vector in the listener's frame, x right, y up, z forward.
+    pub direction: Vector3,
+    pub distance: f32,
+    /// Distance attenuation, already including the emitter gain.
+    pub gain: f32,
+    /// -1 fully left to 1 fully right.
+    pub pan: f32,
+}
+
+/// Turns a mono emitter signal into stereo. `StereoPanner` is the default,
+/// HRTF backends can replace it with `AudioSystem::set_spatializer`.
+pub trait Spatializer {
+    /// Adds `input` rendered at `params` to the interleaved stereo `output`,
+    /// which is twice as long.
+    fn spatialize(&mut self, emitter: EmitterId, params: &SpatialParams, input: &[f32], output: &mut [f32]);
+    /// `emitter` was removed, drop anything kept for it.
+    fn remove_emitter(&mut self, _emitter: EmitterId) {}
+}
+
+/// Equal power panning.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StereoPanner;
+
+impl StereoPanner {
+    pub fn gains(pan: f32) -> (f32, f32) {
+        let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+        (angle.cos(), angle.sin())
     }
+}
 
-    fn channels(&self) -> u16 {
-        self.channels
+impl Spatializer for StereoPanner {
+    fn spatialize(&mut self, _emitter: EmitterId, params: &SpatialParams, input: &[f32], output: &mut [f32]) {
+        let (l, r) = Self::gains(params.pan);
+        for (i, sample) in input.iter().enumerate() {
+            output[i * 2] += sample * l * params.gain;
+            output[i * 2 + 1] += sample * r * params.gain;
+        }
+    }
+}
+
+/// Where mixed audio goes. Samples are interleaved stereo.
+pub trait AudioOutput {
+    fn sample_rate(&self) -> u32;
+    /// Frames the output can take now, `elapsed` seconds after the last call.
+    fn frames_needed(&mut self, elapsed: f64) -> usize;
+    fn write(&mut self, samples: &[f32]);
+}
+
+/// Discards audio at the rate a device would play it, for headless runs.
+pub struct NullOutput {
+    sample_rate: u32,
+    pending: f64,
+    /// Frames written so far.
+    pub written: usize,
+}
+
+impl NullOutput {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            pending: 0.0,
+            written: 0,
+        }
     }
+}
 
+impl Default for NullOutput {
+    fn default() -> Self {
+        Self::new(SAMPLE_RATE)
+    }
+}
+
+impl AudioOutput for NullOutput {
     fn sample_rate(&self) -> u32 {
         self.sample_rate
     }
 
-    fn total_duration(&self) -> Option<std::time::Duration> {
-        Some(self.duration)
+    fn frames_needed(&mut self, elapsed: f64) -> usize {
+        self.pending += elapsed * self.sample_rate as f64;
+        let frames = self.pending.floor();
+        self.pending -= frames;
+        frames as usize
+    }
+
+    fn write(&mut self, samples: &[f32]) {
+        self.written += samples.len() / 2;
     }
 }
 
-impl SoundLibrary {
-    pub fn create_library() -> Self {
+/// Collects audio and saves it as a 32 bit float WAV file when finished or
+/// dropped, for checking mixes in tests.
+pub struct FileOutput {
+    path: String,
+    sample_rate: u32,
+    pending: f64,
+    samples: Vec<f32>,
+    saved: bool,
+}
+
+impl FileOutput {
+    pub fn new(path: &str, sample_rate: u32) -> Self {
         Self {
-            sounds: HashMap::new(),
+            path: path.to_string(),
+            sample_rate,
+            pending: 0.0,
+            samples: vec![],
+            saved: false,
         }
     }
 
-    pub fn with_raw_sound_file(mut self, name: &'static str) -> Self {
-        if let Ok(input_audio) = std::fs::read(name) {
-            let sound = Sound::new(
-                bytemuck::cast_slice(input_audio.as_slice()).to_vec(),
-                1,
-                44100,
-            );
-            self.sounds.insert(name.split('\\').last().unwrap(), sound);
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    pub fn finish(&mut self) -> Result<(), Rcode> {
+        self.saved = true;
+        std::fs::write(&self.path, wav_bytes(&self.samples, 2, self.sample_rate)).map_err(|_| Rcode::Fail)
+    }
+}
+
+impl Drop for FileOutput {
+    fn drop(&mut self) {
+        if !self.saved {
+            let _ = self.finish();
         }
-        self
     }
+}
 
-    pub fn with_sound(mut self, name: &'static str, sound: Sound) -> Self {
-        self.sounds.insert(name, sound);
-        self
+impl AudioOutput for FileOutput {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
     }
 
-    pub fn get_sound(&self, name: &'static str) -> Option<&Sound> {
-        self.sounds.get(name)
+    fn frames_needed(&mut self, elapsed: f64) -> usize {
+        self.pending += elapsed * self.sample_rate as f64;
+        let frames = self.pending.floor();
+        self.pending -= frames;
+        frames as usize
+    }
+
+    fn write(&mut self, samples: &[f32]) {
+        self.samples.extend_from_slice(samples);
     }
 }
 
-impl Listener {
-    pub fn new(transform: Transform3, audio_processor: &mut AudioProcessor) -> Self {
-        let mut mono_buffer = Buffer::empty(audio_processor, 1);
-        let mut ambisonics1_buffer = Buffer::empty(audio_processor, 9);
-        let mut ambisonics2_buffer = Buffer::empty(audio_processor, 9);
-        let mut stereo_buffer = Buffer::empty(audio_processor, 2);
-        Self {
-            transform,
-            frame_count: 0,
-            audio_queue: VecDeque::new(),
-            mono_buffer,
-            stereo_buffer,
-            ambisonics1_buffer,
-            ambisonics2_buffer,
-        }
-    }
-    #[inline]
-    pub fn get_frames(&mut self) -> Vec<SoundFrame> {
-        //get all the sounds for the current frame
-        let frame_sounds = self
-            .audio_queue
-            .drain(0..self.frame_count)
-            .collect::<Vec<SoundFrame>>();
-        //reset the counter
-        self.frame_count = 0;
-
-        frame_sounds
-    }
-
-    #[inline]
-    fn add_frames(&mut self, frames: Vec<SoundFrame>) {
-        self.frame_count += frames.len();
-        self.audio_queue.append(&mut frames.into_iter().collect());
-    }
-
-    #[inline]
-    pub fn apply_convolutions(
-        &mut self,
-        mut frame: SoundFrame,
-        emitter: &Emitter,
-        audio_processor: &mut AudioProcessor,
-    ) -> SoundFrame {
-        let emitter_position = self.transform.pos - emitter.transform.pos;
-        let emitter_position2 = emitter.transform.pos - self.transform.pos;
-        let rotation =
-            Rotor3::from_vectors(self.transform.rot.forward(), emitter_position2.normal());
-        let distance = 1.0 / emitter_position.length();
-        //apply the effects and return the new frame
-        let mut output: Vec<f32> = Vec::with_capacity(2 * frame.size as usize);
-        let mut mono_buffer = Buffer::new_with_data(audio_processor, 1, frame.data.collect());
-        // emitter.panning.apply(
-        //     IPLPanningEffectParams{ direction: emitter_position2.normal().into() },
-        //     &mut mono_buffer,
-        //     &mut self.stereo_buffer,
-        // );
-
-        emitter.ambisonics_encode.apply(
-            IPLAmbisonicsEncodeEffectParams {
-                direction: rotation.forward().normal().into(),
-                order: 2,
-            },
-            &mut mono_buffer,
-            &mut self.ambisonics1_buffer,
-        );
-
-        emitter.direct.apply(
-            IPLDirectEffectParams {
-                flags: IPLDirectEffectFlags::IPL_DIRECTEFFECTFLAGS_APPLYAIRABSORPTION|
-                IPLDirectEffectFlags::IPL_DIRECTEFFECTFLAGS_APPLYDIRECTIVITY|
-                IPLDirectEffectFlags::IPL_DIRECTEFFECTFLAGS_APPLYDISTANCEATTENUATION|
-                IPLDirectEffectFlags::IPL_DIRECTEFFECTFLAGS_APPLYTRANSMISSION,
-                transmissionType: crate::steam_audio_bindgen::IPLTransmissionType::IPL_TRANSMISSIONTYPE_FREQDEPENDENT,
-                distanceAttenuation: distance,
-                airAbsorption: [distance * 3.0, distance * 2.0, distance * 2.5],
-                directivity: 1.0,
-                occlusion: 0.0,
-                transmission: [0.3, 0.2, 0.1],
-            },
-            &mut self.ambisonics1_buffer,
-            &mut self.ambisonics2_buffer,
-        );
-
-        emitter.ambisonics_binaural.apply(
-            IPLAmbisonicsBinauralEffectParams {
-                hrtf: audio_processor.hrtf(),
-                order: 2,
-            },
-            &mut self.ambisonics2_buffer,
-            &mut self.stereo_buffer,
-        );
-        let o = interleave(audio_processor, output, &mut self.stereo_buffer);
-        frame.data = o.into_iter();
-        frame.channels = 2;
-        frame
+/// A 32 bit float WAV file.
+pub fn wav_bytes(samples: &[f32], channels: u16, sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 4) as u32;
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    //IEEE float
+    out.extend_from_slice(&3u16.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * channels as u32 * 4).to_le_bytes());
+    out.extend_from_slice(&(channels * 4).to_le_bytes());
+    out.extend_from_slice(&32u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        out.extend_from_slice(&sample.to_le_bytes());
     }
+    out
+}
 
+/// Plays on the default sound device.
+#[cfg(feature = "cpal")]
+pub struct CpalOutput {
+    //Audio stops when the stream is dropped
+    _stream: cpal::Stream,
+    queue: Arc<std::sync::Mutex<std::collections::VecDeque<f32>>>,
+    sample_rate: u32,
+}
+
+#[cfg(feature = "cpal")]
+impl CpalOutput {
+    pub fn new() -> Result<Self, Rcode> {
+        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+        let device = cpal::default_host().default_output_device().ok_or_else(|| {
+            log::warn!("No audio output device");
+            Rcode::Fail
+        })?;
+        let config = device.default_output_config().map_err(|e| {
+            log::warn!("Audio output unavailable: {}", e);
+            Rcode::Fail
+        })?;
+        let queue = Arc::new(std::sync::Mutex::new(std::collections::VecDeque::new()));
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::I16 => Self::build_stream::<i16>(&device, &config.config(), queue.clone()),
+            cpal::SampleFormat::U16 => Self::build_stream::<u16>(&device, &config.config(), queue.clone()),
+            _ => Self::build_stream::<f32>(&device, &config.config(), queue.clone()),
+        }?;
+        stream.play().map_err(|_| Rcode::Fail)?;
+        Ok(Self {
+            _stream: stream,
+            queue,
+            sample_rate: config.sample_rate().0,
+        })
+    }
+
+    fn build_stream<T: cpal::SizedSample + cpal::FromSample<f32>>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        queue: Arc<std::sync::Mutex<std::collections::VecDeque<f32>>>,
+    ) -> Result<cpal::Stream, Rcode> {
+        use cpal::traits::DeviceTrait;
+        let channels = config.channels as usize;
+        device
+            .build_output_stream(
+                config,
+                move |data: &mut [T], _| {
+                    let mut queue = queue.lock().unwrap();
+                    for frame in data.chunks_mut(channels) {
+                        let l = queue.pop_front().unwrap_or(0.0);
+                        let r = queue.pop_front().unwrap_or(0.0);
+                        for (i, sample) in frame.iter_mut().enumerate() {
+                            let value = match i {
+                                0 => l,
+                                1 => r,
+                                _ => 0.0,
+                            };
+                            *sample = T::from_sample(value);
+                        }
+                    }
+                },
+                |e| log::error!("Audio stream error: {}", e),
+                None,
+            )
+            .map_err(|e| {
+                log::warn!("Audio output unavailable: {}", e);
+                Rcode::Fail
+            })
+    }
+}
+
+#[cfg(feature = "cpal")]
+impl AudioOutput for CpalOutput {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn frames_needed(&mut self, _elapsed: f64) -> usize {
+        let queued = self.queue.lock().unwrap().len() / 2;
+        (FRAME_SIZE * FRAMES_TO_BUFFER).saturating_sub(queued)
+    }
+
+    fn write(&mut self, samples: &[f32]) {
+        self.queue.lock().unwrap().extend(samples.iter().copied());
+    }
+}
+
+/// Mixes every emitter as heard by the active listener and feeds the result
+/// to an `AudioOutput`. Call `update` once per frame.
+pub struct AudioSystem {
+    pub master_gain: f32,
+    /// Listener the mix is rendered for, the first one registered.
+    pub active_listener: Option<ListenerId>,
+    pub buses: BusGraph,
+    pub occlusion: OcclusionSettings,
+    occlusion_timer: f64,
+    output: Box<dyn AudioOutput>,
+    spatializer: Box<dyn Spatializer>,
+    emitters: HashMap<EmitterId, Emitter>,
+    listeners: HashMap<ListenerId, Listener>,
+    library: SoundLibrary,
+    next_id: u32,
+    mono: Vec<f32>,
 }
 
 impl AudioSystem {
-    pub fn create_system() -> Self {
-        let (stream, stream_handle) =
-            rodio::OutputStream::try_default().expect("No handle to output stream");
-        let audio_processor = AudioProcessor::new(44100, FRAME_SIZE);
-        let sink = Sink::try_new(&stream_handle).expect("Failed to create sink");
+    pub fn new(output: Box<dyn AudioOutput>) -> Self {
         Self {
-            stream,
-            stream_handle,
-            listeners: HashMap::new(),
+            master_gain: 1.0,
+            active_listener: None,
+            buses: BusGraph::default(),
+            occlusion: OcclusionSettings::default(),
+            occlusion_timer: 0.0,
+            output,
+            spatializer: Box::new(StereoPanner),
             emitters: HashMap::new(),
+            listeners: HashMap::new(),
             library: SoundLibrary::default(),
-            audio_processor,
-            sink,
+            next_id: 0,
+            mono: vec![],
         }
     }
 
+    /// Plays on the default device when the `cpal` feature is on and one is
+    /// available, otherwise discards the audio with a warning. `cpal` is not
+    /// a default feature. With the `steam-audio` feature emitters are
+    /// rendered with its HRTF.
+    pub fn create_system() -> Self {
+        #[allow(unused_mut)]
+        let mut system = Self::new(Box::new(NullOutput::default()));
+        #[cfg(feature = "cpal")]
+        match CpalOutput::new() {
+            Ok(output) => system.set_output(Box::new(output)),
+            Err(_) => log::warn!("Audio is discarded, no output device could be opened"),
+        }
+        #[cfg(not(feature = "cpal"))]
+        log::warn!("Audio is discarded, og_engine was built without the cpal feature");
+        #[cfg(feature = "steam-audio")]
+        {
+            let rate = system.sample_rate();
+            system.set_spatializer(Box::new(SteamAudioSpatializer::new(rate, SpatialMode::Binaural)));
+        }
+        system
+    }
+
     pub fn with_emitter(mut self, emitter: Emitter) -> Self {
-        self.emitters.insert(self.emitters.len() as u32, emitter);
+        self.register_emitter(emitter);
         self
     }
 
@@ -435,128 +889,299 @@ impl AudioSystem {
     }
 
     pub fn with_listener(mut self, listener: Listener) -> Self {
-        self.listeners.insert(self.listeners.len() as u32, listener);
+        self.register_listener(listener);
         self
     }
 
-    pub fn register_emitter(&mut self, emitter: Emitter) -> u32 {
-        let uid = self.emitters.len() as u32;
+    pub fn set_output(&mut self, output: Box<dyn AudioOutput>) {
+        self.output = output;
+    }
+
+    pub fn set_spatializer(&mut self, spatializer: Box<dyn Spatializer>) {
+        self.spatializer = spatializer;
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.output.sample_rate()
+    }
+
+    pub fn library(&self) -> &SoundLibrary {
+        &self.library
+    }
+
+    pub fn library_mut(&mut self) -> &mut SoundLibrary {
+        &mut self.library
+    }
+
+    pub fn register_emitter(&mut self, emitter: Emitter) -> EmitterId {
+        let uid = self.next_id;
+        self.next_id += 1;
         self.emitters.insert(uid, emitter);
         uid
     }
 
-    pub fn register_listener(&mut self, listener: Listener) -> u32 {
-        let uid = self.listeners.len() as u32;
+    pub fn register_listener(&mut self, listener: Listener) -> ListenerId {
+        let uid = self.next_id;
+        self.next_id += 1;
         self.listeners.insert(uid, listener);
+        self.active_listener.get_or_insert(uid);
         uid
     }
 
-    pub fn get_emitter(&mut self, uid: u32) -> Option<&mut Emitter> {
+    pub fn remove_emitter(&mut self, uid: EmitterId) -> Option<Emitter> {
+        self.spatializer.remove_emitter(uid);
+        self.emitters.remove(&uid)
+    }
+
+    pub fn remove_listener(&mut self, uid: ListenerId) -> Option<Listener> {
+        if self.active_listener == Some(uid) {
+            self.active_listener = None;
+        }
+        self.listeners.remove(&uid)
+    }
+
+    pub fn get_emitter(&mut self, uid: EmitterId) -> Option<&mut Emitter> {
         self.emitters.get_mut(&uid)
     }
 
-    pub fn get_listener(&mut self, uid: u32) -> Option<&mut Listener> {
+    pub fn get_listener(&mut self, uid: ListenerId) -> Option<&mut Listener> {
         self.listeners.get_mut(&uid)
     }
 
-    fn frame_to_buffer(frame: SoundFrame) -> SamplesBuffer<f32> {
-        SamplesBuffer::new(
-            frame.channels as u16,
-            frame.sample_rate as u32,
-            frame.data.collect::<Vec<f32>>(),
-        )
-    }
-
-    fn play_listener(listener: &Listener) {
-        //For all the frames in get_frames, create buffers and append them to a sink
-    }
-
-    #[inline]
-    pub fn update(&mut self) {
-        if self.sink.len() < FRAMES_TO_BUFFER {
-            let (mixer_controller, mixer) = rodio::dynamic_mixer::mixer::<f32>(2, 44100);
-            //for each Listener, apply_convolutions.
-            for (_, listener) in self.listeners.iter_mut() {
-                //for each Emitter, get the frames and send them to all Listeners
-                for (emitter_id, emitter) in self.emitters.iter_mut() {
-                    if let Some(frames) = emitter.get_frames() {
-                        for (i, frame) in frames.into_iter().enumerate() {
-                            let out =
-                                listener.apply_convolutions(
-                                    frame,
-                                    &*emitter,
-                                    &mut self.audio_processor,
-                                );
-                            mixer_controller.add(out);
-                        }
+    /// Plays a library sound on a new non-spatial emitter that is removed
+    /// when it finishes.
+    pub fn play_sound(&mut self, name: &str) -> Option<EmitterId> {
+        let sound = self.library.get_sound(name)?.clone();
+        let mut emitter = Emitter::ambient(None);
+        emitter.one_shot = true;
+        emitter.play(&sound);
+        Some(self.register_emitter(emitter))
+    }
+
+    /// Moves listeners with `follow_camera` to the camera.
+    pub fn follow_camera(&mut self, camera: &Camera) {
+        for listener in self.listeners.values_mut() {
+            if listener.follow_camera {
+                listener.follow(camera);
+            }
+        }
+    }
+
+    /// Mixes as much audio as the output needs `elapsed` seconds after the
+    /// last update.
+    pub fn update(&mut self, elapsed: f64) {
+        self.update_velocities(elapsed as f32);
+        let mut frames = self.output.frames_needed(elapsed);
+        while frames > 0 {
+            let block = frames.min(FRAME_SIZE);
+            let mix = self.mix(block);
+            self.output.write(&mix);
+            frames -= block;
+        }
+        let finished: Vec<EmitterId> = self
+            .emitters
+            .iter()
+            .filter(|(_, e)| e.one_shot && e.voices.is_empty())
+            .map(|(id, _)| *id)
+            .collect();
+        for id in finished {
+            self.remove_emitter(id);
+        }
+    }
+
+    /// Casts rays from the listener to every occludable emitter through
+    /// `geometry`, at most `occlusion.update_rate` times a second. The game
+    /// loop calls it with `OGEngine::audio_occluder`, call it every frame
+    /// when using an `AudioSystem` without the engine.
+    pub fn update_occlusion(&mut self, geometry: &dyn OcclusionGeometry, elapsed: f64) {
+        if !self.occlusion.enabled {
+            return;
+        }
+        self.occlusion_timer += elapsed;
+        if self.occlusion_timer < 1.0 / self.occlusion.update_rate.max(0.001) as f64 {
+            return;
+        }
+        self.occlusion_timer = 0.0;
+        let default_listener = Listener::default();
+        let listener = self
+            .active_listener
+            .and_then(|id| self.listeners.get(&id))
+            .unwrap_or(&default_listener);
+        let rot = listener.transform.rot;
+        for emitter in self.emitters.values_mut() {
+            if emitter.spatial && emitter.occludable && !emitter.voices.is_empty() {
+                emitter.occlusion_target = Occlusion::measure(
+                    geometry,
+                    &self.occlusion,
+                    listener.transform.pos,
+                    emitter.transform.pos,
+                    rot.right(),
+                    rot.up(),
+                );
+            }
+        }
+    }
+
+    fn update_velocities(&mut self, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        for emitter in self.emitters.values_mut() {
+            let pos = emitter.transform.pos;
+            emitter.velocity = emitter.last_position.map(|last| (pos - last) / dt).unwrap_or_default();
+            emitter.last_position = Some(pos);
+        }
+        for listener in self.listeners.values_mut() {
+            let pos = listener.transform.pos;
+            listener.velocity = listener.last_position.map(|last| (pos - last) / dt).unwrap_or_default();
+            listener.last_position = Some(pos);
+        }
+    }
+
+    /// Renders `frames` frames of interleaved stereo and advances every
+    /// playing sound.
+    pub fn mix(&mut self, frames: usize) -> Vec<f32> {
+        let mut out = vec![0.0; frames * 2];
+        let rate = self.output.sample_rate() as f64;
+        self.buses.advance((frames as f64 / rate) as f32);
+        self.buses.begin(frames);
+        let default_listener = Listener::default();
+        let listener = self
+            .active_listener
+            .and_then(|id| self.listeners.get(&id))
+            .unwrap_or(&default_listener);
+
+        for (id, emitter) in self.emitters.iter_mut() {
+            if emitter.paused || emitter.voices.is_empty() {
+                continue;
+            }
+            if emitter.spatial {
+                let local = listener.to_local(emitter.transform.pos);
+                let distance = local.length();
+                let direction = if distance > f32::EPSILON {
+                    local / distance
+                } else {
+                    Vector3::forward()
+                };
+                let occluded = self.occlusion.enabled && emitter.occludable;
+                if occluded {
+                    let amount = frames as f32 / rate as f32 / self.occlusion.smoothing.max(0.001);
+                    let target = emitter.occlusion_target;
+                    emitter.occlusion.approach(&target, amount);
+                } else {
+                    emitter.occlusion = Occlusion::default();
+                }
+                let params = SpatialParams {
+                    direction,
+                    distance,
+                    gain: emitter.attenuation.gain(distance) * emitter.gain * emitter.occlusion.gain(),
+                    pan: direction.x,
+                };
+                let pitch = emitter.pitch * doppler(listener, emitter);
+                self.mono.clear();
+                self.mono.resize(frames, 0.0);
+                for voice in emitter.voices.iter_mut() {
+                    let step = voice.sample_rate() as f64 / rate * pitch as f64;
+                    for sample in self.mono.iter_mut() {
+                        let (l, r) = voice.next_frame(step);
+                        *sample += (l + r) * 0.5;
+                    }
+                }
+                if occluded && emitter.occlusion.occlusion > 0.0 {
+                    emitter.low_pass.cutoff = emitter.occlusion.cutoff(OPEN_CUTOFF, self.occlusion.muffled_cutoff);
+                    emitter.low_pass.process_mono(&mut self.mono, rate as u32);
+                }
+                let out = self.buses.input(&emitter.bus);
+                self.spatializer.spatialize(*id, &params, &self.mono, out);
+            } else {
+                let out = self.buses.input(&emitter.bus);
+                for voice in emitter.voices.iter_mut() {
+                    let step = voice.sample_rate() as f64 / rate * emitter.pitch as f64;
+                    for frame in out.chunks_exact_mut(2) {
+                        let (l, r) = voice.next_frame(step);
+                        frame[0] += l * emitter.gain;
+                        frame[1] += r * emitter.gain;
                     }
                 }
             }
-            self.sink.append(mixer);
+            emitter.voices.retain(|v| !v.is_finished());
+        }
+        self.buses.process(&mut out, rate as u32);
+
+        let gain = self.master_gain * listener.gain;
+        for sample in out.iter_mut() {
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
         }
+        out
     }
 }
 
-impl SoundFrame {
-    pub fn empty() -> Self {
-        Self {
-            data: vec![].into_iter(),
-            duration: std::time::Duration::from_secs(0),
-            channels: 0,
-            sample_rate: 0,
-            size: 0,
-        }
+//Pitch factor for the relative motion of listener and emitter
+fn doppler(listener: &Listener, emitter: &Emitter) -> f32 {
+    if emitter.doppler_factor <= 0.0 {
+        return 1.0;
     }
+    let offset = emitter.transform.pos - listener.transform.pos;
+    let distance = offset.length();
+    if distance <= f32::EPSILON {
+        return 1.0;
+    }
+    let to_emitter = offset / distance;
+    let max_speed = SPEED_OF_SOUND * 0.9;
+    let listener_speed = (listener.velocity.dot(to_emitter) * emitter.doppler_factor).clamp(-max_speed, max_speed);
+    let emitter_speed = (emitter.velocity.dot(to_emitter) * emitter.doppler_factor).clamp(-max_speed, max_speed);
+    (SPEED_OF_SOUND + listener_speed) / (SPEED_OF_SOUND + emitter_speed)
+}
 
-    fn interleave(mut self) -> Self {
-        let chunk_size = self.data.len() / 2;
-        let frame_data = self.data.collect::<Vec<_>>();
-        let mut chunks = frame_data.chunks(chunk_size);
-        self.data = chunks
-            .next()
-            .unwrap()
-            .iter()
-            .copied()
-            .interleave(chunks.next().unwrap().iter().copied())
-            .collect::<Vec<_>>()
-            .into_iter();
-        self
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_output_paces_and_counts_frames() {
+        let mut output = NullOutput::default();
+        let mut emitter = Emitter::ambient(None);
+        emitter.play(&Sound::new(vec![0.5; 10], 1, SAMPLE_RATE));
+        let mut system = AudioSystem::new(Box::new(NullOutput::default())).with_emitter(emitter);
+        assert_eq!(system.sample_rate(), SAMPLE_RATE);
+        assert_eq!(output.frames_needed(0.01), 441);
+        let mix = system.mix(441);
+        assert_eq!(mix.len(), 882);
+        assert_eq!(&mix[0..2], &[0.5, 0.5]);
+        assert_eq!(&mix[20..22], &[0.0, 0.0]);
+        output.write(&mix);
+        assert_eq!(output.written, 441);
     }
 
-    fn mono_to_stereo(mut self) -> Self {
-        self.channels = 2;
-        let c2 = self.data.clone();
-        self.data = self
-            .data
-            .interleave(c2)
-            .collect::<Vec<f32>>()
-            .into_iter();
-        self
+    #[test]
+    fn file_output_gets_the_mix() {
+        let path = std::env::temp_dir().join("og_engine_file_output_test.wav");
+        let path = path.to_str().unwrap().to_string();
+        //100 frames of a known stereo buffer, then silence
+        let sound = Sound::new([0.25, -0.5].repeat(100), 2, SAMPLE_RATE);
+        let mut emitter = Emitter::ambient(None);
+        emitter.play(&sound);
+        let mut system = AudioSystem::new(Box::new(FileOutput::new(&path, SAMPLE_RATE))).with_emitter(emitter);
+        system.update(0.01);
+        //Dropping the output saves the file
+        drop(system);
+
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        let samples: Vec<f32> = bytes[44..]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        assert_eq!(samples.len(), 441 * 2);
+        for (i, frame) in samples.chunks_exact(2).enumerate() {
+            let expected = if i < 100 { [0.25, -0.5] } else { [0.0, 0.0] };
+            assert!(
+                (frame[0] - expected[0]).abs() < 1e-6 && (frame[1] - expected[1]).abs() < 1e-6,
+                "frame {}: {:?}",
+                i,
+                frame
+            );
+        }
     }
 }
-
-// struct SinkPool {
-//     sinks: HashMap<u32, rodio::Sink>,
-// }
-
-// impl SinkPool {
-//     fn new(stream_handle: &rodio::OutputStreamHandle) -> Self {
-//         Self {
-//             sinks: (0..MAXSINKS)
-//                 .into_iter()
-//                 .map(|_| Sink::try_new(stream_handle).expect("Failed to create sink"))
-//                 .collect(),
-//         }
-//     }
-
-//     fn get_sink(&mut self, emitter_id: u32) -> Option<&mut Sink> {
-
-//         self.sinks.iter_mut().find_map(|(id, s)| {
-//             if (emitter_id == *id) && (s.len() < FRAMES_TO_BUFFER) {
-//                 Some(s)
-//             } else {
-//                 None
-//             }
-//         })
-//     }
-// }
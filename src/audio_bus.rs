@@ -0,0 +1,953 @@
+use super::og_engine::Rcode;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+pub const MASTER_BUS: &str = "master";
+pub const MUSIC_BUS: &str = "music";
+pub const SFX_BUS: &str = "sfx";
+pub const VOICE_BUS: &str = "voice";
+pub const AMBIENCE_BUS: &str = "ambience";
+
+/// Processing on a bus, run in the order the effects were added.
+pub trait Effect {
+    /// `buffer` is interleaved stereo.
+    fn process(&mut self, buffer: &mut [f32], sample_rate: u32);
+    /// Sets a parameter by name, used by automation. Unknown names are
+    /// ignored.
+    fn set_param(&mut self, _name: &str, _value: f32) {}
+    fn param(&self, _name: &str) -> Option<f32> {
+        None
+    }
+}
+
+/// Audio routed from one bus into another as well as into its parent, e.g.
+/// to a shared reverb.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BusSend {
+    pub target: String,
+    pub gain: f32,
+    /// Sent before the bus gain and mute are applied.
+    pub pre_fader: bool,
+}
+
+/// A group of sounds mixed together, processed by its effects and passed on
+/// to its parent bus. Emitters pick theirs with `Emitter::bus`.
+pub struct Bus {
+    pub name: String,
+    pub gain: f32,
+    pub mute: bool,
+    /// While any bus is soloed only soloed buses, and the buses under them,
+    /// are heard.
+    pub solo: bool,
+    parent: Option<String>,
+    sends: Vec<BusSend>,
+    effects: Vec<Box<dyn Effect>>,
+    buffer: Vec<f32>,
+    //Peak of the last block, for ducking
+    level: f32,
+    duck: f32,
+    //Gain at the end of the last block, ramped from to avoid clicks
+    last_gain: f32,
+}
+
+impl Bus {
+    /// Feeds the master bus unless given another parent.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            gain: 1.0,
+            mute: false,
+            solo: false,
+            parent: if name == MASTER_BUS {
+                None
+            } else {
+                Some(MASTER_BUS.to_string())
+            },
+            sends: vec![],
+            effects: vec![],
+            buffer: vec![],
+            level: 0.0,
+            duck: 1.0,
+            last_gain: 1.0,
+        }
+    }
+
+    pub fn with_parent(mut self, parent: &str) -> Self {
+        self.parent = Some(parent.to_string());
+        self
+    }
+
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self.last_gain = gain;
+        self
+    }
+
+    pub fn with_send(mut self, target: &str, gain: f32) -> Self {
+        self.sends.insert(
+            self.sends.len(),
+            BusSend {
+                target: target.to_string(),
+                gain,
+                pre_fader: false,
+            },
+        );
+        self
+    }
+
+    pub fn with_effect<E: Effect + 'static>(mut self, effect: E) -> Self {
+        self.add_effect(effect);
+        self
+    }
+
+    pub fn add_effect<E: Effect + 'static>(&mut self, effect: E) {
+        self.effects.insert(self.effects.len(), Box::new(effect));
+    }
+
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    pub fn sends(&self) -> &[BusSend] {
+        &self.sends
+    }
+
+    pub fn effects(&self) -> &[Box<dyn Effect>] {
+        &self.effects
+    }
+
+    pub fn effects_mut(&mut self) -> &mut Vec<Box<dyn Effect>> {
+        &mut self.effects
+    }
+
+    /// Peak level of the last mixed block, after effects and gain.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+}
+
+/// What an automation changes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BusParam {
+    Gain,
+    /// Gain of the send to the named bus.
+    Send(String),
+    /// A parameter of the effect at an index on the bus.
+    Effect(usize, String),
+}
+
+//A linear ramp of one parameter
+struct Automation {
+    bus: String,
+    param: BusParam,
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+/// Lowers the `target` bus while the `trigger` bus is playing, e.g. music
+/// under dialogue.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ducking {
+    pub trigger: String,
+    pub target: String,
+    /// Gain of the target while ducked.
+    pub amount: f32,
+    /// Trigger peak level that starts ducking.
+    pub threshold: f32,
+    /// Seconds to duck.
+    pub attack: f32,
+    /// Seconds to come back up once the trigger is quiet.
+    pub release: f32,
+    envelope: f32,
+}
+
+impl Ducking {
+    pub fn new(trigger: &str, target: &str, amount: f32) -> Self {
+        Self {
+            trigger: trigger.to_string(),
+            target: target.to_string(),
+            amount,
+            threshold: 0.01,
+            attack: 0.1,
+            release: 0.5,
+            envelope: 1.0,
+        }
+    }
+}
+
+/// Buses of an `AudioSystem`. Starts with master, and music, sfx, voice and
+/// ambience feeding it.
+pub struct BusGraph {
+    buses: HashMap<String, Bus>,
+    automations: Vec<Automation>,
+    duckings: Vec<Ducking>,
+    //Buses before every bus they feed, rebuilt when routing changes
+    order: Vec<String>,
+    dirty: bool,
+}
+
+impl Default for BusGraph {
+    fn default() -> Self {
+        let mut graph = Self {
+            buses: HashMap::new(),
+            automations: vec![],
+            duckings: vec![],
+            order: vec![],
+            dirty: true,
+        };
+        for name in [MASTER_BUS, MUSIC_BUS, SFX_BUS, VOICE_BUS, AMBIENCE_BUS] {
+            graph.add_bus(Bus::new(name));
+        }
+        graph
+    }
+}
+
+impl BusGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces a bus with the same name.
+    pub fn add_bus(&mut self, bus: Bus) {
+        self.buses.insert(bus.name.clone(), bus);
+        self.dirty = true;
+    }
+
+    /// Buses routed into a removed bus go to master instead. Master can't be
+    /// removed.
+    pub fn remove_bus(&mut self, name: &str) -> Option<Bus> {
+        if name == MASTER_BUS {
+            return None;
+        }
+        let bus = self.buses.remove(name)?;
+        for other in self.buses.values_mut() {
+            if other.parent.as_deref() == Some(name) {
+                other.parent = Some(MASTER_BUS.to_string());
+            }
+            other.sends.retain(|s| s.target != name);
+        }
+        self.dirty = true;
+        Some(bus)
+    }
+
+    pub fn bus(&self, name: &str) -> Option<&Bus> {
+        self.buses.get(name)
+    }
+
+    pub fn bus_mut(&mut self, name: &str) -> Option<&mut Bus> {
+        self.buses.get_mut(name)
+    }
+
+    pub fn set_parent(&mut self, name: &str, parent: &str) -> Rcode {
+        if name == MASTER_BUS || !self.buses.contains_key(parent) {
+            return Rcode::Fail;
+        }
+        match self.buses.get_mut(name) {
+            Some(bus) => {
+                bus.parent = Some(parent.to_string());
+                self.dirty = true;
+                Rcode::Ok
+            }
+            None => Rcode::Fail,
+        }
+    }
+
+    /// Adds or updates the send from `name` to `target`.
+    pub fn set_send(&mut self, name: &str, send: BusSend) -> Rcode {
+        match self.buses.get_mut(name) {
+            Some(bus) => {
+                match bus.sends.iter_mut().find(|s| s.target == send.target) {
+                    Some(existing) => *existing = send,
+                    None => bus.sends.insert(bus.sends.len(), send),
+                }
+                self.dirty = true;
+                Rcode::Ok
+            }
+            None => Rcode::Fail,
+        }
+    }
+
+    pub fn remove_send(&mut self, name: &str, target: &str) {
+        if let Some(bus) = self.buses.get_mut(name) {
+            bus.sends.retain(|s| s.target != target);
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_gain(&mut self, name: &str, gain: f32) {
+        if let Some(bus) = self.buses.get_mut(name) {
+            bus.gain = gain;
+        }
+    }
+
+    pub fn set_mute(&mut self, name: &str, mute: bool) {
+        if let Some(bus) = self.buses.get_mut(name) {
+            bus.mute = mute;
+        }
+    }
+
+    pub fn set_solo(&mut self, name: &str, solo: bool) {
+        if let Some(bus) = self.buses.get_mut(name) {
+            bus.solo = solo;
+        }
+    }
+
+    pub fn add_ducking(&mut self, ducking: Ducking) {
+        self.duckings.insert(self.duckings.len(), ducking);
+    }
+
+    pub fn clear_ducking(&mut self) {
+        self.duckings.clear();
+        for bus in self.buses.values_mut() {
+            bus.duck = 1.0;
+        }
+    }
+
+    /// Current value of a parameter.
+    pub fn param(&self, name: &str, param: &BusParam) -> Option<f32> {
+        let bus = self.buses.get(name)?;
+        match param {
+            BusParam::Gain => Some(bus.gain),
+            BusParam::Send(target) => bus.sends.iter().find(|s| &s.target == target).map(|s| s.gain),
+            BusParam::Effect(index, param) => bus.effects.get(*index)?.param(param),
+        }
+    }
+
+    pub fn set_param(&mut self, name: &str, param: &BusParam, value: f32) {
+        let bus = match self.buses.get_mut(name) {
+            Some(bus) => bus,
+            None => return,
+        };
+        match param {
+            BusParam::Gain => bus.gain = value,
+            BusParam::Send(target) => {
+                if let Some(send) = bus.sends.iter_mut().find(|s| &s.target == target) {
+                    send.gain = value;
+                }
+            }
+            BusParam::Effect(index, param) => {
+                if let Some(effect) = bus.effects.get_mut(*index) {
+                    effect.set_param(param, value);
+                }
+            }
+        }
+    }
+
+    /// Moves a parameter from its current value to `to` over `duration`
+    /// seconds, replacing any automation of the same parameter. Effects that
+    /// don't report the parameter start from `to`.
+    pub fn automate(&mut self, name: &str, param: BusParam, to: f32, duration: f32) {
+        let from = self.param(name, &param).unwrap_or(to);
+        self.automations.retain(|a| !(a.bus == name && a.param == param));
+        self.automations.insert(
+            self.automations.len(),
+            Automation {
+                bus: name.to_string(),
+                param,
+                from,
+                to,
+                duration: duration.max(0.0),
+                elapsed: 0.0,
+            },
+        );
+    }
+
+    /// Fades a bus gain, e.g. to bring the music in.
+    pub fn fade(&mut self, name: &str, to: f32, duration: f32) {
+        self.automate(name, BusParam::Gain, to, duration);
+    }
+
+    /// Advances automation and ducking `elapsed` seconds.
+    pub fn advance(&mut self, elapsed: f32) {
+        let mut automations = std::mem::take(&mut self.automations);
+        for automation in automations.iter_mut() {
+            automation.elapsed += elapsed;
+            let t = if automation.duration > 0.0 {
+                (automation.elapsed / automation.duration).min(1.0)
+            } else {
+                1.0
+            };
+            let value = automation.from + (automation.to - automation.from) * t;
+            self.set_param(&automation.bus, &automation.param, value);
+        }
+        automations.retain(|a| a.elapsed < a.duration);
+        self.automations = automations;
+
+        for bus in self.buses.values_mut() {
+            bus.duck = 1.0;
+        }
+        for ducking in self.duckings.iter_mut() {
+            let active = self
+                .buses
+                .get(&ducking.trigger)
+                .map_or(false, |b| b.level > ducking.threshold);
+            let (goal, time) = if active {
+                (ducking.amount, ducking.attack)
+            } else {
+                (1.0, ducking.release)
+            };
+            let rate = if time > 0.0 { elapsed / time } else { 1.0 };
+            let step = (1.0 - ducking.amount).abs() * rate;
+            ducking.envelope = if ducking.envelope > goal {
+                (ducking.envelope - step).max(goal)
+            } else {
+                (ducking.envelope + step).min(goal)
+            };
+            if let Some(bus) = self.buses.get_mut(&ducking.target) {
+                bus.duck *= ducking.envelope;
+            }
+        }
+    }
+
+    /// Clears every bus for a block of `frames`.
+    pub fn begin(&mut self, frames: usize) {
+        if self.dirty {
+            for bus in self.buses.values() {
+                if let Some(parent) = bus.parent.as_deref().filter(|p| !self.buses.contains_key(*p)) {
+                    log::warn!("Bus {} goes to master, its parent {} doesn't exist", bus.name, parent);
+                }
+            }
+            self.order = self.sort();
+            self.dirty = false;
+        }
+        for bus in self.buses.values_mut() {
+            bus.buffer.clear();
+            bus.buffer.resize(frames * 2, 0.0);
+        }
+    }
+
+    /// Interleaved stereo input of a bus, master if there is no such bus.
+    pub fn input(&mut self, name: &str) -> &mut Vec<f32> {
+        let name = if self.buses.contains_key(name) { name } else { MASTER_BUS };
+        &mut self.buses.get_mut(name).unwrap().buffer
+    }
+
+    /// Processes every bus into its parent and sends, and adds master to
+    /// `out`.
+    pub fn process(&mut self, out: &mut [f32], sample_rate: u32) {
+        //Silence what was played straight into buses outside the solo, before
+        //soloed children are mixed into them
+        if self.buses.values().any(|b| b.solo) {
+            let silent: Vec<String> = self
+                .buses
+                .keys()
+                .filter(|name| !self.under_solo(name))
+                .cloned()
+                .collect();
+            for name in silent {
+                self.buses.get_mut(&name).unwrap().buffer.iter_mut().for_each(|s| *s = 0.0);
+            }
+        }
+        let frames = out.len() / 2;
+        for name in self.order.clone() {
+            let bus = match self.buses.get_mut(&name) {
+                Some(bus) => bus,
+                None => continue,
+            };
+            let mut buffer = std::mem::take(&mut bus.buffer);
+            for effect in bus.effects.iter_mut() {
+                effect.process(&mut buffer, sample_rate);
+            }
+            let sends = bus.sends.clone();
+            for send in sends.iter().filter(|s| s.pre_fader) {
+                self.add_to(&send.target, &buffer, send.gain);
+            }
+
+            let bus = self.buses.get_mut(&name).unwrap();
+            let gain = if bus.mute { 0.0 } else { bus.gain * bus.duck };
+            let from = bus.last_gain;
+            bus.last_gain = gain;
+            let mut level: f32 = 0.0;
+            for (i, frame) in buffer.chunks_exact_mut(2).enumerate() {
+                let g = from + (gain - from) * (i + 1) as f32 / frames.max(1) as f32;
+                frame[0] *= g;
+                frame[1] *= g;
+                level = level.max(frame[0].abs()).max(frame[1].abs());
+            }
+            bus.level = level;
+            let parent = bus.parent.clone();
+            let parent = self.route(parent.as_deref()).map(|p| p.to_string());
+
+            for send in sends.iter().filter(|s| !s.pre_fader) {
+                self.add_to(&send.target, &buffer, send.gain);
+            }
+            match parent {
+                Some(parent) => self.add_to(&parent, &buffer, 1.0),
+                None => out.iter_mut().zip(buffer.iter()).for_each(|(o, s)| *o += s),
+            }
+            self.buses.get_mut(&name).unwrap().buffer = buffer;
+        }
+    }
+
+    fn add_to(&mut self, name: &str, samples: &[f32], gain: f32) {
+        if let Some(bus) = self.buses.get_mut(name) {
+            for (out, sample) in bus.buffer.iter_mut().zip(samples.iter()) {
+                *out += sample * gain;
+            }
+        }
+    }
+
+    //Where a bus with `parent` mixes into, master when the parent is missing
+    fn route<'a>(&'a self, parent: Option<&'a str>) -> Option<&'a str> {
+        parent.map(|p| if self.buses.contains_key(p) { p } else { MASTER_BUS })
+    }
+
+    //Whether the bus or one it feeds into is soloed
+    fn under_solo(&self, name: &str) -> bool {
+        let mut current = Some(name);
+        let mut depth = 0;
+        while let Some(name) = current {
+            let bus = match self.buses.get(name) {
+                Some(bus) => bus,
+                None => return false,
+            };
+            if bus.solo {
+                return true;
+            }
+            current = self.route(bus.parent.as_deref());
+            depth += 1;
+            if depth > self.buses.len() {
+                return false;
+            }
+        }
+        false
+    }
+
+    //Orders buses so each comes before its parent and send targets. Buses in
+    //a loop are left out
+    fn sort(&self) -> Vec<String> {
+        let mut inputs: HashMap<&str, usize> = self.buses.keys().map(|k| (k.as_str(), 0)).collect();
+        let outputs = |bus: &Bus| -> Vec<String> {
+            self.route(bus.parent.as_deref())
+                .map(|p| p.to_string())
+                .into_iter()
+                .chain(bus.sends.iter().map(|s| s.target.clone()))
+                .filter(|t| self.buses.contains_key(t))
+                .collect()
+        };
+        for bus in self.buses.values() {
+            for target in outputs(bus) {
+                *inputs.get_mut(target.as_str()).unwrap() += 1;
+            }
+        }
+        let mut ready: Vec<String> = inputs
+            .iter()
+            .filter(|(_, n)| **n == 0)
+            .map(|(k, _)| k.to_string())
+            .collect();
+        let mut order = vec![];
+        while let Some(name) = ready.pop() {
+            for target in outputs(&self.buses[&name]) {
+                let n = inputs.get_mut(target.as_str()).unwrap();
+                *n -= 1;
+                if *n == 0 {
+                    ready.insert(ready.len(), target);
+                }
+            }
+            order.insert(order.len(), name);
+        }
+        if order.len() < self.buses.len() {
+            log::error!("Audio buses are routed in a loop, some won't be heard");
+        }
+        order
+    }
+}
+
+/// Which frequencies a `Biquad` lets through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterKind {
+    LowPass,
+    HighPass,
+}
+
+/// A 12 dB per octave filter. Parameters are `cutoff` in Hz and `q`.
+#[derive(Clone, Debug)]
+pub struct Biquad {
+    pub kind: FilterKind,
+    pub cutoff: f32,
+    pub q: f32,
+    coefficients: [f32; 5],
+    //Coefficients were worked out for this cutoff, q and rate
+    tuned: (f32, f32, u32),
+    //x1, x2, y1, y2 per channel
+    state: [[f32; 4]; 2],
+}
+
+impl Biquad {
+    pub fn new(kind: FilterKind, cutoff: f32, q: f32) -> Self {
+        Self {
+            kind,
+            cutoff,
+            q,
+            coefficients: [1.0, 0.0, 0.0, 0.0, 0.0],
+            tuned: (0.0, 0.0, 0),
+            state: [[0.0; 4]; 2],
+        }
+    }
+
+    pub fn low_pass(cutoff: f32) -> Self {
+        Self::new(FilterKind::LowPass, cutoff, std::f32::consts::FRAC_1_SQRT_2)
+    }
+
+    pub fn high_pass(cutoff: f32) -> Self {
+        Self::new(FilterKind::HighPass, cutoff, std::f32::consts::FRAC_1_SQRT_2)
+    }
+
+    //From the Audio EQ Cookbook
+    fn tune(&mut self, sample_rate: u32) {
+        if self.tuned == (self.cutoff, self.q, sample_rate) {
+            return;
+        }
+        self.tuned = (self.cutoff, self.q, sample_rate);
+        let nyquist = sample_rate as f32 * 0.5;
+        let w0 = 2.0 * PI * self.cutoff.clamp(10.0, nyquist * 0.99) / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * self.q.max(0.01));
+        let cos = w0.cos();
+        let (b0, b1, b2) = match self.kind {
+            FilterKind::LowPass => ((1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0),
+            FilterKind::HighPass => ((1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0),
+        };
+        let a0 = 1.0 + alpha;
+        self.coefficients = [b0 / a0, b1 / a0, b2 / a0, -2.0 * cos / a0, (1.0 - alpha) / a0];
+    }
+
+    /// Filters mono audio, using the left channel's state.
+    pub fn process_mono(&mut self, samples: &mut [f32], sample_rate: u32) {
+        self.tune(sample_rate);
+        for sample in samples.iter_mut() {
+            *sample = self.tick(0, *sample);
+        }
+    }
+
+    /// Filters one sample of `channel`, 0 or 1.
+    pub fn tick(&mut self, channel: usize, x: f32) -> f32 {
+        let [b0, b1, b2, a1, a2] = self.coefficients;
+        let [x1, x2, y1, y2] = self.state[channel];
+        let y = b0 * x + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+        self.state[channel] = [x, x1, y, y1];
+        y
+    }
+}
+
+impl Effect for Biquad {
+    fn process(&mut self, buffer: &mut [f32], sample_rate: u32) {
+        self.tune(sample_rate);
+        for frame in buffer.chunks_exact_mut(2) {
+            frame[0] = self.tick(0, frame[0]);
+            frame[1] = self.tick(1, frame[1]);
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) {
+        match name {
+            "cutoff" => self.cutoff = value,
+            "q" => self.q = value,
+            _ => {}
+        }
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        match name {
+            "cutoff" => Some(self.cutoff),
+            "q" => Some(self.q),
+            _ => None,
+        }
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.max(1e-6).log10()
+}
+
+/// Evens out loudness by turning down anything over `threshold` dB. Both
+/// channels are turned down together.
+#[derive(Clone, Debug)]
+pub struct Compressor {
+    pub threshold: f32,
+    pub ratio: f32,
+    /// Seconds.
+    pub attack: f32,
+    pub release: f32,
+    /// dB added after compressing.
+    pub makeup: f32,
+    envelope: f32,
+}
+
+impl Default for Compressor {
+    fn default() -> Self {
+        Self {
+            threshold: -12.0,
+            ratio: 4.0,
+            attack: 0.01,
+            release: 0.2,
+            makeup: 0.0,
+            envelope: -120.0,
+        }
+    }
+}
+
+impl Compressor {
+    pub fn new(threshold: f32, ratio: f32) -> Self {
+        Self {
+            threshold,
+            ratio,
+            ..Self::default()
+        }
+    }
+}
+
+impl Effect for Compressor {
+    fn process(&mut self, buffer: &mut [f32], sample_rate: u32) {
+        let coefficient = |time: f32| (-1.0 / (time.max(1e-4) * sample_rate as f32)).exp();
+        let (attack, release) = (coefficient(self.attack), coefficient(self.release));
+        let ratio = self.ratio.max(1.0);
+        for frame in buffer.chunks_exact_mut(2) {
+            let level = gain_to_db(frame[0].abs().max(frame[1].abs()));
+            let k = if level > self.envelope { attack } else { release };
+            self.envelope = level + k * (self.envelope - level);
+            let over = (self.envelope - self.threshold).max(0.0);
+            let gain = db_to_gain(self.makeup - over * (1.0 - 1.0 / ratio));
+            frame[0] *= gain;
+            frame[1] *= gain;
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) {
+        match name {
+            "threshold" => self.threshold = value,
+            "ratio" => self.ratio = value,
+            "attack" => self.attack = value,
+            "release" => self.release = value,
+            "makeup" => self.makeup = value,
+            _ => {}
+        }
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        match name {
+            "threshold" => Some(self.threshold),
+            "ratio" => Some(self.ratio),
+            "attack" => Some(self.attack),
+            "release" => Some(self.release),
+            "makeup" => Some(self.makeup),
+            _ => None,
+        }
+    }
+}
+
+/// An echo. `time` in seconds, `feedback`, `wet` and `dry` are gains.
+#[derive(Clone, Debug)]
+pub struct Delay {
+    pub time: f32,
+    pub feedback: f32,
+    pub wet: f32,
+    pub dry: f32,
+    /// Longest `time` in seconds, the delay lines are allocated for it once.
+    pub max_time: f32,
+    lines: [Vec<f32>; 2],
+    index: usize,
+}
+
+//Longest delay a new `Delay` can be automated to
+const MAX_DELAY_TIME: f32 = 2.0;
+
+impl Delay {
+    pub fn new(time: f32, feedback: f32) -> Self {
+        Self {
+            time,
+            feedback,
+            wet: 0.5,
+            dry: 1.0,
+            max_time: time.max(MAX_DELAY_TIME),
+            lines: [vec![], vec![]],
+            index: 0,
+        }
+    }
+}
+
+impl Effect for Delay {
+    fn process(&mut self, buffer: &mut [f32], sample_rate: u32) {
+        let max = ((self.max_time.max(0.0) * sample_rate as f32) as usize).max(1);
+        //Only reallocated when the sample rate or max_time change
+        if self.lines[0].len() != max + 1 {
+            self.lines = [vec![0.0; max + 1], vec![0.0; max + 1]];
+            self.index = 0;
+        }
+        let length = max + 1;
+        let delay = ((self.time.max(0.0) * sample_rate as f32) as usize).clamp(1, max);
+        let read = (self.index + length - delay) % length;
+        let feedback = self.feedback.clamp(0.0, 0.99);
+        for (i, frame) in buffer.chunks_exact_mut(2).enumerate() {
+            let read = (read + i) % length;
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let delayed = self.lines[channel][read];
+                self.lines[channel][self.index] = *sample + delayed * feedback;
+                *sample = *sample * self.dry + delayed * self.wet;
+            }
+            self.index = (self.index + 1) % length;
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) {
+        match name {
+            "time" => self.time = value,
+            "feedback" => self.feedback = value,
+            "wet" => self.wet = value,
+            "dry" => self.dry = value,
+            _ => {}
+        }
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        match name {
+            "time" => Some(self.time),
+            "feedback" => Some(self.feedback),
+            "wet" => Some(self.wet),
+            "dry" => Some(self.dry),
+            _ => None,
+        }
+    }
+}
+
+//Freeverb tunings, in samples at 44.1kHz
+const COMB_TUNING: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALLPASS_TUNING: [usize; 4] = [556, 441, 341, 225];
+const STEREO_SPREAD: usize = 23;
+
+#[derive(Clone, Debug)]
+struct Comb {
+    buffer: Vec<f32>,
+    index: usize,
+    store: f32,
+}
+
+impl Comb {
+    fn tick(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.store = output * (1.0 - damping) + self.store * damping;
+        self.buffer[self.index] = input + self.store * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AllPass {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl AllPass {
+    fn tick(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.index];
+        self.buffer[self.index] = input + delayed * 0.5;
+        self.index = (self.index + 1) % self.buffer.len();
+        delayed - input
+    }
+}
+
+/// Algorithmic room reverb. `room_size` and `damping` go from 0 to 1, `wet`
+/// and `dry` are gains.
+#[derive(Clone, Debug)]
+pub struct Reverb {
+    pub room_size: f32,
+    pub damping: f32,
+    pub wet: f32,
+    pub dry: f32,
+    combs: [Vec<Comb>; 2],
+    allpasses: [Vec<AllPass>; 2],
+    sample_rate: u32,
+}
+
+impl Default for Reverb {
+    fn default() -> Self {
+        Self::new(0.5, 0.5)
+    }
+}
+
+impl Reverb {
+    pub fn new(room_size: f32, damping: f32) -> Self {
+        Self {
+            room_size,
+            damping,
+            wet: 0.3,
+            dry: 1.0,
+            combs: [vec![], vec![]],
+            allpasses: [vec![], vec![]],
+            sample_rate: 0,
+        }
+    }
+
+    //Sizes the delay lines for the rate
+    fn build(&mut self, sample_rate: u32) {
+        let scale = |n: usize| ((n as f32 * sample_rate as f32 / 44100.0) as usize).max(1);
+        for channel in 0..2 {
+            let spread = channel * STEREO_SPREAD;
+            self.combs[channel] = COMB_TUNING
+                .iter()
+                .map(|n| Comb {
+                    buffer: vec![0.0; scale(n + spread)],
+                    index: 0,
+                    store: 0.0,
+                })
+                .collect();
+            self.allpasses[channel] = ALLPASS_TUNING
+                .iter()
+                .map(|n| AllPass {
+                    buffer: vec![0.0; scale(n + spread)],
+                    index: 0,
+                })
+                .collect();
+        }
+        self.sample_rate = sample_rate;
+    }
+}
+
+impl Effect for Reverb {
+    fn process(&mut self, buffer: &mut [f32], sample_rate: u32) {
+        if self.sample_rate != sample_rate {
+            self.build(sample_rate);
+        }
+        let feedback = 0.7 + self.room_size.clamp(0.0, 1.0) * 0.28;
+        let damping = self.damping.clamp(0.0, 1.0) * 0.4;
+        for frame in buffer.chunks_exact_mut(2) {
+            let input = (frame[0] + frame[1]) * 0.015;
+            for channel in 0..2 {
+                let mut out = 0.0;
+                for comb in self.combs[channel].iter_mut() {
+                    out += comb.tick(input, feedback, damping);
+                }
+                for allpass in self.allpasses[channel].iter_mut() {
+                    out = allpass.tick(out);
+                }
+                frame[channel] = frame[channel] * self.dry + out * self.wet;
+            }
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) {
+        match name {
+            "room_size" => self.room_size = value,
+            "damping" => self.damping = value,
+            "wet" => self.wet = value,
+            "dry" => self.dry = value,
+            _ => {}
+        }
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        match name {
+            "room_size" => Some(self.room_size),
+            "damping" => Some(self.damping),
+            "wet" => Some(self.wet),
+            "dry" => Some(self.dry),
+            _ => None,
+        }
+    }
+}
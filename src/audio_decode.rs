@@ -0,0 +1,385 @@
+use super::{
+    audio::Sound,
+    og_engine::Rcode,
+};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error,
+    formats::{FormatOptions, FormatReader, SeekMode, SeekTo},
+    io::{MediaSource, MediaSourceStream},
+    meta::MetadataOptions,
+    probe::Hint,
+    units::{Time, TimeBase},
+};
+
+/// Where encoded audio is read from.
+#[derive(Clone, Debug)]
+pub enum AudioSource {
+    File(PathBuf),
+    Bytes(Arc<[u8]>),
+}
+
+impl AudioSource {
+    fn open(&self) -> Result<Box<dyn MediaSource>, Rcode> {
+        Ok(match self {
+            AudioSource::File(path) => Box::new(std::fs::File::open(path).map_err(|_| Rcode::NoFile)?),
+            AudioSource::Bytes(bytes) => Box::new(std::io::Cursor::new(bytes.clone())),
+        })
+    }
+
+    fn extension(&self) -> Option<String> {
+        match self {
+            AudioSource::File(path) => path.extension().map(|e| e.to_string_lossy().to_lowercase()),
+            AudioSource::Bytes(_) => None,
+        }
+    }
+}
+
+//Reads packets from the first audio track and decodes them to f32
+struct PacketDecoder {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    channels: u16,
+    sample_rate: u32,
+    frames: Option<u64>,
+    time_base: Option<TimeBase>,
+    buffer: Option<SampleBuffer<f32>>,
+}
+
+impl PacketDecoder {
+    fn open(source: &AudioSource, extension: Option<&str>) -> Result<Self, Rcode> {
+        let stream = MediaSourceStream::new(source.open()?, Default::default());
+        let mut hint = Hint::new();
+        if let Some(extension) = extension.map(|e| e.to_string()).or_else(|| source.extension()) {
+            hint.with_extension(&extension);
+        }
+        let probed = symphonia::default::get_probe()
+            .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+            .map_err(|e| {
+                log::error!("Unsupported audio: {}", e);
+                Rcode::Fail
+            })?;
+        let format = probed.format;
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or(Rcode::Fail)?;
+        let params = track.codec_params.clone();
+        let decoder = symphonia::default::get_codecs()
+            .make(&params, &DecoderOptions::default())
+            .map_err(|e| {
+                log::error!("Unsupported audio codec: {}", e);
+                Rcode::Fail
+            })?;
+        Ok(Self {
+            track_id: track.id,
+            format,
+            decoder,
+            channels: params.channels.map(|c| c.count() as u16).unwrap_or(0),
+            sample_rate: params.sample_rate.unwrap_or(0),
+            frames: params.n_frames,
+            time_base: params.time_base,
+            buffer: None,
+        })
+    }
+
+    /// Channel count and interleaved samples of the next packet, `None` at
+    /// the end.
+    fn next_block(&mut self) -> Result<Option<(usize, &[f32])>, Rcode> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(Error::ResetRequired) => {
+                    self.decoder.reset();
+                    continue;
+                }
+                Err(e) => {
+                    log::error!("Audio read failed: {}", e);
+                    return Err(Rcode::Fail);
+                }
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            match self.decoder.decode(&packet) {
+                Ok(decoded) => {
+                    let spec = *decoded.spec();
+                    self.channels = spec.channels.count() as u16;
+                    self.sample_rate = spec.rate;
+                    let needed = decoded.capacity() * spec.channels.count();
+                    if self.buffer.as_ref().map_or(true, |b| b.capacity() < needed) {
+                        self.buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+                    }
+                    let buffer = self.buffer.as_mut().unwrap();
+                    buffer.copy_interleaved_ref(decoded);
+                    return Ok(Some((spec.channels.count(), buffer.samples())));
+                }
+                //A corrupt packet is skipped, like players do
+                Err(Error::DecodeError(e)) => log::warn!("Skipped audio packet: {}", e),
+                Err(e) => {
+                    log::error!("Audio decode failed: {}", e);
+                    return Err(Rcode::Fail);
+                }
+            }
+        }
+    }
+
+    fn ts_to_frame(&self, ts: u64) -> u64 {
+        match self.time_base {
+            Some(base) => {
+                let time = base.calc_time(ts);
+                ((time.seconds as f64 + time.frac) * self.sample_rate as f64).round() as u64
+            }
+            None => ts,
+        }
+    }
+
+    /// Seeks close to `frame` and returns the frame decoding restarts at.
+    fn seek(&mut self, frame: u64) -> Result<u64, Rcode> {
+        let rate = self.sample_rate.max(1) as f64;
+        let seeked = self
+            .format
+            .seek(
+                SeekMode::Accurate,
+                SeekTo::Time {
+                    time: Time::from(frame as f64 / rate),
+                    track_id: Some(self.track_id),
+                },
+            )
+            .map_err(|_| Rcode::Fail)?;
+        self.decoder.reset();
+        Ok(self.ts_to_frame(seeked.actual_ts))
+    }
+}
+
+/// Decodes a whole WAV, OGG Vorbis, FLAC or MP3 file. `extension` helps pick
+/// the format, it is guessed from the contents otherwise.
+pub fn decode(source: &AudioSource, extension: Option<&str>) -> Result<Sound, Rcode> {
+    let mut decoder = PacketDecoder::open(source, extension)?;
+    let mut samples = vec![];
+    while let Some((_, block)) = decoder.next_block()? {
+        samples.extend_from_slice(block);
+    }
+    if decoder.channels == 0 || decoder.sample_rate == 0 {
+        return Err(Rcode::Fail);
+    }
+    Ok(Sound::new(samples, decoder.channels, decoder.sample_rate))
+}
+
+pub fn decode_file<P: AsRef<Path>>(path: P) -> Result<Sound, Rcode> {
+    decode(&AudioSource::File(path.as_ref().to_path_buf()), None)
+}
+
+pub fn decode_bytes(bytes: &[u8], extension: Option<&str>) -> Result<Sound, Rcode> {
+    decode(&AudioSource::Bytes(bytes.into()), extension)
+}
+
+/// Converts interleaved `samples` from one rate to another with cubic
+/// interpolation.
+pub fn resample(samples: &[f32], channels: u16, from: u32, to: u32) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if from == to || from == 0 || to == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let frames = samples.len() / channels;
+    let out_frames = (frames as u64 * to as u64 / from as u64) as usize;
+    let step = from as f64 / to as f64;
+    let at = |frame: isize, channel: usize| {
+        let frame = frame.clamp(0, frames as isize - 1) as usize;
+        samples[frame * channels + channel]
+    };
+    let mut out = Vec::with_capacity(out_frames * channels);
+    for i in 0..out_frames {
+        let pos = i as f64 * step;
+        let index = pos.floor() as isize;
+        let t = (pos - index as f64) as f32;
+        for c in 0..channels {
+            let (p0, p1, p2, p3) = (at(index - 1, c), at(index, c), at(index + 1, c), at(index + 2, c));
+            //Catmull-Rom
+            let a = -0.5 * p0 + 1.5 * p1 - 1.5 * p2 + 0.5 * p3;
+            let b = p0 - 2.5 * p1 + 2.0 * p2 - 0.5 * p3;
+            let c1 = -0.5 * p0 + 0.5 * p2;
+            out.insert(out.len(), ((a * t + b) * t + c1) * t + p1);
+        }
+    }
+    out
+}
+
+/// Decodes audio a little at a time while it plays, for music and other long
+/// sounds that would take a lot of memory decoded. Play it with
+/// `Emitter::play_stream`.
+pub struct SoundStream {
+    source: AudioSource,
+    extension: Option<String>,
+    decoder: PacketDecoder,
+    //Decoded frames as left and right, starting at frame `start`
+    buffer: VecDeque<(f32, f32)>,
+    start: u64,
+    ended: bool,
+}
+
+impl SoundStream {
+    pub fn open(source: AudioSource, extension: Option<&str>) -> Result<Self, Rcode> {
+        let mut decoder = PacketDecoder::open(&source, extension)?;
+        let mut buffer = VecDeque::new();
+        //Decode the first packet so the channels and rate are known
+        let ended = Self::decode_into(&mut decoder, &mut buffer)?;
+        Ok(Self {
+            source,
+            extension: extension.map(|e| e.to_string()),
+            decoder,
+            buffer,
+            start: 0,
+            ended,
+        })
+    }
+
+    pub fn open_file<P: AsRef<Path>>(path: P) -> Result<Self, Rcode> {
+        Self::open(AudioSource::File(path.as_ref().to_path_buf()), None)
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.decoder.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.decoder.sample_rate
+    }
+
+    /// Length in frames, if the file says.
+    pub fn frames(&self) -> Option<u64> {
+        self.decoder.frames
+    }
+
+    //Returns true at the end of the stream
+    fn decode_into(decoder: &mut PacketDecoder, buffer: &mut VecDeque<(f32, f32)>) -> Result<bool, Rcode> {
+        match decoder.next_block()? {
+            //The packet sets the channel count, it is unknown before the first
+            Some((channels, block)) => {
+                let channels = channels.max(1);
+                for frame in block.chunks_exact(channels) {
+                    buffer.push_back(match frame {
+                        [mono] => (*mono, *mono),
+                        [l, r, ..] => (*l, *r),
+                        _ => (0.0, 0.0),
+                    });
+                }
+                Ok(false)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Moves the read position to `frame`. Reopens the source if it can't
+    /// seek.
+    pub fn seek(&mut self, frame: u64) -> Result<(), Rcode> {
+        self.buffer.clear();
+        self.ended = false;
+        match self.decoder.seek(frame) {
+            Ok(start) => self.start = start.min(frame),
+            Err(_) => {
+                self.decoder = PacketDecoder::open(&self.source, self.extension.as_deref())?;
+                self.start = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Left and right at fractional frame `pos`, `None` past the end. Frames
+    /// before `pos` are dropped, so positions should only move forwards.
+    pub fn frame_at(&mut self, pos: f64) -> Option<(f32, f32)> {
+        let index = pos.max(0.0).floor() as u64;
+        if index < self.start {
+            self.seek(index).ok()?;
+        }
+        while !self.ended && self.start + (self.buffer.len() as u64) < index + 2 {
+            match Self::decode_into(&mut self.decoder, &mut self.buffer) {
+                Ok(ended) => self.ended = ended,
+                Err(_) => self.ended = true,
+            }
+        }
+        let drop = ((index - self.start) as usize).min(self.buffer.len());
+        self.buffer.drain(..drop);
+        self.start += drop as u64;
+        if index != self.start {
+            return None;
+        }
+        let (l0, r0) = *self.buffer.front()?;
+        let (l1, r1) = self.buffer.get(1).copied().unwrap_or((l0, r0));
+        let t = (pos - index as f64) as f32;
+        Some((l0 + (l1 - l0) * t, r0 + (r1 - r0) * t))
+    }
+}
+
+/// Encoded audio kept in memory and decoded while it plays. Load it through
+/// the `AssetServer` for music, `Sound` decodes the whole file up front.
+pub struct EncodedSound {
+    pub bytes: Arc<[u8]>,
+    pub extension: Option<String>,
+}
+
+impl EncodedSound {
+    pub fn stream(&self) -> Result<SoundStream, Rcode> {
+        SoundStream::open(AudioSource::Bytes(self.bytes.clone()), self.extension.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::wav_bytes;
+
+    //Stereo ramp, left rising and right falling
+    fn ramp(frames: usize) -> Vec<f32> {
+        (0..frames).flat_map(|i| [i as f32 / frames as f32, -(i as f32) / frames as f32]).collect()
+    }
+
+    #[test]
+    fn wav_round_trips() {
+        let samples = ramp(1000);
+        let sound = decode_bytes(&wav_bytes(&samples, 2, 22050), Some("wav")).unwrap();
+        assert_eq!(sound.channels(), 2);
+        assert_eq!(sound.sample_rate(), 22050);
+        assert_eq!(sound.samples(), &samples[..]);
+        assert!(decode_bytes(b"not audio", Some("wav")).is_err());
+    }
+
+    #[test]
+    fn resample_length_and_same_rate() {
+        let samples = ramp(1000);
+        assert_eq!(resample(&samples, 2, 44100, 44100), samples);
+        let up = resample(&samples, 2, 22050, 44100);
+        assert_eq!(up.len(), 2000 * 2);
+        let down = resample(&samples, 2, 44100, 22050);
+        assert_eq!(down.len(), 500 * 2);
+        //Every other output frame lands on an input frame when halving
+        assert!((down[20] - samples[40]).abs() < 1e-6);
+        assert!((down[21] - samples[41]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn stream_seeks() {
+        let samples = ramp(4000);
+        let frame = |i: usize| (samples[i * 2], samples[i * 2 + 1]);
+        let bytes: Arc<[u8]> = wav_bytes(&samples, 2, 22050).into();
+        let mut stream = SoundStream::open(AudioSource::Bytes(bytes), Some("wav")).unwrap();
+        assert_eq!(stream.channels(), 2);
+        assert_eq!(stream.frames(), Some(4000));
+        assert_eq!(stream.frame_at(10.0), Some(frame(10)));
+        let (l, r) = stream.frame_at(2500.5).unwrap();
+        assert!((l - (frame(2500).0 + frame(2501).0) / 2.0).abs() < 1e-6);
+        assert!((r - (frame(2500).1 + frame(2501).1) / 2.0).abs() < 1e-6);
+        //Going back seeks
+        assert_eq!(stream.frame_at(100.0), Some(frame(100)));
+        stream.seek(3000).unwrap();
+        assert_eq!(stream.frame_at(3000.0), Some(frame(3000)));
+        assert_eq!(stream.frame_at(4000.0), None);
+    }
+}
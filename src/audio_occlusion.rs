@@ -0,0 +1,328 @@
+use super::{
+    collision::World,
+    geometry::{Mesh, MeshType},
+    math_3d::Vector3,
+    transform::Transform3,
+};
+use rapier3d::parry::query::RayCast;
+use rapier3d::prelude::{point, vector, Ray};
+
+/// How a surface lets sound through. Colliders pick theirs with their
+/// `user_data`, an index into `OcclusionSettings::materials`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AudioMaterial {
+    /// Fraction of sound lost passing through one surface, 0 to 1.
+    pub absorption: f32,
+    /// Fraction lost per meter travelled inside a collider.
+    pub absorption_per_meter: f32,
+}
+
+impl Default for AudioMaterial {
+    fn default() -> Self {
+        Self {
+            absorption: 0.5,
+            absorption_per_meter: 0.5,
+        }
+    }
+}
+
+impl AudioMaterial {
+    pub fn new(absorption: f32, absorption_per_meter: f32) -> Self {
+        Self {
+            absorption,
+            absorption_per_meter,
+        }
+    }
+
+    /// Sound left after crossing `thickness` meters of it.
+    pub fn transmission(&self, thickness: f32) -> f32 {
+        let surface = 1.0 - self.absorption.clamp(0.0, 1.0);
+        let inside = (1.0 - self.absorption_per_meter.clamp(0.0, 1.0)).powf(thickness.max(0.0));
+        surface * inside
+    }
+}
+
+/// Something a ray crossed on its way to an emitter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OcclusionHit {
+    pub material: usize,
+    /// Meters travelled inside, 0 for a single triangle.
+    pub thickness: f32,
+}
+
+/// Geometry that blocks sound.
+pub trait OcclusionGeometry {
+    /// What lies on the segment between `from` and `to`.
+    fn hits(&self, from: Vector3, to: Vector3) -> Vec<OcclusionHit>;
+}
+
+//Surface crossings followed per collider and ray
+const MAX_CROSSINGS: usize = 32;
+//Distance moved past a crossing before looking for the next
+const CROSSING_STEP: f32 = 1e-4;
+
+impl OcclusionGeometry for World<'_> {
+    fn hits(&self, from: Vector3, to: Vector3) -> Vec<OcclusionHit> {
+        let length = (to - from).length();
+        if length <= f32::EPSILON {
+            return vec![];
+        }
+        let dir = (to - from) / length;
+        let ray_from = |p: Vector3, d: Vector3| Ray::new(point![p.x, p.y, p.z], vector![d.x, d.y, d.z]);
+        let forward = ray_from(from, dir);
+        let back = ray_from(to, dir * -1.0);
+        let mut hits = vec![];
+        for (_, collider) in self.colliders().iter() {
+            if collider.is_sensor() {
+                continue;
+            }
+            let position = collider.position();
+            let shape = collider.shape();
+            //Something the listener or emitter is inside doesn't block them
+            match shape.cast_ray(position, &forward, length, true) {
+                Some(toi) if toi > 0.0 => {}
+                _ => continue,
+            }
+            if shape.cast_ray(position, &back, length, true) == Some(0.0) {
+                continue;
+            }
+            //Every surface crossing along the segment, so concave shapes and
+            //ones crossed more than once count only the parts inside
+            let mut crossings = vec![];
+            let mut t = 0.0;
+            while crossings.len() < MAX_CROSSINGS {
+                let ray = ray_from(from + dir * t, dir);
+                match shape.cast_ray(position, &ray, length - t, false) {
+                    Some(toi) => {
+                        t += toi;
+                        crossings.insert(crossings.len(), t);
+                        t += CROSSING_STEP;
+                    }
+                    None => break,
+                }
+                if t >= length {
+                    break;
+                }
+            }
+            //Entries pair with the exit after them, an open surface has none
+            for pair in crossings.chunks(2) {
+                hits.insert(
+                    hits.len(),
+                    OcclusionHit {
+                        material: collider.user_data as usize,
+                        thickness: pair.get(1).map_or(0.0, |exit| exit - pair[0]),
+                    },
+                );
+            }
+        }
+        hits
+    }
+}
+
+/// Triangles from meshes, for levels without colliders.
+#[derive(Clone, Debug, Default)]
+pub struct MeshOccluder {
+    triangles: Vec<([Vector3; 3], usize)>,
+}
+
+impl MeshOccluder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mesh(mut self, mesh: &Mesh, transform: &Transform3, material: usize) -> Self {
+        self.add_mesh(mesh, transform, material);
+        self
+    }
+
+    /// Adds the triangles of `mesh` placed at `transform`.
+    pub fn add_mesh(&mut self, mesh: &Mesh, transform: &Transform3, material: usize) {
+        let place = |p: Vector3| transform.rot * (p * transform.scale) + transform.pos;
+        match &mesh.mesh_type {
+            MeshType::NonIndexed(triangles) => {
+                for tri in triangles {
+                    let corners = [place(tri.v[0].position), place(tri.v[1].position), place(tri.v[2].position)];
+                    self.triangles.insert(self.triangles.len(), (corners, material));
+                }
+            }
+            MeshType::Indexed(vertices, indices) => {
+                for tri in indices.chunks_exact(3) {
+                    let corner = |i: u32| place(vertices[i as usize].position);
+                    let corners = [corner(tri[0]), corner(tri[1]), corner(tri[2])];
+                    self.triangles.insert(self.triangles.len(), (corners, material));
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.triangles.clear();
+    }
+}
+
+//Möller–Trumbore, distance along `dir` to the triangle
+fn ray_triangle(origin: Vector3, dir: Vector3, [a, b, c]: &[Vector3; 3]) -> Option<f32> {
+    let (e1, e2) = (*b - *a, *c - *a);
+    let p = dir.cross(e2);
+    let det = e1.dot(p);
+    if det.abs() < 1e-7 {
+        return None;
+    }
+    let t_vec = origin - *a;
+    let u = t_vec.dot(p) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = t_vec.cross(e1);
+    let v = dir.dot(q) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    Some(e2.dot(q) / det)
+}
+
+impl OcclusionGeometry for MeshOccluder {
+    fn hits(&self, from: Vector3, to: Vector3) -> Vec<OcclusionHit> {
+        let length = (to - from).length();
+        if length <= f32::EPSILON {
+            return vec![];
+        }
+        let dir = (to - from) / length;
+        self.triangles
+            .iter()
+            .filter(|(tri, _)| matches!(ray_triangle(from, dir, tri), Some(t) if t > 0.0 && t < length))
+            .map(|(_, material)| OcclusionHit {
+                material: *material,
+                thickness: 0.0,
+            })
+            .collect()
+    }
+}
+
+/// How emitters are occluded, see `AudioSystem::update_occlusion`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OcclusionSettings {
+    pub enabled: bool,
+    /// Times a second the rays are cast.
+    pub update_rate: f32,
+    /// Rays cast to points around each emitter, 1 to 7. More give partial
+    /// occlusion around corners.
+    pub rays: usize,
+    /// Meters from the emitter the extra rays aim at.
+    pub emitter_radius: f32,
+    /// Indexed by `OcclusionHit::material`, the first is used for unknown
+    /// indices.
+    pub materials: Vec<AudioMaterial>,
+    /// Low-pass cutoff in Hz of a fully muffled emitter.
+    pub muffled_cutoff: f32,
+    /// Seconds to move to a new occlusion value.
+    pub smoothing: f32,
+}
+
+impl Default for OcclusionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            update_rate: 10.0,
+            rays: 5,
+            emitter_radius: 0.5,
+            materials: vec![AudioMaterial::default()],
+            muffled_cutoff: 600.0,
+            smoothing: 0.1,
+        }
+    }
+}
+
+/// How blocked an emitter is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Occlusion {
+    /// Fraction of rays that hit something, 0 to 1.
+    pub occlusion: f32,
+    /// Sound passing through what's in the way, 0 to 1.
+    pub transmission: f32,
+}
+
+impl Default for Occlusion {
+    fn default() -> Self {
+        Self {
+            occlusion: 0.0,
+            transmission: 1.0,
+        }
+    }
+}
+
+impl Occlusion {
+    //How far the sound is muffled, 0 to 1
+    fn muffle(&self) -> f32 {
+        self.occlusion * (1.0 - self.transmission)
+    }
+
+    pub fn gain(&self) -> f32 {
+        1.0 - self.muffle()
+    }
+
+    /// Low-pass cutoff, falling from `open` to `muffled` in octaves.
+    pub fn cutoff(&self, open: f32, muffled: f32) -> f32 {
+        open * (muffled / open).powf(self.muffle())
+    }
+
+    /// Casts `settings.rays` rays from `listener` to around `emitter`.
+    /// `right` and `up` spread the extra rays. An emitter at the listener
+    /// is never occluded.
+    pub fn measure(
+        geometry: &dyn OcclusionGeometry,
+        settings: &OcclusionSettings,
+        listener: Vector3,
+        emitter: Vector3,
+        right: Vector3,
+        up: Vector3,
+    ) -> Self {
+        if (emitter - listener).length() <= f32::EPSILON {
+            return Self::default();
+        }
+        let forward = (emitter - listener).normal();
+        let r = settings.emitter_radius;
+        let offsets = [
+            Vector3::default(),
+            right * r,
+            right * -r,
+            up * r,
+            up * -r,
+            forward * r,
+            forward * -r,
+        ];
+        let rays = settings.rays.clamp(1, offsets.len());
+        let mut blocked = 0;
+        let mut transmission = 0.0;
+        for offset in offsets.iter().take(rays) {
+            let hits = geometry.hits(listener, emitter + *offset);
+            if hits.is_empty() {
+                continue;
+            }
+            blocked += 1;
+            transmission += hits.iter().fold(1.0, |t, hit| {
+                let material = settings
+                    .materials
+                    .get(hit.material)
+                    .or_else(|| settings.materials.first())
+                    .copied()
+                    .unwrap_or_default();
+                t * material.transmission(hit.thickness)
+            });
+        }
+        if blocked == 0 {
+            return Self::default();
+        }
+        Self {
+            occlusion: blocked as f32 / rays as f32,
+            transmission: transmission / blocked as f32,
+        }
+    }
+
+    /// Moves towards `target` by `amount`, 0 to 1.
+    pub fn approach(&mut self, target: &Occlusion, amount: f32) {
+        let amount = amount.clamp(0.0, 1.0);
+        self.occlusion += (target.occlusion - self.occlusion) * amount;
+        self.transmission += (target.transmission - self.transmission) * amount;
+    }
+}
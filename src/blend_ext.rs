@@ -0,0 +1,704 @@
+use super::{
+    game_object::{GameObject, Property},
+    geometry::*,
+    math_3d::*,
+    og_engine::Rcode,
+    pixel::Pixel,
+    sprite::Sprite,
+    transform::*,
+    util::{AutoLoader, ImageLoader},
+};
+use blend::{Blend, Instance};
+use std::collections::HashMap;
+
+//Blender object type for meshes (OB_MESH)
+const OB_MESH: i16 = 1;
+//CustomData layer types
+const CD_MLOOPUV: i32 = 16;
+const CD_PROP_BYTE_COLOR: i32 = 17;
+const CD_PROP_FLOAT2: i32 = 49;
+//IDProperty types
+const IDP_STRING: u8 = 0;
+const IDP_INT: u8 = 1;
+const IDP_FLOAT: u8 = 2;
+const IDP_ARRAY: u8 = 5;
+const IDP_GROUP: u8 = 6;
+const IDP_DOUBLE: u8 = 8;
+
+/// Loads every object in a .blend file. Objects are returned as a hierarchy
+/// (children are nested under their parent) together with the images their
+/// materials reference. `PBRTexture` indices point into the returned images.
+pub fn get_game_objects(data: &[u8]) -> Result<(Vec<GameObject>, Vec<Sprite>), Rcode> {
+    let blend = Blend::new(std::io::Cursor::new(data)).map_err(|_| Rcode::Fail)?;
+    process_blend(&blend, None)
+}
+
+pub fn get_game_objects_from_file(path: &str) -> Result<(Vec<GameObject>, Vec<Sprite>), Rcode> {
+    let blend_path = std::path::Path::new(path);
+    if !blend_path.exists() {
+        return Err(Rcode::NoFile);
+    }
+    let blend = Blend::from_path(blend_path).map_err(|_| Rcode::Fail)?;
+    process_blend(&blend, blend_path.parent())
+}
+
+fn process_blend(
+    blend: &Blend,
+    base_dir: Option<&std::path::Path>,
+) -> Result<(Vec<GameObject>, Vec<Sprite>), Rcode> {
+    let mut images = ImageCache::new(base_dir);
+    let mut objects: Vec<(String, Option<String>, GameObject)> = blend
+        .instances_with_code(*b"OB")
+        .map(|obj| {
+            let name = id_name(&obj);
+            let parent = if obj.is_valid("parent") {
+                Some(id_name(&obj.get("parent")))
+            } else {
+                None
+            };
+            Ok((name, parent, process_object(&obj, &mut images)?))
+        })
+        .collect::<Result<_, Rcode>>()?;
+
+    //Nest children under their parents. Blender only allows a single parent,
+    // so we can keep attaching leaves until nothing is left to move.
+    loop {
+        let leaf = objects.iter().position(|(name, parent, _)| {
+            parent.is_some()
+                && !objects
+                    .iter()
+                    .any(|(_, other_parent, _)| other_parent.as_ref() == Some(name))
+        });
+        match leaf {
+            Some(i) => {
+                let (_, parent, child) = objects.remove(i);
+                let parent = parent.unwrap();
+                if let Some((_, _, parent_obj)) = objects.iter_mut().find(|(n, _, _)| *n == parent) {
+                    parent_obj.children.insert(parent_obj.children.len(), child);
+                } else {
+                    //Parent isn't in this file (linked library), keep it at the root
+                    objects.insert(objects.len(), (child.name.clone(), None, child));
+                }
+            }
+            None => break,
+        }
+    }
+
+    Ok((
+        objects.into_iter().map(|(_, _, go)| go).collect(),
+        images.sprites,
+    ))
+}
+
+//Blender prefixes every ID name with its two letter code ("OBCube")
+fn id_name(inst: &Instance) -> String {
+    let name = inst.get("id").get_string("name");
+    name.get(2..).unwrap_or_default().to_string()
+}
+
+//Blender is Z up, the engine is Y up
+fn to_y_up(v: [f32; 3]) -> Vector3 {
+    Vector3::new(v[0], v[2], -v[1])
+}
+
+pub fn process_object(obj: &Instance, images: &mut ImageCache) -> Result<GameObject, Rcode> {
+    let transform = object_transform(obj);
+
+    let meshes = if obj.get_i16("type") == OB_MESH
+        && obj.is_valid("data")
+        && obj.get("data").code()[0..=1] == *b"ME"
+    {
+        extract_meshes(&obj.get("data"), images)?
+    } else {
+        vec![]
+    };
+
+    let mut out_object = GameObject::new(transform, None, meshes);
+    out_object.name = id_name(obj);
+    let id = obj.get("id");
+    if id.is_valid("properties") {
+        if let Some(Property::Group(props)) = read_property(&id.get("properties")) {
+            out_object.properties = props;
+        }
+    }
+    Ok(out_object)
+}
+
+//Transform relative to the parent. Blender keeps the parent's inverse world
+// matrix from the moment of parenting in parentinv, the child's local space
+// is parentinv * basis.
+fn object_transform(obj: &Instance) -> Transform3 {
+    let rotation = match obj.get_i16("rotmode") {
+        ROT_MODE_QUAT => BlenderRotation::Quat(vec4(&obj.get_f32_vec("quat"), [1.0, 0.0, 0.0, 0.0])),
+        ROT_MODE_AXIS_ANGLE => {
+            let axis = vec3(&obj.get_f32_vec("rotAxis"), [0.0, 1.0, 0.0]);
+            BlenderRotation::AxisAngle(axis, obj.get_f32("rotAngle"))
+        }
+        mode => BlenderRotation::Euler(vec3(&obj.get_f32_vec("rot"), [0.0; 3]), mode),
+    };
+    let inv = obj.get_f32_vec("parentinv");
+    let parentinv = match inv.len() {
+        16 if obj.is_valid("parent") => Some([
+            [inv[0], inv[1], inv[2], inv[3]],
+            [inv[4], inv[5], inv[6], inv[7]],
+            [inv[8], inv[9], inv[10], inv[11]],
+            [inv[12], inv[13], inv[14], inv[15]],
+        ]),
+        _ => None,
+    };
+    local_transform(
+        vec3(&obj.get_f32_vec("loc"), [0.0; 3]),
+        &rotation,
+        vec3(&obj.get_f32_vec("size"), [1.0; 3]),
+        parentinv,
+    )
+}
+
+fn vec3(v: &[f32], default: [f32; 3]) -> [f32; 3] {
+    v.try_into().unwrap_or(default)
+}
+
+fn vec4(v: &[f32], default: [f32; 4]) -> [f32; 4] {
+    v.try_into().unwrap_or(default)
+}
+
+//Object.rotmode
+const ROT_MODE_QUAT: i16 = 0;
+const ROT_MODE_AXIS_ANGLE: i16 = -1;
+//Axes of the Euler modes 1 to 6 (XYZ, XZY, YXZ, YZX, ZXY, ZYX), applied
+// first to last
+const EULER_ORDERS: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+
+//An object's rotation as Blender stores it for its rotmode
+enum BlenderRotation {
+    //Angles around x, y and z with the rotmode
+    Euler([f32; 3], i16),
+    //w, x, y, z
+    Quat([f32; 4]),
+    AxisAngle([f32; 3], f32),
+}
+
+impl BlenderRotation {
+    fn quaternion(&self) -> cgmath::Quaternion<f32> {
+        use cgmath::{InnerSpace, One, Quaternion, Rad, Rotation3, Vector3 as V3};
+        match self {
+            BlenderRotation::Euler(angles, mode) => {
+                let order = EULER_ORDERS[(*mode).clamp(1, 6) as usize - 1];
+                let axes = [V3::unit_x(), V3::unit_y(), V3::unit_z()];
+                //XYZ is Rz * Ry * Rx, the first axis is applied first
+                order.iter().fold(Quaternion::one(), |q, axis| {
+                    Quaternion::from_axis_angle(axes[*axis], Rad(angles[*axis])) * q
+                })
+            }
+            BlenderRotation::Quat([w, x, y, z]) => {
+                let q = Quaternion::new(*w, *x, *y, *z);
+                if q.magnitude2() > f32::EPSILON {
+                    q.normalize()
+                } else {
+                    Quaternion::one()
+                }
+            }
+            BlenderRotation::AxisAngle(axis, angle) => {
+                let axis = V3::from(*axis);
+                if axis.magnitude2() > f32::EPSILON {
+                    Quaternion::from_axis_angle(axis.normalize(), Rad(*angle))
+                } else {
+                    Quaternion::one()
+                }
+            }
+        }
+    }
+}
+
+//Converts Blender's z-up loc, rotation and size, with the parent inverse
+// when parented, to a y-up transform
+fn local_transform(
+    loc: [f32; 3],
+    rotation: &BlenderRotation,
+    size: [f32; 3],
+    parentinv: Option<[[f32; 4]; 4]>,
+) -> Transform3 {
+    use cgmath::{InnerSpace, Matrix3, Matrix4, Quaternion};
+    let q = rotation.quaternion();
+    let mut matrix = Matrix4::from_translation(cgmath::Vector3::from(loc))
+        * Matrix4::from(q)
+        * Matrix4::from_nonuniform_scale(size[0], size[1], size[2]);
+    if let Some(parentinv) = parentinv {
+        matrix = Matrix4::from(parentinv) * matrix;
+    }
+
+    let (x, y, z) = (matrix.x.truncate(), matrix.y.truncate(), matrix.z.truncate());
+    let scale = [x.magnitude(), y.magnitude(), z.magnitude()];
+    let q = if scale.iter().all(|s| *s > f32::EPSILON) {
+        Quaternion::from(Matrix3::from_cols(x / scale[0], y / scale[1], z / scale[2])).normalize()
+    } else {
+        q
+    };
+    let pos = matrix.w;
+    Transform3 {
+        pos: to_y_up([pos.x, pos.y, pos.z]),
+        rot: Rotor3::from_quat([q.v.x, q.v.z, -q.v.y, q.s]),
+        scale: Vector3::new(scale[0], scale[2], scale[1]),
+    }
+}
+
+/// Splits a Blender mesh into one `Mesh` per material slot. Every face corner
+/// gets its own vertex so per-corner UVs and colors survive.
+pub fn extract_meshes(mesh: &Instance, images: &mut ImageCache) -> Result<Vec<Mesh>, Rcode> {
+    let topology = match MeshTopology::read(mesh)? {
+        Some(topology) => topology,
+        None => return Ok(vec![]),
+    };
+    let uvs = loop_layer(mesh, CD_MLOOPUV, Some("mloopuv"))
+        .map(|layer| layer.map(|uv| uv.get_f32_vec("uv")).map(|uv| (uv[0], uv[1])).collect::<Vec<_>>())
+        //Blender 3.5+ stores UV maps as generic float2 attributes
+        .or_else(|| {
+            loop_layer(mesh, CD_PROP_FLOAT2, None)
+                .map(|layer| layer.map(|uv| (uv.get_f32("x"), uv.get_f32("y"))).collect::<Vec<_>>())
+        })
+        .map(|uvs| {
+            uvs.into_iter()
+                .map(|(u, v)| UV { u, v: 1.0 - v, w: 0.0 })
+                .collect::<Vec<UV>>()
+        })
+        .unwrap_or_default();
+    let colors = loop_layer(mesh, CD_PROP_BYTE_COLOR, Some("mloopcol"))
+        .map(|layer| {
+            layer
+                .map(|c| Pixel::rgba(c.get_u8("r"), c.get_u8("g"), c.get_u8("b"), c.get_u8("a")))
+                .collect::<Vec<Pixel>>()
+        })
+        .unwrap_or_default();
+
+    let materials: Vec<Option<Instance>> = if mesh.is_valid("mat") {
+        mesh.get_iter("mat").map(Some).collect()
+    } else {
+        vec![]
+    };
+
+    let mut by_material: HashMap<usize, (Vec<Vertex>, Vec<u32>)> = HashMap::new();
+    for &(start, count, mat) in &topology.faces {
+        let corners = topology.corner_verts.get(start..start + count).ok_or(Rcode::Fail)?;
+        let positions = corners
+            .iter()
+            .map(|v| topology.verts.get(*v).copied().ok_or(Rcode::Fail))
+            .collect::<Result<Vec<[f32; 3]>, Rcode>>()?;
+        let (vertices, indices) = by_material.entry(mat).or_insert_with(|| (vec![], vec![]));
+        let first = vertices.len() as u32;
+        for (i, position) in positions.iter().enumerate() {
+            let mut vertex: Vertex = position.into();
+            if let Some(uv) = uvs.get(start + i) {
+                vertex.tex_coords = *uv;
+            }
+            if let Some(color) = colors.get(start + i) {
+                vertex.color = *color;
+            }
+            vertices.insert(vertices.len(), vertex);
+        }
+        for [a, b, c] in triangulate(&positions) {
+            indices.extend_from_slice(&[first + a as u32, first + c as u32, first + b as u32]);
+        }
+    }
+
+    let mut by_material: Vec<(usize, (Vec<Vertex>, Vec<u32>))> = by_material.into_iter().collect();
+    by_material.sort_by_key(|(mat, _)| *mat);
+    Ok(by_material
+        .into_iter()
+        .filter(|(_, (_, indices))| !indices.is_empty())
+        .map(|(mat, (vertices, indices))| {
+            let mut new_mesh: Mesh = (vertices, indices).into();
+            if let Some(Some(material)) = materials.get(mat) {
+                if let Some(image_index) = material_image(material, images) {
+                    new_mesh.textures.insert(0, PBRTexture::Color(image_index));
+                }
+            }
+            new_mesh.calculate_normals(NormalMode::Shaded);
+            new_mesh
+        })
+        .collect())
+}
+
+//Vertex positions, the vertex of every face corner and (first corner, corner
+// count, material) for every face
+struct MeshTopology {
+    verts: Vec<[f32; 3]>,
+    corner_verts: Vec<usize>,
+    faces: Vec<(usize, usize, usize)>,
+}
+
+impl MeshTopology {
+    //None for a mesh without faces, Err for a layout we can't read
+    fn read(mesh: &Instance) -> Result<Option<Self>, Rcode> {
+        if mesh.is_valid("mpoly") && mesh.is_valid("mloop") && mesh.is_valid("mvert") {
+            return Ok(Some(Self::read_legacy(mesh)));
+        }
+        //Blender 3.6 renamed the offsets to poly_offset_indices, 4.0 to face_offset_indices
+        let offsets = ["face_offset_indices", "poly_offset_indices"]
+            .into_iter()
+            .find(|field| mesh.is_valid(field));
+        match offsets {
+            Some(offsets) => Self::read_attributes(mesh, offsets).map(Some),
+            None if mesh.is_valid("mpoly") || mesh.is_valid("mloop") => Err(Rcode::Fail),
+            None => Ok(None),
+        }
+    }
+
+    fn read_legacy(mesh: &Instance) -> Self {
+        let verts = mesh
+            .get_iter("mvert")
+            .map(|v| {
+                let co = v.get_f32_vec("co");
+                to_y_up([co[0], co[1], co[2]]).into()
+            })
+            .collect();
+        let corner_verts = mesh
+            .get_iter("mloop")
+            .map(|l| l.get_i32("v").max(0) as usize)
+            .collect();
+        let faces = mesh
+            .get_iter("mpoly")
+            .map(|poly| {
+                (
+                    poly.get_i32("loopstart").max(0) as usize,
+                    poly.get_i32("totloop").max(0) as usize,
+                    poly.get_i16("mat_nr").max(0) as usize,
+                )
+            })
+            .collect();
+        Self {
+            verts,
+            corner_verts,
+            faces,
+        }
+    }
+
+    //Blender 3.6+ keeps positions, corner vertices and material indices as
+    // named attributes and faces as an array of corner offsets
+    fn read_attributes(mesh: &Instance, offsets: &str) -> Result<Self, Rcode> {
+        let verts = named_layer(mesh, &["vert_data", "vdata"], "position")
+            .ok_or(Rcode::Fail)?
+            .get_iter("data")
+            .map(|co| to_y_up([co.get_f32("x"), co.get_f32("y"), co.get_f32("z")]).into())
+            .collect();
+        let corner_verts = named_layer(mesh, &["corner_data", "ldata"], ".corner_vert")
+            .ok_or(Rcode::Fail)?
+            .get_i32_vec("data")
+            .into_iter()
+            .map(|v| v.max(0) as usize)
+            .collect();
+        let materials = named_layer(mesh, &["face_data", "pdata"], "material_index")
+            .map(|layer| layer.get_i32_vec("data"))
+            .unwrap_or_default();
+        //One more offset than faces, the last one is the corner count
+        let offsets = mesh.get_i32_vec(offsets);
+        let faces = offsets
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| {
+                if w[0] < 0 || w[1] < w[0] {
+                    return Err(Rcode::Fail);
+                }
+                let mat = materials.get(i).copied().unwrap_or(0).max(0) as usize;
+                Ok((w[0] as usize, (w[1] - w[0]) as usize, mat))
+            })
+            .collect::<Result<_, Rcode>>()?;
+        Ok(Self {
+            verts,
+            corner_verts,
+            faces,
+        })
+    }
+}
+
+//Finds a layer by name in the first CustomData block of `blocks` the mesh has
+fn named_layer<'a>(mesh: &Instance<'a>, blocks: &[&str], name: &str) -> Option<Instance<'a>> {
+    let block = blocks.iter().find(|block| mesh.is_valid(block))?;
+    let data = mesh.get(block);
+    if !data.is_valid("layers") {
+        return None;
+    }
+    data.get_iter("layers")
+        .find(|layer| layer.get_string("name") == name && layer.is_valid("data"))
+}
+
+//Ear clipping, so concave n-gons come out right. Returns corner indices in
+// the polygon's winding.
+fn triangulate(positions: &[[f32; 3]]) -> Vec<[usize; 3]> {
+    let count = positions.len();
+    if count < 3 {
+        return vec![];
+    }
+    if count == 3 {
+        return vec![[0, 1, 2]];
+    }
+    //Project onto the plane the polygon faces most (Newell's normal)
+    let mut normal = [0.0f32; 3];
+    for i in 0..count {
+        let (a, b) = (positions[i], positions[(i + 1) % count]);
+        normal[0] += (a[1] - b[1]) * (a[2] + b[2]);
+        normal[1] += (a[2] - b[2]) * (a[0] + b[0]);
+        normal[2] += (a[0] - b[0]) * (a[1] + b[1]);
+    }
+    let axis = (0..3)
+        .max_by(|a, b| normal[*a].abs().total_cmp(&normal[*b].abs()))
+        .unwrap_or(2);
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    let flip = if normal[axis] < 0.0 { -1.0 } else { 1.0 };
+    let points: Vec<(f32, f32)> = positions.iter().map(|p| (p[u], p[v] * flip)).collect();
+
+    let cross = |a: (f32, f32), b: (f32, f32), c: (f32, f32)| {
+        (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+    };
+    let mut remaining: Vec<usize> = (0..count).collect();
+    let mut triangles = Vec::with_capacity(count - 2);
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|i| {
+            let (a, b, c) = (remaining[(i + n - 1) % n], remaining[*i], remaining[(i + 1) % n]);
+            let (pa, pb, pc) = (points[a], points[b], points[c]);
+            cross(pa, pb, pc) > 0.0
+                && !remaining.iter().any(|&p| {
+                    p != a
+                        && p != b
+                        && p != c
+                        && cross(pa, pb, points[p]) >= 0.0
+                        && cross(pb, pc, points[p]) >= 0.0
+                        && cross(pc, pa, points[p]) >= 0.0
+                })
+        });
+        match ear {
+            Some(i) => {
+                triangles.insert(triangles.len(), [remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]]);
+                remaining.remove(i);
+            }
+            //Degenerate or self intersecting, fan what's left
+            None => break,
+        }
+    }
+    for i in 1..remaining.len() - 1 {
+        triangles.insert(triangles.len(), [remaining[0], remaining[i], remaining[i + 1]]);
+    }
+    triangles
+}
+
+//Returns the active loop layer of the given CustomData type, falling back to
+// the legacy mesh field for files written before the attribute system.
+fn loop_layer<'a>(
+    mesh: &Instance<'a>,
+    layer_type: i32,
+    legacy: Option<&str>,
+) -> Option<Box<dyn Iterator<Item = Instance<'a>> + 'a>> {
+    //Blender 4.0 renamed ldata to corner_data
+    if let Some(block) = ["corner_data", "ldata"].into_iter().find(|block| mesh.is_valid(block)) {
+        let ldata = mesh.get(block);
+        if ldata.is_valid("layers") {
+            let layers: Vec<Instance<'a>> = ldata
+                .get_iter("layers")
+                .filter(|l| l.get_i32("type") == layer_type && l.is_valid("data"))
+                .collect();
+            //"active" is the offset of the active layer among layers of the same type
+            let active = layers
+                .first()
+                .map(|l| l.get_i32("active").max(0) as usize)
+                .unwrap_or_default();
+            if let Some(layer) = layers.into_iter().nth(active) {
+                return Some(Box::new(layer.get_iter("data")));
+            }
+        }
+    }
+    match legacy {
+        Some(legacy) if mesh.is_valid(legacy) => Some(Box::new(mesh.get_iter(legacy))),
+        _ => None,
+    }
+}
+
+//Finds the first image texture node feeding a material
+fn material_image(material: &Instance, images: &mut ImageCache) -> Option<usize> {
+    if !material.is_valid("nodetree") {
+        return None;
+    }
+    let tree = material.get("nodetree");
+    if !tree.is_valid("nodes") {
+        return None;
+    }
+    tree.get_iter("nodes")
+        .filter(|node| node.get_string("idname") == "ShaderNodeTexImage" && node.is_valid("id"))
+        .find_map(|node| images.get(&node.get("id")))
+}
+
+pub struct ImageCache<'p> {
+    base_dir: Option<&'p std::path::Path>,
+    names: HashMap<String, Option<usize>>,
+    pub sprites: Vec<Sprite>,
+}
+
+impl<'p> ImageCache<'p> {
+    pub fn new(base_dir: Option<&'p std::path::Path>) -> Self {
+        Self {
+            base_dir,
+            names: HashMap::new(),
+            sprites: vec![],
+        }
+    }
+
+    pub fn get(&mut self, image: &Instance) -> Option<usize> {
+        let name = id_name(image);
+        if let Some(index) = self.names.get(&name) {
+            return *index;
+        }
+        let sprite = if image.is_valid("packedfile") {
+            AutoLoader::load_image_from_bytes(&image.get("packedfile").get_u8_vec("data")).ok()
+        } else {
+            //Blender paths starting with "//" are relative to the .blend file
+            let file_path = image.get_string("filepath");
+            let relative = file_path.trim_start_matches("//");
+            let full_path = match self.base_dir {
+                Some(dir) if file_path.starts_with("//") => dir.join(relative),
+                _ => std::path::PathBuf::from(relative),
+            };
+            AutoLoader::load_image_resource(&full_path.to_string_lossy()).ok()
+        };
+        let index = sprite.map(|spr| {
+            self.sprites.insert(self.sprites.len(), spr);
+            self.sprites.len() - 1
+        });
+        self.names.insert(name, index);
+        index
+    }
+}
+
+fn read_property(prop: &Instance) -> Option<Property> {
+    let data = prop.get("data");
+    match prop.get_u8("type") {
+        IDP_STRING => Some(Property::String(
+            String::from_utf8_lossy(&data.get_u8_vec("pointer"))
+                .trim_end_matches('\0')
+                .to_string(),
+        )),
+        IDP_INT => Some(Property::Int(data.get_i32("val"))),
+        IDP_FLOAT => Some(Property::Float(f32::from_bits(data.get_i32("val") as u32) as f64)),
+        //Doubles are stored across val (low word) and val2 (high word)
+        IDP_DOUBLE => Some(Property::Float(f64::from_bits(
+            (data.get_i32("val2") as u32 as u64) << 32 | data.get_i32("val") as u32 as u64,
+        ))),
+        IDP_ARRAY => {
+            let len = prop.get_i32("len").max(0) as usize;
+            let raw = data.get_u8_vec("pointer");
+            let values = match prop.get_u8("subtype") {
+                IDP_INT => raw
+                    .chunks_exact(4)
+                    .take(len)
+                    .map(|c| Property::Int(i32::from_le_bytes([c[0], c[1], c[2], c[3]])))
+                    .collect(),
+                IDP_FLOAT => raw
+                    .chunks_exact(4)
+                    .take(len)
+                    .map(|c| Property::Float(f32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f64))
+                    .collect(),
+                IDP_DOUBLE => raw
+                    .chunks_exact(8)
+                    .take(len)
+                    .map(|c| {
+                        Property::Float(f64::from_le_bytes([
+                            c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7],
+                        ]))
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+            Some(Property::Array(values))
+        }
+        IDP_GROUP => {
+            let mut group = HashMap::new();
+            if data.is_valid("group") {
+                for child in data.get_iter("group") {
+                    if let Some(value) = read_property(&child) {
+                        group.insert(child.get_string("name"), value);
+                    }
+                }
+            }
+            Some(Property::Group(group))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Rotation;
+
+    fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+        a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-5)
+    }
+
+    fn rotate(rotation: &BlenderRotation, v: [f32; 3]) -> [f32; 3] {
+        rotation.quaternion().rotate_vector(cgmath::Vector3::from(v)).into()
+    }
+
+    #[test]
+    fn euler_xyz_applies_x_first() {
+        let quarter = std::f32::consts::FRAC_PI_2;
+        //x then z takes x to y, z then x would take it to z
+        let xyz = BlenderRotation::Euler([quarter, 0.0, quarter], 1);
+        assert!(close(rotate(&xyz, [1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]));
+        let zyx = BlenderRotation::Euler([quarter, 0.0, quarter], 6);
+        assert!(close(rotate(&zyx, [1.0, 0.0, 0.0]), [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn quaternion_and_axis_angle_modes() {
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        //A quarter turn around z in Blender's w, x, y, z order
+        let quat = BlenderRotation::Quat([half, 0.0, 0.0, half]);
+        assert!(close(rotate(&quat, [1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]));
+        let axis_angle = BlenderRotation::AxisAngle([0.0, 0.0, 2.0], std::f32::consts::FRAC_PI_2);
+        assert!(close(rotate(&axis_angle, [1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]));
+        //Unset values don't rotate
+        assert!(close(rotate(&BlenderRotation::Quat([0.0; 4]), [1.0, 2.0, 3.0]), [1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn local_transform_is_y_up_and_applies_parentinv() {
+        let none = BlenderRotation::Euler([0.0; 3], 1);
+        let t = local_transform([1.0, 2.0, 3.0], &none, [1.0, 2.0, 3.0], None);
+        assert!(close(t.pos.into(), [1.0, 3.0, -2.0]));
+        assert!(close(t.scale.into(), [1.0, 3.0, 2.0]));
+
+        //Parented while the parent sat at x = 1
+        let mut parentinv = [[0.0; 4]; 4];
+        for (i, col) in parentinv.iter_mut().enumerate() {
+            col[i] = 1.0;
+        }
+        parentinv[3][0] = -1.0;
+        let t = local_transform([1.0, 2.0, 3.0], &none, [1.0; 3], Some(parentinv));
+        assert!(close(t.pos.into(), [0.0, 3.0, -2.0]));
+    }
+
+    //Twice the signed area of a triangle in the xy plane
+    fn area(p: &[[f32; 3]], [a, b, c]: [usize; 3]) -> f32 {
+        (p[b][0] - p[a][0]) * (p[c][1] - p[a][1]) - (p[b][1] - p[a][1]) * (p[c][0] - p[a][0])
+    }
+
+    #[test]
+    fn triangulate_convex_and_concave() {
+        assert!(triangulate(&[[0.0; 3], [1.0, 0.0, 0.0]]).is_empty());
+        let square = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+        assert_eq!(triangulate(&square).len(), 2);
+
+        //An L, a fan from any corner would cover the notch
+        let l = [
+            [0.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [2.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [1.0, 2.0, 0.0],
+            [0.0, 2.0, 0.0],
+        ];
+        let triangles = triangulate(&l);
+        assert_eq!(triangles.len(), 4);
+        assert!(triangles.iter().all(|t| area(&l, *t) > 0.0));
+        let total: f32 = triangles.iter().map(|t| area(&l, *t)).sum();
+        assert!((total - 6.0).abs() < 1e-5);
+    }
+}
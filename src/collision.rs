@@ -43,6 +43,29 @@ impl World<'_> {
         }
     }
 
+    pub fn colliders(&self) -> &ColliderSet {
+        &self.colliders
+    }
+
+    pub fn colliders_mut(&mut self) -> &mut ColliderSet {
+        &mut self.colliders
+    }
+
+    pub fn rigid_bodies(&self) -> &RigidBodySet {
+        &self.rigid_bodies
+    }
+
+    pub fn add_collider(&mut self, collider: Collider) -> ColliderHandle {
+        self.colliders.insert(collider)
+    }
+
+    pub fn add_body(&mut self, body: RigidBody, collider: Collider) -> RigidBodyHandle {
+        let handle = self.rigid_bodies.insert(body);
+        self.colliders
+            .insert_with_parent(collider, handle, &mut self.rigid_bodies);
+        handle
+    }
+
     pub fn step(&mut self){
         self.physics_pipeline.step(
             &self.gravity,
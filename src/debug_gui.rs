@@ -1 +1,395 @@
+use super::{
+    engine::OGEngine,
+    game_object::GameObject,
+    layer::{LayerInfo, Mask},
+    math_3d::{Rotor3, Vector3},
+    og_engine::OGData,
+    platform::Key,
+    renderer::Renderer,
+    ui::InputCapture,
+};
+use std::collections::VecDeque;
+use winit::{event::WindowEvent, window::Window};
 
+//Frames shown in the frame time graph
+const FRAME_HISTORY: usize = 240;
+
+/// Which built in panels are open.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DebugPanels {
+    pub inspector: bool,
+    pub layers: bool,
+    pub camera: bool,
+    pub stats: bool,
+}
+
+impl Default for DebugPanels {
+    fn default() -> Self {
+        Self {
+            inspector: true,
+            layers: true,
+            camera: true,
+            stats: true,
+        }
+    }
+}
+
+/// egui overlay drawn on top of every layer. Toggled with `toggle_key`.
+///
+/// Game specific panels can be added with `add_window`, they are shown with
+/// the built in ones.
+pub struct DebugGui {
+    pub visible: bool,
+    pub toggle_key: Key,
+    pub panels: DebugPanels,
+    context: egui::Context,
+    state: egui_winit::State,
+    renderer: egui_wgpu::Renderer,
+    windows: Vec<(String, Box<dyn FnMut(&mut egui::Ui)>)>,
+    frame_times: VecDeque<f32>,
+    //Path of child indices from `renderer.game_objects`
+    selected: Vec<usize>,
+    output: Option<(Vec<egui::ClippedPrimitive>, egui::TexturesDelta, f32)>,
+}
+
+impl DebugGui {
+    pub fn new(window: &Window, renderer: &Renderer) -> Self {
+        let context = egui::Context::default();
+        let state = egui_winit::State::new(
+            egui::ViewportId::ROOT,
+            window,
+            Some(window.scale_factor() as f32),
+            Some(renderer.device.limits().max_texture_dimension_2d as usize),
+        );
+        let egui_renderer = egui_wgpu::Renderer::new(&renderer.device, renderer.surface_config.format, None, 1);
+        Self {
+            visible: false,
+            toggle_key: Key::F12,
+            panels: DebugPanels::default(),
+            context,
+            state,
+            renderer: egui_renderer,
+            windows: vec![],
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+            selected: vec![],
+            output: None,
+        }
+    }
+
+    pub fn context(&self) -> &egui::Context {
+        &self.context
+    }
+
+    pub fn add_window(&mut self, title: &str, contents: impl FnMut(&mut egui::Ui) + 'static) {
+        self.windows.insert(self.windows.len(), (title.to_string(), Box::new(contents)));
+    }
+
+    /// Passes a window event to egui. Returns true when egui used it and the
+    /// game shouldn't see it.
+    pub fn on_event(&mut self, event: &WindowEvent) -> bool {
+        if !self.visible {
+            return false;
+        }
+        self.state.on_window_event(&self.context, event).consumed
+    }
+
+    /// Builds the overlay for this frame.
+    pub fn run<D: OGData + 'static>(&mut self, engine: &mut OGEngine<D>, frame_time: f32) {
+        if self.frame_times.len() == FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+        if engine.get_raw_key(self.toggle_key).pressed {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            self.output = None;
+            return;
+        }
+
+        let raw_input = self.state.take_egui_input(&engine.window);
+        let context = self.context.clone();
+        let full_output = context.run(raw_input, |ctx| {
+            self.stats_panel(ctx);
+            self.layers_panel(ctx, engine);
+            self.camera_panel(ctx, engine);
+            self.inspector_panel(ctx, engine);
+            for (title, contents) in &mut self.windows {
+                egui::Window::new(title.as_str()).show(ctx, |ui| contents(ui));
+            }
+        });
+        self.state
+            .handle_platform_output(&engine.window, &self.context, full_output.platform_output);
+        engine.capture_input(InputCapture {
+            mouse: context.wants_pointer_input() || context.is_pointer_over_area(),
+            keyboard: context.wants_keyboard_input(),
+        });
+        let primitives = context.tessellate(full_output.shapes, full_output.pixels_per_point);
+        self.output = Some((primitives, full_output.textures_delta, full_output.pixels_per_point));
+    }
+
+    /// Draws the overlay onto the current surface texture.
+    pub fn render(&mut self, renderer: &Renderer, encoder: &mut wgpu::CommandEncoder) {
+        let (primitives, textures, pixels_per_point) = match self.output.take() {
+            Some(output) => output,
+            None => return,
+        };
+        let screen = egui_wgpu::renderer::ScreenDescriptor {
+            size_in_pixels: [renderer.surface_config.width, renderer.surface_config.height],
+            pixels_per_point,
+        };
+        for (id, delta) in &textures.set {
+            self.renderer.update_texture(&renderer.device, &renderer.queue, *id, delta);
+        }
+        let buffers = self
+            .renderer
+            .update_buffers(&renderer.device, &renderer.queue, encoder, &primitives, &screen);
+        renderer.queue.submit(buffers);
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Debug GUI Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &renderer.surface_texture_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            self.renderer.render(&mut render_pass, &primitives, &screen);
+        }
+        for id in &textures.free {
+            self.renderer.free_texture(id);
+        }
+    }
+
+    fn stats_panel(&mut self, ctx: &egui::Context) {
+        let frame_times = &self.frame_times;
+        egui::Window::new("Stats")
+            .open(&mut self.panels.stats)
+            .show(ctx, |ui| {
+                let last = frame_times.back().copied().unwrap_or(0.0);
+                let average = frame_times.iter().sum::<f32>() / frame_times.len().max(1) as f32;
+                let worst = frame_times.iter().copied().fold(0.0, f32::max);
+                ui.label(format!("FPS: {:.0}", if average > 0.0 { 1.0 / average } else { 0.0 }));
+                ui.label(format!(
+                    "Frame: {:.2} ms (avg {:.2}, max {:.2})",
+                    last * 1000.0,
+                    average * 1000.0,
+                    worst * 1000.0
+                ));
+                frame_graph(ui, frame_times, worst);
+            });
+    }
+
+    fn layers_panel<D: OGData + 'static>(&mut self, ctx: &egui::Context, engine: &mut OGEngine<D>) {
+        egui::Window::new("Layers")
+            .open(&mut self.panels.layers)
+            .show(ctx, |ui| {
+                for layer in engine.layers.iter_mut() {
+                    let kind = match &layer.layer_info {
+                        LayerInfo::Image(_) => "Image".to_string(),
+                        LayerInfo::Render(render) => format!(
+                            "Render {:?}",
+                            Mask::from_bits_truncate(render.mask as u64)
+                        ),
+                    };
+                    ui.checkbox(&mut layer.shown, format!("{}: {}", layer.id, kind));
+                }
+            });
+    }
+
+    fn camera_panel<D: OGData + 'static>(&mut self, ctx: &egui::Context, engine: &mut OGEngine<D>) {
+        egui::Window::new("Camera")
+            .open(&mut self.panels.camera)
+            .show(ctx, |ui| {
+                let camera = &mut engine.camera;
+                egui::Grid::new("camera").show(ui, |ui| {
+                    ui.label("FOV");
+                    ui.add(egui::DragValue::new(&mut camera.fov).speed(0.5).clamp_range(1.0..=179.0));
+                    ui.end_row();
+                    ui.label("Near");
+                    ui.add(egui::DragValue::new(&mut camera.clip_near).speed(0.01).clamp_range(0.001..=f32::MAX));
+                    ui.end_row();
+                    ui.label("Far");
+                    ui.add(egui::DragValue::new(&mut camera.clip_far).speed(1.0).clamp_range(0.01..=f32::MAX));
+                    ui.end_row();
+                });
+                ui.separator();
+                vector3_row(ui, "Position", &mut camera.transform.pos);
+            });
+    }
+
+    fn inspector_panel<D: OGData + 'static>(&mut self, ctx: &egui::Context, engine: &mut OGEngine<D>) {
+        let selected = &mut self.selected;
+        let mut dirty = Mask::empty();
+        egui::Window::new("Inspector")
+            .open(&mut self.panels.inspector)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_source("tree")
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for (i, go) in engine.renderer.game_objects.iter().enumerate() {
+                            object_tree(ui, go, vec![i], selected);
+                        }
+                    });
+                ui.separator();
+                match object_at(&mut engine.renderer.game_objects, selected) {
+                    Some(go) => dirty = object_editor(ui, go),
+                    None => {
+                        ui.label("Select an object");
+                    }
+                }
+            });
+
+        //Transforms are baked into the draw data
+        if !dirty.is_empty() {
+            let renderer = &mut engine.renderer;
+            let masks: Vec<Mask> = renderer
+                .draw_data
+                .iter()
+                .map(|dd| dd.mask)
+                .filter(|mask| mask.intersects(dirty))
+                .collect();
+            for mask in masks {
+                renderer.update_layer_draw_data(mask);
+            }
+        }
+    }
+}
+
+fn frame_graph(ui: &mut egui::Ui, frame_times: &VecDeque<f32>, worst: f32) {
+    let size = egui::vec2(ui.available_width().max(120.0), 60.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(160));
+    //Scale to the slowest frame, but never below 60 FPS
+    let top = worst.max(1.0 / 60.0);
+    let y = |t: f32| rect.bottom() - (t / top).min(1.0) * rect.height();
+    for target in [1.0 / 60.0, 1.0 / 30.0] {
+        if target <= top {
+            painter.hline(rect.x_range(), y(target), egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+        }
+    }
+    let step = rect.width() / (FRAME_HISTORY - 1) as f32;
+    let points: Vec<egui::Pos2> = frame_times
+        .iter()
+        .enumerate()
+        .map(|(i, t)| egui::pos2(rect.left() + i as f32 * step, y(*t)))
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, egui::Color32::LIGHT_GREEN)));
+}
+
+fn object_label(go: &GameObject, index: usize) -> String {
+    if go.name.is_empty() {
+        format!("Object {}", index)
+    } else {
+        go.name.clone()
+    }
+}
+
+fn object_tree(ui: &mut egui::Ui, go: &GameObject, path: Vec<usize>, selected: &mut Vec<usize>) {
+    let label = object_label(go, *path.last().unwrap());
+    let is_selected = *selected == path;
+    if go.children.is_empty() {
+        if ui.selectable_label(is_selected, label).clicked() {
+            *selected = path;
+        }
+        return;
+    }
+    egui::CollapsingHeader::new(label)
+        .id_source(&path)
+        .show(ui, |ui| {
+            if ui.selectable_label(is_selected, "(this object)").clicked() {
+                *selected = path.clone();
+            }
+            for (i, child) in go.children.iter().enumerate() {
+                let mut child_path = path.clone();
+                child_path.insert(child_path.len(), i);
+                object_tree(ui, child, child_path, selected);
+            }
+        });
+}
+
+fn object_at<'a>(objects: &'a mut [GameObject], path: &[usize]) -> Option<&'a mut GameObject> {
+    let (first, rest) = path.split_first()?;
+    let mut go = objects.get_mut(*first)?;
+    for i in rest {
+        go = go.children.get_mut(*i)?;
+    }
+    Some(go)
+}
+
+//Returns the layers that need their draw data rebuilt
+fn object_editor(ui: &mut egui::Ui, go: &mut GameObject) -> Mask {
+    let old_mask = go.layer_mask;
+    let mut changed = false;
+    ui.heading(if go.name.is_empty() { "Object" } else { go.name.as_str() });
+    changed |= ui.checkbox(&mut go.active, "Active").changed();
+
+    changed |= vector3_row(ui, "Position", &mut go.transform.pos);
+    changed |= vector3_row(ui, "Scale", &mut go.transform.scale);
+    changed |= rotor_row(ui, &mut go.transform.rot);
+
+    ui.label("Layers");
+    ui.horizontal_wrapped(|ui| {
+        for (name, flag) in [
+            ("D3", Mask::D3),
+            ("GUI", Mask::GUI),
+            ("3", Mask::LAYER3),
+            ("4", Mask::LAYER4),
+        ] {
+            let mut on = go.layer_mask.contains(flag);
+            if ui.checkbox(&mut on, name).changed() {
+                go.layer_mask.set(flag, on);
+            }
+        }
+    });
+
+    if !go.properties.is_empty() {
+        ui.collapsing("Properties", |ui| {
+            for (key, value) in &go.properties {
+                ui.label(format!("{}: {:?}", key, value));
+            }
+        });
+    }
+
+    if changed || go.layer_mask != old_mask {
+        old_mask | go.layer_mask
+    } else {
+        Mask::empty()
+    }
+}
+
+fn vector3_row(ui: &mut egui::Ui, label: &str, v: &mut Vector3) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let x = ui.add(egui::DragValue::new(&mut v.x).speed(0.05).prefix("x ")).changed();
+        let y = ui.add(egui::DragValue::new(&mut v.y).speed(0.05).prefix("y ")).changed();
+        let z = ui.add(egui::DragValue::new(&mut v.z).speed(0.05).prefix("z ")).changed();
+        x || y || z
+    })
+    .inner
+}
+
+fn rotor_row(ui: &mut egui::Ui, rot: &mut Rotor3) -> bool {
+    let changed = ui
+        .horizontal(|ui| {
+            ui.label("Rotation");
+            let a = ui.add(egui::DragValue::new(&mut rot.a).speed(0.01).prefix("a ")).changed();
+            let xy = ui.add(egui::DragValue::new(&mut rot.b12).speed(0.01).prefix("xy ")).changed();
+            let xz = ui.add(egui::DragValue::new(&mut rot.b13).speed(0.01).prefix("xz ")).changed();
+            let yz = ui.add(egui::DragValue::new(&mut rot.b23).speed(0.01).prefix("yz ")).changed();
+            a || xy || xz || yz
+        })
+        .inner;
+    if changed {
+        rot.normalize();
+    }
+    changed
+}
@@ -1,4 +1,6 @@
 use super::{
+    geometry::{Triangle, Vertex, UV},
+    math_3d::Vector3,
     sprite::Sprite,
     util::{Vf2d, Vi2d},
     renderer::Renderer,
@@ -10,18 +12,39 @@ use std::sync::Arc;
 pub struct SmallD {
     pub id: i32,
     pub sprite: Sprite,
+    /// Pixels of `sprite` to texture coordinates.
     pub uv_scale: Vf2d,
+    /// Where `sprite` starts in the texture, non zero for atlas regions.
+    pub uv_offset: Vf2d,
+}
+
+impl SmallD {
+    /// Texture coordinates of the corners of a region of `sprite`, in
+    /// `DecalInstance` order.
+    pub fn uv_quad(&self, source_pos: Vf2d, source_size: Vf2d) -> [Vf2d; 4] {
+        let tl = self.uv_offset + source_pos * self.uv_scale;
+        let br = tl + source_size * self.uv_scale;
+        [tl, Vf2d::new(tl.x, br.y), br, Vf2d::new(br.x, tl.y)]
+    }
+
+    /// Texture coordinates of the whole of `sprite`.
+    pub fn full_uv_quad(&self) -> [Vf2d; 4] {
+        let size = Vf2d::new(self.sprite.width as f32, self.sprite.height as f32);
+        self.uv_quad(Vf2d::new(0.0, 0.0), size)
+    }
 }
 
 #[derive(Clone)]
 pub struct Decal {
     pub d_inst: Arc<SmallD>,
+    //False for atlas regions, the atlas deletes the texture
+    owns_texture: bool,
 }
 
 impl Drop for Decal {
     fn drop(&mut self) {
         let id = self.get().id;
-        if id != -1 {
+        if id != -1 && self.owns_texture {
             Renderer::delete_texture(&mut (id as u32));
         }
     }
@@ -33,44 +56,97 @@ impl Decal {
             id: -1,
             sprite: Sprite::new(0, 0),
             uv_scale: Vf2d::from((1.0, 1.0)),
+            uv_offset: Vf2d::from((0.0, 0.0)),
         };
         Self {
             d_inst: Arc::new(small),
+            owns_texture: false,
         }
     }
 
     pub fn create(spr: Option<Sprite>, renderer: &mut Renderer) -> Self {
         match spr {
             Some(sprite) => {
-                let mut small = SmallD {
-                    id: renderer.create_texture(sprite.width, sprite.height),
+                let id = renderer.create_texture(sprite.width, sprite.height);
+                renderer.update_texture(id as u32, &sprite);
+                let small = SmallD {
+                    id,
+                    uv_scale: Vf2d::from((
+                        1.0 / (sprite.width.max(1) as f32),
+                        1.0 / (sprite.height.max(1) as f32),
+                    )),
+                    uv_offset: Vf2d::from((0.0, 0.0)),
                     sprite,
-                    uv_scale: Vf2d::from((1.0, 1.0)),
                 };
-                //Decal::update(&mut small);
                 Self {
                     d_inst: Arc::new(small),
+                    owns_texture: true,
                 }
             }
             None => Decal::empty(),
         }
     }
 
-    /*fn update(small: &mut SmallD) {
-        if small.id == -1 { return; };
-        small.uv_scale = Vf2d::from(
-            (1.0 / (small.sprite.width as f32),
-             (1.0 / (small.sprite.height as f32))
-            ));
-        Renderer::apply_texture(small.id as u32);
-        Renderer::update_texture(small.id as u32, &small.sprite);
-    }*/
+    /// A decal drawing from a texture owned by something else, like a
+    /// `TextureAtlas` page.
+    pub fn shared(small: SmallD) -> Self {
+        Self {
+            d_inst: Arc::new(small),
+            owns_texture: false,
+        }
+    }
 
     pub fn get(&self) -> Arc<SmallD> {
         Arc::clone(&self.d_inst)
     }
 }
 
+/// How decals are blended with what's under them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DecalMode {
+    Normal,
+    Additive,
+    Multiplicative,
+    Illuminate,
+}
+
+impl Default for DecalMode {
+    fn default() -> Self {
+        DecalMode::Normal
+    }
+}
+
+impl DecalMode {
+    pub const ALL: [DecalMode; 4] = [
+        DecalMode::Normal,
+        DecalMode::Additive,
+        DecalMode::Multiplicative,
+        DecalMode::Illuminate,
+    ];
+
+    pub fn blend_state(&self) -> wgpu::BlendState {
+        let (src_factor, dst_factor) = match self {
+            DecalMode::Normal => (wgpu::BlendFactor::SrcAlpha, wgpu::BlendFactor::OneMinusSrcAlpha),
+            DecalMode::Additive => (wgpu::BlendFactor::SrcAlpha, wgpu::BlendFactor::One),
+            DecalMode::Multiplicative => (wgpu::BlendFactor::Dst, wgpu::BlendFactor::OneMinusSrcAlpha),
+            //Darkens where the decal is transparent, lightens where opaque
+            DecalMode::Illuminate => (wgpu::BlendFactor::OneMinusSrcAlpha, wgpu::BlendFactor::SrcAlpha),
+        };
+        wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                operation: wgpu::BlendOperation::Add,
+                src_factor,
+                dst_factor,
+            },
+            alpha: wgpu::BlendComponent {
+                operation: wgpu::BlendOperation::Add,
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            },
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct DecalInstance {
     pub decal: Option<Arc<SmallD>>,
@@ -78,6 +154,7 @@ pub struct DecalInstance {
     pub uv: [Vf2d; 4],
     pub w: [f32; 4],
     pub tint: [Pixel; 4],
+    pub mode: DecalMode,
 }
 
 impl Default for DecalInstance {
@@ -93,6 +170,7 @@ impl Default for DecalInstance {
             ],
             w: [1.0; 4],
             tint: [Pixel::rgb(255, 255, 255); 4],
+            mode: DecalMode::Normal,
         }
     }
 }
@@ -101,6 +179,95 @@ impl DecalInstance {
     fn get(&self) -> &Arc<SmallD> {
         self.decal.as_ref().unwrap()
     }
+
+    /// Texture drawn from, -1 for untextured quads.
+    pub fn texture_id(&self) -> i32 {
+        self.decal.as_ref().map(|d| d.id).unwrap_or(-1)
+    }
+
+    //Two triangles, 0 1 2 and 0 2 3
+    fn vertices(&self) -> [Vertex; 6] {
+        let vertex = |i: usize| Vertex {
+            position: Vector3::new(self.pos[i].x, self.pos[i].y, 0.0),
+            tex_coords: UV {
+                u: self.uv[i].x,
+                v: self.uv[i].y,
+                w: self.w[i],
+            },
+            normal: Vector3::default(),
+            color: self.tint[i],
+        };
+        [vertex(0), vertex(1), vertex(2), vertex(0), vertex(2), vertex(3)]
+    }
+}
+
+impl From<&Triangle> for DecalInstance {
+    //The last corner repeats, so the second triangle of the quad is empty
+    fn from(tri: &Triangle) -> Self {
+        let corner = |i: usize| tri.v[i.min(2)];
+        let mut di = DecalInstance::default();
+        for i in 0..4 {
+            let v = corner(i);
+            di.pos[i] = Vf2d::new(v.position.x, v.position.y);
+            di.uv[i] = Vf2d::new(v.tex_coords.u, v.tex_coords.v);
+            di.w[i] = if v.tex_coords.w == 0.0 { 1.0 } else { v.tex_coords.w };
+            di.tint[i] = v.color;
+        }
+        di
+    }
+}
+
+/// Decals sharing a texture and blend mode, drawn with one call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecalBatch {
+    pub texture: i32,
+    pub mode: DecalMode,
+    pub vertices: std::ops::Range<u32>,
+}
+
+/// Turns a layer's `DecalInstance`s into vertices and batches.
+#[derive(Default)]
+pub struct DecalBatcher {
+    pub vertices: Vec<Vertex>,
+    pub batches: Vec<DecalBatch>,
+}
+
+impl DecalBatcher {
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.batches.clear();
+    }
+
+    /// Adds `instances` as batches of their own. With `sort` they are
+    /// grouped by texture and blend mode first, so overlapping decals from
+    /// different textures may swap order. Decals from one atlas page keep
+    /// theirs.
+    pub fn add(&mut self, instances: &[DecalInstance], sort: bool) -> std::ops::Range<usize> {
+        let first = self.batches.len();
+        let mut order: Vec<&DecalInstance> = instances.iter().collect();
+        if sort {
+            order.sort_by_key(|di| (di.texture_id(), di.mode));
+        }
+        for di in order {
+            let start = self.vertices.len() as u32;
+            self.vertices.extend_from_slice(&di.vertices());
+            let end = self.vertices.len() as u32;
+            match self.batches[first..].last_mut() {
+                Some(batch) if batch.texture == di.texture_id() && batch.mode == di.mode => {
+                    batch.vertices.end = end
+                }
+                _ => self.batches.insert(
+                    self.batches.len(),
+                    DecalBatch {
+                        texture: di.texture_id(),
+                        mode: di.mode,
+                        vertices: start..end,
+                    },
+                ),
+            }
+        }
+        first..self.batches.len()
+    }
 }
 
 #[derive(Clone)]
@@ -1,16 +1,31 @@
 use super::{
-    og_engine::OGData,
+    asset::{AssetChanged, AssetServer, Handle},
+    audio::AudioSystem,
+    audio_occlusion::OcclusionGeometry,
+    hot_reload::HotReload,
+    input::InputMap,
+    og_engine::{OGData, Rcode},
     camera::Camera,
-    decal::{Decal, DecalInstance, SmallD},
+    atlas::TextureAtlas,
+    decal::{Decal, DecalInstance, DecalMode, SmallD},
+    font::{Font, FontId, FontSystem},
+    gamepad::{GamepadAxis, GamepadButton, GamepadId, Gamepads},
     geometry::{Triangle, UV, Mesh, Vertex},
-    layer::{LayerDesc, LayerInfo, LayerType, Image, EMPTY_IMAGE, PipelineBundle},
-    pixel::{Pixel, PixelMode},
-    platform::{PLATFORM_DATA, Platform, Key},
+    layer::{LayerDesc, LayerInfo, LayerType, Image, Mask, EMPTY_IMAGE, PipelineBundle},
+    pixel::{Pixel, PixelFunc, PixelMode},
+    platform::{InputState, Platform, Key, WindowState},
     renderer::Renderer,
-    sprite::{Sprite},
-    util::{HWButton, Mouse, Vf2d, Vi2d, BMPLoader, ImageLoader, PNGLoader},
+    replay::{InputRecording, Replay},
+    sprite::{Sprite, SpriteFlip},
+    text::{blend_over, TextLayout, TextOptions, TextStyle},
+    text_effect::TextEffect,
+    text_input::{TextEvent, TextInput},
+    ui::InputCapture,
+    util::{HWButton, Mouse, Vf2d, Vi2d, AutoLoader, BMPLoader, ImageLoader, PNGLoader},
 };
 use std::sync::Arc;
+#[cfg(feature = "debug-gui")]
+use super::debug_gui::DebugGui;
 
 pub struct OGEngine<D: OGData + 'static> {
     pub app_name: String,
@@ -32,9 +47,37 @@ pub struct OGEngine<D: OGData + 'static> {
     pub layers: Vec<LayerDesc<D>>,
     pub mouse_position: Vi2d,
     pub font_decal: Decal,
+    /// Sprites turned into decals with `create_decal`.
+    pub atlas: TextureAtlas,
+    pub decal_mode: DecalMode,
+    pub pixel_mode: PixelMode,
+    /// Scales the alpha of pixels drawn in `PixelMode::Alpha`.
+    pub blend_factor: f32,
+    pub custom_pixel_func: Option<PixelFunc>,
+    pub fonts: FontSystem,
     pub depth_buffer: Vec<f64>,
     pub window: winit::window::Window,
-    //pub audio_system: AudioSystem
+    pub assets: AssetServer,
+    pub hot_reload: HotReload,
+    /// Keyboard and mouse state, written by the platform layer.
+    pub input: InputState,
+    pub window_state: WindowState,
+    /// Input the UI claimed this frame, hidden from `get_mouse` and `get_key`.
+    /// Set it with `capture_input` so input map actions see it too.
+    pub input_capture: InputCapture,
+    pub gamepads: Gamepads,
+    /// Named actions and axes, see `InputMap`.
+    pub input_map: InputMap,
+    /// Typed text, see `get_text_input`.
+    pub text_input: TextInput,
+    /// Input recording and playback, see `Replay`.
+    pub replay: Replay,
+    #[cfg(feature = "debug-gui")]
+    pub debug_gui: Option<DebugGui>,
+    pub audio_system: AudioSystem,
+    /// Level geometry that occludes emitters, measured every frame with
+    /// `AudioSystem::update_occlusion`.
+    pub audio_occluder: Option<Box<dyn OcclusionGeometry>>,
 }
 
 impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
@@ -71,51 +114,155 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
     }
 
     pub fn is_focused(&self) -> bool {
-        unsafe { PLATFORM_DATA.key_focus }
+        self.input.key_focus
     }
 
+    /// Hides mouse and/or keyboard input from the game, including input map
+    /// actions and axes, for the rest of the frame.
+    pub fn capture_input(&mut self, capture: InputCapture) {
+        self.input_capture.mouse |= capture.mouse;
+        self.input_capture.keyboard |= capture.keyboard;
+        self.input_map.capture = self.input_capture;
+    }
+
+    /// State of `k`, or an idle button while the UI has keyboard focus.
     pub fn get_key(&self, k: Key) -> HWButton {
-        unsafe {
-            if let Some(button) = PLATFORM_DATA.key_map.as_mut().unwrap().get(&k) {
-                *button
-            } else {
-                HWButton::new()
-            }
+        if self.input_capture.keyboard {
+            return HWButton::new();
         }
+        self.get_raw_key(k)
     }
 
-    pub fn set_key(&self, k: Key, hw: HWButton) {
-        unsafe {
-            let key = PLATFORM_DATA
-                .key_map
-                .as_mut()
-                .unwrap()
-                .entry(k)
-                .or_insert(hw);
-            *key = hw;
-        }
+    /// State of `k`, even when the UI has captured the keyboard.
+    pub fn get_raw_key(&self, k: Key) -> HWButton {
+        self.input.get_key(k)
     }
-    pub fn clear_keys(&self) {
-        unsafe {
-            for (key, value) in PLATFORM_DATA.key_map.as_mut().unwrap() {
-                (*value).pressed = false;
-                (*value).released = false;
-            }
+
+    /// Keys that went down this frame.
+    pub fn pressed_keys(&self) -> Vec<Key> {
+        if self.input_capture.keyboard {
+            return vec![];
         }
+        self.input
+            .key_map
+            .iter()
+            .filter(|(_, button)| button.pressed)
+            .map(|(key, _)| *key)
+            .collect()
     }
 
+    pub fn set_key(&mut self, k: Key, hw: HWButton) {
+        self.input.key_map.insert(k, hw);
+    }
+    pub fn clear_keys(&mut self) {
+        self.input.clear_keys();
+    }
+
+    /// State of `b`, or an idle button while the mouse is over the UI.
     pub fn get_mouse(&self, b: Mouse) -> HWButton {
-        unsafe {
-            match b {
-                Mouse::Left => PLATFORM_DATA.mouse_map.as_ref().unwrap()[0],
-                Mouse::Right => PLATFORM_DATA.mouse_map.as_ref().unwrap()[1],
-                Mouse::Middle => PLATFORM_DATA.mouse_map.as_ref().unwrap()[2],
-            }
+        if self.input_capture.mouse {
+            return HWButton::new();
+        }
+        self.get_raw_mouse(b)
+    }
+
+    /// State of `b`, even when the UI has captured the mouse.
+    pub fn get_raw_mouse(&self, b: Mouse) -> HWButton {
+        match b {
+            Mouse::Left => self.input.get_mouse(0),
+            Mouse::Right => self.input.get_mouse(1),
+            Mouse::Middle => self.input.get_mouse(2),
+        }
+    }
+
+    /// Text typed this frame. Empty while the UI has captured the keyboard.
+    pub fn get_text_input(&self) -> &[TextEvent] {
+        if self.input_capture.keyboard {
+            &[]
+        } else {
+            self.text_input.events()
+        }
+    }
+
+    /// Lets the window receive input method (IME) events, turn on while a
+    /// text field is focused.
+    pub fn set_ime_allowed(&mut self, allowed: bool) {
+        if self.text_input.ime_allowed != allowed {
+            self.text_input.ime_allowed = allowed;
+            self.window.set_ime_allowed(allowed);
         }
     }
 
+    /// Where the input method should show its candidate window, in pixels.
+    pub fn set_ime_position(&mut self, pos: Vf2d) {
+        let size = self.window.inner_size();
+        let scale = Vf2d::new(
+            size.width as f32 / self.pixels_w.max(1) as f32,
+            size.height as f32 / self.pixels_h.max(1) as f32,
+        );
+        self.window.set_ime_position(winit::dpi::PhysicalPosition::new(
+            (pos.x * scale.x) as i32,
+            (pos.y * scale.y) as i32,
+        ));
+    }
+
+    /// Ids of the connected gamepads.
+    pub fn get_gamepads(&self) -> Vec<GamepadId> {
+        self.gamepads.connected()
+    }
+
+    pub fn get_gamepad_button(&self, id: GamepadId, b: GamepadButton) -> HWButton {
+        self.gamepads.get_button(id, b)
+    }
+
+    pub fn get_gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepads.get_axis(id, axis)
+    }
+
+    pub fn rumble_gamepad(&mut self, id: GamepadId, strong: f32, weak: f32, duration: std::time::Duration) -> Rcode {
+        match self.gamepads.rumble(id, strong, weak, duration) {
+            Ok(()) => Rcode::Ok,
+            Err(e) => e,
+        }
+    }
+
+    /// Records the input of every frame from now on.
+    pub fn start_recording(&mut self) {
+        self.replay.start_recording();
+    }
+
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.replay.stop_recording()
+    }
+
+    /// Ignores real input and feeds `recording` back frame by frame, with
+    /// the recorded frame times.
+    pub fn start_replay(&mut self, recording: InputRecording) {
+        self.replay.start_replay(recording);
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_replaying()
+    }
+
+    pub fn replay_finished(&self) -> bool {
+        self.replay.finished()
+    }
+
+    /// Queues triangles in clip space on the draw target, like decals.
     pub fn render_gl_tris(&mut self, triangles: &[Triangle], texture: u32) {
-        Renderer::draw_triangles(triangles, texture);
+        let decal = Arc::new(SmallD {
+            id: texture as i32,
+            sprite: Sprite::new(0, 0),
+            uv_scale: Vf2d::new(1.0, 1.0),
+            uv_offset: Vf2d::new(0.0, 0.0),
+        });
+        for tri in triangles {
+            self.push_decal_instance(DecalInstance {
+                decal: Some(Arc::clone(&decal)),
+                ..DecalInstance::from(tri)
+            });
+        }
     }
 
     pub fn set_perspective(&self, fov: f32, aspect: f32, near_clip: f32, far_clip: f32) {
@@ -124,34 +271,32 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         //(GL.glFrustum)(-f_W, f_W, -f_H, f_H, near_clip as f64, far_clip as f64)
     }
 
-    pub fn set_mouse(&self, i: usize, k: HWButton) {
-        unsafe { PLATFORM_DATA.mouse_map.as_mut().unwrap()[i] = k }
+    pub fn set_mouse(&mut self, i: usize, k: HWButton) {
+        self.input.mouse_map[i] = k;
     }
 
-    pub fn set_mouse_pos(&self, x: f32, y: f32) {
-        unsafe {
-            let px = PLATFORM_DATA.pixel_size.unwrap_or_default();
-            let mp: Vi2d = (
-                x.floor() as i32 * px.x + PLATFORM_DATA.view_position.unwrap().x,
-                y.floor() as i32 * px.y + PLATFORM_DATA.view_position.unwrap().y,
-            )
-                .into();
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                self.window
-                    .set_cursor_position(winit::dpi::PhysicalPosition { x: mp.x, y: mp.y });
-            }
+    pub fn set_mouse_pos(&mut self, x: f32, y: f32) {
+        let px = self.window_state.pixel_size;
+        let mp: Vi2d = (
+            x.floor() as i32 * px.x + self.window_state.view_position.x,
+            y.floor() as i32 * px.y + self.window_state.view_position.y,
+        )
+            .into();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.window
+                .set_cursor_position(winit::dpi::PhysicalPosition { x: mp.x, y: mp.y });
+        }
 
-            #[cfg(target_arch = "wasm32")]
-            {}
+        #[cfg(target_arch = "wasm32")]
+        {}
 
-            /*if winapi::um::winuser::SetCursorPos(mp.x, mp.y) == 0
-            {
-                panic!("SetCursorPos failed");
-            }*/
-            PLATFORM_DATA.raw_mouse_position = Some((mp.x as f32, mp.y as f32).into());
-            PLATFORM_DATA.mouse_position = Some((x.floor(), y.floor()).into());
-        }
+        /*if winapi::um::winuser::SetCursorPos(mp.x, mp.y) == 0
+        {
+            panic!("SetCursorPos failed");
+        }*/
+        self.input.raw_mouse_position = (mp.x as f32, mp.y as f32).into();
+        self.input.mouse_position = (x.floor(), y.floor()).into();
     }
 
     #[cfg(feature = "web-sys")]
@@ -183,39 +328,40 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
     }
 
     pub fn mouse_x(&self) -> f32 {
-        unsafe { PLATFORM_DATA.mouse_position.unwrap_or_default().x }
+        self.input.mouse_position.x
     }
 
     pub fn mouse_y(&self) -> f32 {
-        unsafe { PLATFORM_DATA.mouse_position.unwrap_or_default().y }
+        self.input.mouse_position.y
     }
 
     pub fn mouse_wheel(&self) -> i32 {
-        0
+        if self.input_capture.mouse {
+            return 0;
+        }
+        self.get_raw_mouse_wheel()
+    }
+
+    pub fn get_raw_mouse_wheel(&self) -> i32 {
+        self.input.mouse_wheel_delta
     }
 
     //pub fn get_window_mouse() -> Vi2d { Vi2d }
 
     pub fn get_mouse_pos(&self) -> Vf2d {
-        unsafe {
-            let m = PLATFORM_DATA.mouse_position;
-            m.unwrap()
-        }
+        self.input.mouse_position
     }
     pub fn get_raw_mouse_pos(&self) -> Vf2d {
-        unsafe {
-            let m = PLATFORM_DATA.raw_mouse_position;
-            m.unwrap()
-        }
+        self.input.raw_mouse_position
     }
 
     //Utility
     pub fn screen_width(&self) -> i32 {
-        unsafe { PLATFORM_DATA.screen_size.unwrap_or_default().x }
+        self.window_state.screen_size.x
     }
 
     pub fn screen_height(&self) -> i32 {
-        unsafe { PLATFORM_DATA.screen_size.unwrap_or_default().y }
+        self.window_state.screen_size.y
     }
 
     pub fn get_draw_target_width(&self) -> i32 {
@@ -242,10 +388,10 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
     }
 
     pub fn get_window_size(&self) -> Vi2d {
-        unsafe { PLATFORM_DATA.window_size.unwrap() }
+        self.window_state.window_size
     }
     pub fn get_window_position(&self) -> Vi2d {
-        unsafe { PLATFORM_DATA.view_position.unwrap() }
+        self.window_state.view_position
     }
 
     pub fn get_screen_size_in_game_pixels(&self) -> Vi2d {
@@ -302,14 +448,47 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         }
     }
 
-    pub fn set_layer_offset(&self, layer: u8, x: f32, y: f32) {}
+    pub fn set_layer_offset(&mut self, layer_id: u32, x: f32, y: f32) {
+        if let Some(image) = self.get_image_layer_mut(layer_id) {
+            image.offset = Vf2d::new(x, y);
+        }
+    }
+
+    pub fn set_layer_scale(&mut self, layer_id: u32, x: f32, y: f32) {
+        if let Some(image) = self.get_image_layer_mut(layer_id) {
+            image.scale = Vf2d::new(x, y);
+        }
+    }
 
-    pub fn set_layer_scale(&self, layer: u8, x: f32, y: f32) {}
+    pub fn set_layer_tint(&mut self, layer_id: u32, tint: Pixel) {
+        if let Some(image) = self.get_image_layer_mut(layer_id) {
+            image.tint = tint;
+        }
+    }
 
-    pub fn set_layer_tint(&self, layer: u8, tint: Pixel) {}
+    pub fn set_layer_opacity(&mut self, layer_id: u32, opacity: f32) {
+        if let Some(image) = self.get_image_layer_mut(layer_id) {
+            image.opacity = opacity.clamp(0.0, 1.0);
+        }
+    }
 
-    //We'll come back to this
-    //pub fn set_layer_custom_render_function
+    /// How the layer is blended with the layers under it.
+    pub fn set_layer_blend(&mut self, layer_id: u32, mode: DecalMode) {
+        if let Some(image) = self.get_image_layer_mut(layer_id) {
+            image.blend = mode;
+        }
+    }
+
+    /// Draws the layer with a custom WGSL shader. It gets the same inputs as
+    /// the default layer shader: the layer texture and sampler in group 0 and
+    /// the layer's offset, scale, tint and opacity in group 1.
+    pub fn set_layer_shader(&mut self, layer_id: u32, source: &str) {
+        self.renderer.set_layer_shader(layer_id, source);
+    }
+
+    pub fn clear_layer_shader(&mut self, layer_id: u32) {
+        self.renderer.clear_layer_shader(layer_id);
+    }
 
     pub fn get_layer_ref(&self, layer_id: u32) -> Option<&LayerDesc<D>> {
         let layer_iter = self.layers.iter();
@@ -321,6 +500,17 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         None
     }
 
+    /// First Render layer drawing any of `mask`.
+    pub fn get_layer_by_mask(&self, mask: Mask) -> Option<u32> {
+        self.layers
+            .iter()
+            .find(|layer| match &layer.layer_info {
+                LayerInfo::Render(render) => Mask::from_bits_truncate(render.mask as u64).intersects(mask),
+                _ => false,
+            })
+            .map(|layer| layer.id)
+    }
+
     pub fn get_image_layer_ref(&self, layer_id: u32) -> Option<&Image> {
         let layer_iter = self.layers.iter();
         for layer in layer_iter {
@@ -333,6 +523,13 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         None
     }
 
+    pub fn get_image_layer_mut(&mut self, layer_id: u32) -> Option<&mut Image> {
+        match self.layers.iter_mut().find(|layer| layer.id == layer_id) {
+            Some(LayerDesc { layer_info: LayerInfo::Image(image), .. }) => Some(image),
+            _ => None,
+        }
+    }
+
     pub fn get_layer(&self, layer_id: u32) -> Result<&LayerDesc<D>, ()> {
         let layer_iter = self.layers.iter();
         for layer in layer_iter {
@@ -370,9 +567,7 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
     }
 
     pub fn get_y_up_direction(&self) -> f32{
-        unsafe{
-            PLATFORM_DATA.y_up_direction
-        }
+        self.window_state.y_up_direction
     }
 
     pub fn add_layer(&mut self, layer_type: LayerType) -> u32 {
@@ -380,7 +575,8 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         let mut layer = LayerDesc::empty(layer_type);
         layer.id = lay_id as u32;
         self.layers.push(layer);
-        self.renderer.active_decals.insert(lay_id as usize, lay_id as u32);
+        let len = self.renderer.active_decals.len();
+        self.renderer.active_decals.insert(len, lay_id as u32);
         self.renderer.update_layer_texture_groups();
         lay_id as u32
     }
@@ -393,38 +589,125 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         let mut layer = LayerDesc::new(layer_info);
         layer.id = lay_id as u32;
         self.layers.push(layer);
-        self.renderer.active_decals.insert(lay_id as usize, lay_id as u32);
+        let len = self.renderer.active_decals.len();
+        self.renderer.active_decals.insert(len, lay_id as u32);
         self.renderer.update_layer_texture_groups();
         lay_id as u32
     }
 
-    pub fn load_sprite(&self, path: &str) -> Sprite {
-        Sprite::load_from_file::<BMPLoader>(path).unwrap()
+    pub fn load_sprite(&self, path: &str) -> Result<Sprite, Rcode> {
+        AutoLoader::load_image_resource(path)
+    }
+
+    /// Checks for changed asset files every `hot_reload.poll_interval` and
+    /// applies assets that were reloaded from disk to the renderer. Called by
+    /// the game loop once per frame.
+    pub fn update_assets(&mut self, elapsed_time: f64) {
+        if self.hot_reload.poll_due(elapsed_time) {
+            self.assets.check_for_changes();
+        }
+        let events = self.assets.drain_events();
+        self.hot_reload.apply(events, &mut self.renderer);
+    }
+
+    /// Assets that were reloaded from disk since the last frame.
+    pub fn asset_events(&self) -> &[AssetChanged] {
+        &self.hot_reload.events
     }
 
-    fn push_decal_instance(&mut self, di: DecalInstance) {
+    /// Loads a sprite in the background. See `AssetServer::load`.
+    pub fn load_sprite_async(&self, path: &str) -> Handle<Sprite> {
+        self.assets.load(path)
+    }
+
+    /// Packs `sprite` into the engine's atlas, so it batches with other
+    /// decals made this way.
+    pub fn create_decal(&mut self, sprite: &Sprite) -> Decal {
+        self.atlas.add(&mut self.renderer, sprite)
+    }
+
+    /// Blend mode of the decals drawn after this.
+    pub fn set_decal_mode(&mut self, mode: DecalMode) {
+        self.decal_mode = mode;
+    }
+
+    pub fn get_decal_mode(&self) -> DecalMode {
+        self.decal_mode
+    }
+
+    fn push_decal_instance(&mut self, mut di: DecalInstance) {
+        di.mode = self.decal_mode;
         self.get_draw_target()
             .expect("Can't get draw target")
             .vec_decal_instance
             .push(di);
     }
 
-    pub fn set_pixel_mode(&mut self, m: PixelMode) {}
+    /// `PixelMode::Custom` needs a function from `set_custom_pixel_mode`,
+    /// without one this switches to `PixelMode::Normal`.
+    pub fn set_pixel_mode(&mut self, m: PixelMode) {
+        self.pixel_mode = match m {
+            PixelMode::Custom if self.custom_pixel_func.is_none() => {
+                log::warn!("No custom pixel function set, drawing in Normal mode");
+                PixelMode::Normal
+            }
+            m => m,
+        };
+    }
+
+    /// Switches to `PixelMode::Custom`, drawing every pixel through `func`.
+    pub fn set_custom_pixel_mode(&mut self, func: impl Fn(i32, i32, Pixel, Pixel) -> Pixel + 'static) {
+        self.custom_pixel_func = Some(Box::new(func));
+        self.pixel_mode = PixelMode::Custom;
+    }
 
     pub fn get_pixel_mode(&self) -> PixelMode {
-        PixelMode::Normal
+        self.pixel_mode
     }
 
-    pub fn set_pixel_blend(&mut self, blend: f32) {}
+    pub fn set_pixel_blend(&mut self, blend: f32) {
+        self.blend_factor = blend.clamp(0.0, 1.0);
+    }
 
     //DRAW ROUTINES
     pub fn draw(&mut self, x: i32, y: i32, p: Pixel) {
-        if let LayerInfo::Image(image_data) = &mut self
-            .get_draw_target()
-            .expect("Can't get draw target")
-            .layer_info
-        {
-            image_data.sprite.set_pixel(x as u32, y as u32, p);
+        if x < 0 || y < 0 {
+            return;
+        }
+        let draw_target = self.draw_target;
+        //Not through get_draw_target, the pixel mode fields are still needed
+        let layer = self
+            .layers
+            .iter_mut()
+            .find(|layer| layer.id == draw_target)
+            .expect("Can't get draw target");
+        if let LayerInfo::Image(image_data) = &mut layer.layer_info {
+            let sprite = &mut image_data.sprite;
+            let (sx, sy) = (x as u32, y as u32);
+            match self.pixel_mode {
+                PixelMode::Normal => {
+                    sprite.set_pixel(sx, sy, p);
+                }
+                PixelMode::Mask => {
+                    if p.a() == 255 {
+                        sprite.set_pixel(sx, sy, p);
+                    }
+                }
+                PixelMode::Alpha => {
+                    let a = (p.a() as f32 * self.blend_factor) as u8;
+                    let src = Pixel::rgba(p.r(), p.g(), p.b(), a);
+                    let dst = sprite.get_pixel(sx, sy);
+                    sprite.set_pixel(sx, sy, src.alpha_blend(&dst));
+                }
+                PixelMode::Custom => match &self.custom_pixel_func {
+                    Some(func) => {
+                        let dst = sprite.get_pixel(sx, sy);
+                        sprite.set_pixel(sx, sy, func(x, y, p, dst));
+                    }
+                    //The function was taken away after switching
+                    None => sprite.set_pixel(sx, sy, p),
+                },
+            }
         }
     }
     //DRAW ROUTINES
@@ -440,6 +723,41 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         }
     }
 
+    pub fn draw_sprite(&mut self, pos: Vi2d, sprite: &Sprite, scale: u32, flip: SpriteFlip) {
+        let size = Vi2d::new(sprite.width as i32, sprite.height as i32);
+        self.draw_partial_sprite(pos, sprite, Vi2d::new(0, 0), size, scale, flip);
+    }
+
+    /// Draws the `size` region at `source_pos` of `sprite`, each pixel
+    /// `scale` pixels wide.
+    pub fn draw_partial_sprite(
+        &mut self,
+        pos: Vi2d,
+        sprite: &Sprite,
+        source_pos: Vi2d,
+        size: Vi2d,
+        scale: u32,
+        flip: SpriteFlip,
+    ) {
+        let scale = scale.max(1) as i32;
+        let (flip_x, flip_y) = super::animation::flip_axes(flip);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let sx = if flip_x { size.x - 1 - x } else { x };
+                let sy = if flip_y { size.y - 1 - y } else { y };
+                let p = sprite.get_pixel((source_pos.x + sx) as u32, (source_pos.y + sy) as u32);
+                for i in 0..scale {
+                    for j in 0..scale {
+                        let (dx, dy) = (pos.x + x * scale + i, pos.y + y * scale + j);
+                        if dx >= 0 && dy >= 0 {
+                            self.draw(dx, dy, p);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     pub fn draw_line(&mut self, pos1: Vi2d, pos2: Vi2d, p: Pixel) {
         self.draw_line_xy(pos1.x, pos1.y, pos2.x, pos2.y, p);
     }
@@ -877,7 +1195,21 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
                 }
             }
         }
-        //self.font_decal = Decal::create(Some(font_sprite), &mut self.renderer);
+        self.font_decal = Decal::create(Some(font_sprite.clone()), &mut self.renderer);
+        self.fonts.set_default_font(Font::Bitmap {
+            sheet: font_sprite,
+            glyph_size: Vi2d::new(8, 8),
+        });
+    }
+
+    /// Loads a TTF or OTF font for use with `set_font`.
+    pub fn load_font(&mut self, path: &str) -> Result<FontId, Rcode> {
+        Ok(self.fonts.add_font(Font::load_from_file(path)?))
+    }
+
+    /// Sets the font and pixel size used by the `draw_string_decal` family.
+    pub fn set_font(&mut self, font: FontId, size: f32) {
+        self.fonts.set_font(font, size);
     }
 
     pub fn draw_decal(&mut self, pos: Vf2d, decal: Arc<SmallD>) {
@@ -898,24 +1230,16 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         scale: Vf2d,
         tint: Pixel,
     ) {
-        let screen_space_pos = Vf2d::from((
+        let size = Vf2d::new(decal.sprite.width as f32, decal.sprite.height as f32);
+        self.draw_partial_decal(pos, decal, Vf2d::new(0.0, 0.0), size, scale, tint);
+    }
+
+    //Pixel position on the screen to clip space
+    fn screen_to_ndc(&self, pos: Vf2d) -> Vf2d {
+        Vf2d::new(
             (pos.x * self.inv_screen_size.x) * 2.0 - 1.0,
             ((pos.y * self.inv_screen_size.y) * 2.0 - 1.0) * -1.0,
-        ));
-        let screen_space_dim = Vf2d::from((
-            screen_space_pos.x + (2.0 * (decal.sprite.width as f32) * self.inv_screen_size.x),
-            screen_space_pos.y - (2.0 * (decal.sprite.height as f32) * self.inv_screen_size.y),
-        ));
-        let mut di = DecalInstance {
-            decal: Some(decal),
-            ..Default::default()
-        };
-        di.tint[0] = tint;
-        di.pos[0] = Vf2d::from((screen_space_pos.x, screen_space_pos.y));
-        di.pos[1] = Vf2d::from((screen_space_pos.x, screen_space_dim.y));
-        di.pos[2] = Vf2d::from((screen_space_dim.x, screen_space_dim.y));
-        di.pos[3] = Vf2d::from((screen_space_dim.x, screen_space_pos.y));
-        self.push_decal_instance(di);
+        )
     }
 
     pub fn draw_partial_decal(
@@ -927,40 +1251,22 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         scale: Vf2d,
         tint: Pixel,
     ) {
-        let screen_space_pos = Vf2d::from((
-            (pos.x * self.inv_screen_size.x) * 2.0 - 1.0,
-            ((pos.y * self.inv_screen_size.y) * 2.0 - 1.0) * -1.0,
-        ));
-        let screen_space_dim = Vf2d::from((
-            screen_space_pos.x + (2.0 * (source_size.x as f32) * self.inv_screen_size.x) * scale.x,
-            screen_space_pos.y - (2.0 * (source_size.y as f32) * self.inv_screen_size.y) * scale.y,
-        ));
-        let mut di = DecalInstance::default();
-        di.tint[0] = tint;
-
-        di.pos[0] = Vf2d::from((screen_space_pos.x, screen_space_pos.y));
-        di.pos[1] = Vf2d::from((screen_space_pos.x, screen_space_dim.y));
-        di.pos[2] = Vf2d::from((screen_space_dim.x, screen_space_dim.y));
-        di.pos[3] = Vf2d::from((screen_space_dim.x, screen_space_pos.y));
-
-        let uvtl = Vf2d::from((
-            source_pos.x * decal.uv_scale.x,
-            source_pos.y * decal.uv_scale.y,
-        ));
-
-        let uvbr = Vf2d::from((
-            uvtl.x + (source_size.x * decal.uv_scale.x),
-            uvtl.y + (source_size.y * decal.uv_scale.y),
-        ));
-
-        di.uv[0] = Vf2d::from((uvtl.x, uvtl.y));
-        di.uv[1] = Vf2d::from((uvtl.x, uvbr.y));
-        di.uv[2] = Vf2d::from((uvbr.x, uvbr.y));
-        di.uv[3] = Vf2d::from((uvbr.x, uvtl.y));
-        di.decal = Some(decal);
+        let br = pos + source_size * scale;
+        let mut di = DecalInstance {
+            uv: decal.uv_quad(source_pos, source_size),
+            tint: [tint; 4],
+            decal: Some(decal),
+            ..Default::default()
+        };
+        di.pos[0] = self.screen_to_ndc(pos);
+        di.pos[1] = self.screen_to_ndc(Vf2d::new(pos.x, br.y));
+        di.pos[2] = self.screen_to_ndc(br);
+        di.pos[3] = self.screen_to_ndc(Vf2d::new(br.x, pos.y));
         self.push_decal_instance(di);
     }
 
+    /// Draws `decal` turned by `angle` radians about `center`, a point of
+    /// the decal in pixels, which is placed at `pos`.
     pub fn draw_rotated_decal(
         &mut self,
         pos: Vf2d,
@@ -970,35 +1276,8 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         scale: Vf2d,
         tint: Pixel,
     ) {
-        let mut di = DecalInstance::default();
-        di.tint[0] = tint;
-        di.pos[0] = Vf2d::new(0.0 - center.x * scale.x, 0.0 - center.y * scale.y);
-        di.pos[1] = Vf2d::new(
-            0.0 - center.x * scale.x,
-            decal.sprite.height as f32 - center.y * scale.y,
-        );
-        di.pos[2] = Vf2d::new(
-            decal.sprite.width as f32 - center.x * scale.x,
-            decal.sprite.height as f32 - center.y * scale.y,
-        );
-        di.pos[3] = Vf2d::new(
-            decal.sprite.width as f32 - center.x * scale.x,
-            0.0 - center.y * scale.y,
-        );
-        let (c, s) = (angle.cos(), angle.sin());
-        for i in 0..4 {
-            di.pos[i] = Vf2d::new(
-                di.pos[1].x * c - di.pos[i].y * s,
-                di.pos[i].x * s + di.pos[i].y * c,
-            );
-            di.pos[i] = Vf2d::new(
-                di.pos[i].x * self.inv_screen_size.x * 2.0 - 1.0,
-                di.pos[i].y * self.inv_screen_size.y * 2.0 - 1.0,
-            );
-            di.pos[i].y += -1.0;
-        }
-        di.decal = Some(decal);
-        self.push_decal_instance(di);
+        let size = Vf2d::new(decal.sprite.width as f32, decal.sprite.height as f32);
+        self.draw_partial_rotated_decal(pos, decal, angle, center, Vf2d::new(0.0, 0.0), size, scale, tint);
     }
 
     pub fn draw_warped_decal(&mut self, decal: Arc<SmallD>, pos: &[Vf2d]) {
@@ -1006,48 +1285,14 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
     }
 
     pub fn draw_warped_decal_with_tint(&mut self, decal: Arc<SmallD>, pos: &[Vf2d], tint: Pixel) {
-        let mut di = DecalInstance {
-            decal: Some(decal),
-            ..Default::default()
-        };
-        di.tint[0] = tint;
-        let mut center = Vf2d::new(0.0, 0.0);
-        let mut rd: f32 = (pos[2].x - pos[0].x) * (pos[3].y - pos[1].y)
-            - (pos[3].x - pos[1].x) * (pos[2].y - pos[0].y);
-        if rd != 0.0 {
-            rd = 1.0 / rd;
-            let rn: f32 = ((pos[3].x - pos[1].x) * (pos[0].y - pos[1].y)
-                - (pos[3].y - pos[1].y) * (pos[0].x - pos[1].x))
-                * rd;
-            let sn: f32 = ((pos[2].x - pos[0].x) * (pos[0].y - pos[1].y)
-                - (pos[2].y - pos[0].y) * (pos[0].x - pos[1].x))
-                * rd;
-            if !((0.0..=1.0).contains(&rn) || (0.0..=1.0).contains(&sn)) {
-                let i = pos[2] - pos[0];
-                center = pos[0] + Vf2d::new(rn * i.x, rn * i.y);
-            }
-            let mut d: [f32; 4] = [0.0; 4];
-            for i in 0..4 {
-                d[i] = (pos[i] - center).mag();
-            }
-            for i in 0..4 {
-                let q = if d[i] == 0.0 {
-                    1.0
-                } else {
-                    (d[i] + d[(i + 2) & 3]) / d[(i + 2) & 3]
-                };
-                di.uv[i].x *= q;
-                di.uv[i].y *= q;
-                di.w[i] *= q;
-                di.pos[i] = Vf2d::new(
-                    (pos[i].x * self.inv_screen_size.x) * 2.0 - 1.0,
-                    ((pos[i].y * self.inv_screen_size.y) * 2.0 - 1.0) * -1.0,
-                );
-            }
-            self.push_decal_instance(di);
-        }
+        let size = Vf2d::new(decal.sprite.width as f32, decal.sprite.height as f32);
+        self.draw_partial_warped_decal(decal, pos.to_vec(), Vf2d::new(0.0, 0.0), size, tint);
     }
 
+    /// Stretches part of `decal` over the quad `pos`, corners in the order
+    /// top left, bottom left, bottom right, top right. Texture coordinates
+    /// are interpolated in perspective, so the quad looks like a tilted
+    /// rectangle rather than two skewed triangles.
     pub fn draw_partial_warped_decal(
         &mut self,
         decal: Arc<SmallD>,
@@ -1056,60 +1301,50 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         source_size: Vf2d,
         tint: Pixel,
     ) {
-        let mut di = DecalInstance::default();
-        di.tint[0] = tint;
-        let mut center = Vf2d::new(0.0, 0.0);
-        let mut rd: f32 = (pos[2].x - pos[0].x) * (pos[3].y - pos[1].y)
+        if pos.len() < 4 {
+            return;
+        }
+        let mut di = DecalInstance {
+            uv: decal.uv_quad(source_pos, source_size),
+            tint: [tint; 4],
+            decal: Some(decal),
+            ..Default::default()
+        };
+        let rd: f32 = (pos[2].x - pos[0].x) * (pos[3].y - pos[1].y)
             - (pos[3].x - pos[1].x) * (pos[2].y - pos[0].y);
-        if rd != 0.0 {
-            let uvtl = Vf2d::new(
-                source_pos.x * decal.uv_scale.x,
-                source_pos.y * decal.uv_scale.y,
-            );
-
-            let uvbr = Vf2d::new(
-                uvtl.x + (source_size.x * decal.uv_scale.x),
-                uvtl.y + (source_size.y * decal.uv_scale.y),
-            );
-
-            di.uv[0] = Vf2d::new(uvtl.x, uvtl.y);
-            di.uv[1] = Vf2d::new(uvtl.x, uvbr.y);
-            di.uv[2] = Vf2d::new(uvbr.x, uvbr.y);
-            di.uv[3] = Vf2d::new(uvbr.x, uvtl.y);
-            rd = 1.0 / rd;
-            let rn: f32 = ((pos[3].x - pos[1].x) * (pos[0].y - pos[1].y)
-                - (pos[3].y - pos[1].y) * (pos[0].x - pos[1].x))
-                * rd;
-            let sn: f32 = ((pos[2].x - pos[0].x) * (pos[0].y - pos[1].y)
-                - (pos[2].y - pos[0].y) * (pos[0].x - pos[1].x))
-                * rd;
-            if !((0.0..=1.0).contains(&rn) || (0.0..=1.0).contains(&sn)) {
-                let i = pos[2] - pos[0];
-                center = Vf2d::new(pos[0].x + rn, pos[0].y + rn) * i;
-            }
-            let mut d: [f32; 4] = [0.0; 4];
-            for i in 0..4 {
-                d[i] = (pos[i] - center).mag();
-            }
-            for i in 0..4 {
-                let q = if d[i] == 0.0 {
-                    1.0
-                } else {
-                    (d[i] + d[(i + 2) & 3]) / d[(i + 2) & 3]
-                };
-                di.uv[i].x *= q;
-                di.uv[i].y *= q;
-                di.w[i] *= q;
-                di.pos[i] = Vf2d::new(
-                    (pos[i].x * self.inv_screen_size.x) * 2.0 - 1.0,
-                    ((pos[i].y * self.inv_screen_size.y) * 2.0 - 1.0) * -1.0,
-                );
-            }
-            di.decal = Some(decal);
-            self.push_decal_instance(di);
+        if rd == 0.0 {
+            return;
+        }
+        let rd = 1.0 / rd;
+        let rn: f32 = ((pos[3].x - pos[1].x) * (pos[0].y - pos[1].y)
+            - (pos[3].y - pos[1].y) * (pos[0].x - pos[1].x))
+            * rd;
+        let sn: f32 = ((pos[2].x - pos[0].x) * (pos[0].y - pos[1].y)
+            - (pos[2].y - pos[0].y) * (pos[0].x - pos[1].x))
+            * rd;
+        //Where the diagonals cross
+        let mut center = Vf2d::new(0.0, 0.0);
+        if (0.0..=1.0).contains(&rn) && (0.0..=1.0).contains(&sn) {
+            center = pos[0] + (pos[2] - pos[0]) * rn;
         }
+        let mut d: [f32; 4] = [0.0; 4];
+        for i in 0..4 {
+            d[i] = (pos[i] - center).mag();
+        }
+        for i in 0..4 {
+            let q = if d[i] == 0.0 {
+                1.0
+            } else {
+                (d[i] + d[(i + 2) & 3]) / d[(i + 2) & 3]
+            };
+            di.uv[i] *= q;
+            di.w[i] *= q;
+            di.pos[i] = self.screen_to_ndc(pos[i]);
+        }
+        self.push_decal_instance(di);
     }
 
+    /// A quad with its own texture coordinates and corner colours.
     pub fn draw_explicit_decal(
         &mut self,
         decal: Arc<SmallD>,
@@ -1118,17 +1353,11 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         col: Vec<Pixel>,
     ) {
         let mut di = DecalInstance::default();
-        if decal.id > 0 {
+        if decal.id >= 0 {
             di.decal = Some(decal);
-        } else {
-            di.decal = None;
         }
-
         for i in 0..4 {
-            di.pos[i] = Vf2d::from((
-                (pos[i].x * self.inv_screen_size.x) * 2.0 - 1.0,
-                (pos[i].y * self.inv_screen_size.y) * 2.0 - 1.0,
-            ));
+            di.pos[i] = self.screen_to_ndc(pos[i]);
             di.uv[i] = uv[i];
             di.tint[i] = col[i];
         }
@@ -1136,15 +1365,7 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
     }
 
     pub fn fill_rect_decal(&mut self, pos: Vf2d, size: Vf2d, col: Pixel) {
-        let points = vec![
-            pos,
-            Vf2d::new(pos.x, pos.y + size.y),
-            pos + size,
-            Vf2d::new(pos.x + size.x, pos.y),
-        ];
-        let uvs = vec![(1.0, 1.0).into(); 4];
-        let cols = vec![col, col, col, col];
-        self.draw_explicit_decal(Decal::empty().get(), points, uvs, cols);
+        self.gradient_fill_rect_decal(pos, size, col, col, col, col);
     }
 
     pub fn gradient_fill_rect_decal(
@@ -1162,7 +1383,7 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
             pos + size,
             Vf2d::new(pos.x + size.x, pos.y),
         ];
-        let uvs = vec![(1.0, 1.0).into(); 4];
+        let uvs = vec![(0.0, 0.0).into(); 4];
         let cols = vec![colTL, colBL, colBR, colTR];
         self.draw_explicit_decal(Decal::empty().get(), points, uvs, cols);
     }
@@ -1178,41 +1399,27 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         scale: Vf2d,
         tint: Pixel,
     ) {
-        let mut di = DecalInstance::default();
-        di.tint[0] = tint;
-        di.pos[0] = (Vf2d::new(0.0, 0.0) - center) * scale;
-        di.pos[1] = (Vf2d::new(0.0, source_size.y) - center) * scale;
-        di.pos[2] = (Vf2d::new(source_size.x, source_size.y) - center) * scale;
-        di.pos[3] = (Vf2d::new(source_size.x, 0.0) - center) * scale;
+        let mut di = DecalInstance {
+            uv: decal.uv_quad(source_pos, source_size),
+            tint: [tint; 4],
+            decal: Some(decal),
+            ..Default::default()
+        };
+        let corners = [
+            Vf2d::new(0.0, 0.0),
+            Vf2d::new(0.0, source_size.y),
+            source_size,
+            Vf2d::new(source_size.x, 0.0),
+        ];
         let (c, s) = (angle.cos(), angle.sin());
         for i in 0..4 {
-            di.pos[i] = Vf2d::new(
-                di.pos[1].x * c - di.pos[i].y * s,
-                di.pos[i].x * s + di.pos[i].y * c,
-            );
-            di.pos[i] = Vf2d::new(
-                di.pos[i].x * self.inv_screen_size.x * 2.0 - 1.0,
-                di.pos[i].y * self.inv_screen_size.y * 2.0 - 1.0,
-            );
-            di.pos[i].y += -1.0;
+            let p = (corners[i] - center) * scale;
+            let p = Vf2d::new(p.x * c - p.y * s, p.x * s + p.y * c);
+            di.pos[i] = self.screen_to_ndc(pos + p);
         }
-        let uvtl = Vf2d::new(
-            source_pos.x * decal.uv_scale.x,
-            source_pos.y * decal.uv_scale.y,
-        );
-
-        let uvbr = Vf2d::new(
-            uvtl.x + (source_size.x * decal.uv_scale.x),
-            uvtl.y + (source_size.y * decal.uv_scale.y),
-        );
-
-        di.uv[0] = Vf2d::new(uvtl.x, uvtl.y);
-        di.uv[1] = Vf2d::new(uvtl.x, uvbr.y);
-        di.uv[2] = Vf2d::new(uvbr.x, uvbr.y);
-        di.uv[3] = Vf2d::new(uvbr.x, uvtl.y);
-        di.decal = Some(decal);
         self.push_decal_instance(di);
     }
+
     pub fn draw_string_decal(&mut self, pos: Vf2d, text: &str) {
         self.draw_string_decal_with_color_and_scale(pos, text, Pixel::WHITE, Vf2d::new(1.0, 1.0));
     }
@@ -1232,40 +1439,118 @@ impl<'e, 'l, D: OGData + 'static> OGEngine<D> {
         col: Pixel,
         scale: Vf2d,
     ) {
-        //self.draw_decal(pos, &FONT_DECAL, scale, Pixel::WHITE);
-        let mut spos = Vf2d::new(0.0, 0.0);
-        for c in text.chars() {
-            if c == '\n' {
-                spos.x = 0.0;
-                spos.y += 8.0 * scale.y;
-            } else {
-                let ox = (c as u8 - 32) % 16;
-                let oy = (c as u8 - 32) / 16;
-                self.draw_partial_decal(
-                    pos + spos,
-                    self.font_decal.get(),
-                    Vf2d::new(ox as f32 * 8.0, oy as f32 * 8.0),
-                    Vf2d::new(8.0, 8.0),
-                    scale,
-                    col,
-                );
-                spos.x += 8.0 * scale.x;
-            }
+        let mut options = self.text_options();
+        options.style.color = col;
+        options.style.scale = scale;
+        let layout = self.layout_text(text, &options);
+        self.draw_text_decal(pos, &layout);
+    }
+
+    /// Draws `text` corrupted by `effect` at `time` seconds. Layouts can be
+    /// corrupted directly with `TextEffect::apply`.
+    pub fn draw_string_decal_with_effect(
+        &mut self,
+        pos: Vf2d,
+        text: &str,
+        col: Pixel,
+        effect: &TextEffect,
+        time: f32,
+    ) {
+        let mut options = self.text_options();
+        options.style.color = col;
+        let layout = effect.apply(&self.layout_text(text, &options), time);
+        self.draw_text_decal(pos, &layout);
+    }
+
+    /// Text options using the current font.
+    pub fn text_options(&self) -> TextOptions {
+        let (font, size) = self.fonts.current_font();
+        TextOptions {
+            style: TextStyle {
+                font,
+                size,
+                ..Default::default()
+            },
+            ..Default::default()
         }
     }
 
-    pub fn get_text_size(&self, s: String) -> Vi2d {
-        let (mut size, mut pos) = (Vi2d::new(0, 1), Vi2d::new(0, 1));
-        for c in s.chars() {
-            if c == '\n' {
-                pos.y += 1;
-                pos.x = 0;
-            } else {
-                pos.x += 1
+    pub fn layout_text(&self, text: &str, options: &TextOptions) -> TextLayout {
+        TextLayout::new(&self.fonts, text, options)
+    }
+
+    pub fn draw_text_decal(&mut self, pos: Vf2d, layout: &TextLayout) {
+        for g in &layout.glyphs {
+            let style = g.style;
+            if let Some(glyph) = self.fonts.glyph(&mut self.renderer, style.font, g.c, style.size) {
+                if glyph.source_size.x > 0.0 && glyph.source_size.y > 0.0 {
+                    let glyph_scale = Vf2d::new(
+                        glyph.size.x / glyph.source_size.x * style.scale.x,
+                        glyph.size.y / glyph.source_size.y * style.scale.y,
+                    );
+                    self.draw_partial_decal(
+                        pos + g.pos + glyph.offset * style.scale,
+                        self.fonts.atlas.decal(),
+                        glyph.source_pos,
+                        glyph.source_size,
+                        glyph_scale,
+                        style.color,
+                    );
+                }
+            }
+        }
+        self.fonts.atlas.upload(&self.renderer);
+    }
+
+    /// Blends `layout` onto the current draw target, which has to be an Image
+    /// layer.
+    pub fn draw_text(&mut self, pos: Vi2d, layout: &TextLayout) {
+        //Rasterize everything first, the atlas may be rebuilt while doing so
+        let mut quads = vec![];
+        for g in &layout.glyphs {
+            if let Some(glyph) = self.fonts.glyph(&mut self.renderer, g.style.font, g.c, g.style.size) {
+                if glyph.source_size.x > 0.0 && glyph.source_size.y > 0.0 {
+                    quads.insert(quads.len(), (g, glyph));
+                }
+            }
+        }
+        self.fonts.atlas.upload(&self.renderer);
+
+        let atlas = self.fonts.atlas.sprite();
+        let draw_target = self.draw_target;
+        let sprite = match self.layers.iter_mut().find(|l| l.id == draw_target) {
+            Some(LayerDesc {
+                layer_info: LayerInfo::Image(image),
+                ..
+            }) => &mut image.sprite,
+            _ => return,
+        };
+        for (g, glyph) in quads {
+            let top_left = pos.to_vf2d() + g.pos + glyph.offset * g.style.scale;
+            let size = glyph.size * g.style.scale;
+            for dy in 0..size.y.ceil() as i32 {
+                for dx in 0..size.x.ceil() as i32 {
+                    //Nearest sample from the atlas, same as the decal sampler
+                    let sx = glyph.source_pos.x + (dx as f32 + 0.5) / size.x * glyph.source_size.x;
+                    let sy = glyph.source_pos.y + (dy as f32 + 0.5) / size.y * glyph.source_size.y;
+                    let src = atlas.get_pixel(sx as u32, sy as u32);
+                    let alpha = src.a() as u32 * g.style.color.a() as u32 / 255;
+                    if alpha == 0 {
+                        continue;
+                    }
+                    let (x, y) = (top_left.x as i32 + dx, top_left.y as i32 + dy);
+                    if x < 0 || y < 0 {
+                        continue;
+                    }
+                    let dst = sprite.get_pixel(x as u32, y as u32);
+                    sprite.set_pixel(x as u32, y as u32, blend_over(dst, g.style.color, alpha as u8));
+                }
             }
-            size.x = std::cmp::max(size.x, pos.x);
-            size.y = std::cmp::max(size.y, pos.y);
         }
-        Vi2d::new(size.x * 8, size.y * 8)
+    }
+
+    pub fn get_text_size(&self, s: String) -> Vi2d {
+        let size = self.layout_text(&s, &self.text_options()).size;
+        Vi2d::new(size.x.ceil() as i32, size.y.ceil() as i32)
     }
 }
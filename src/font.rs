@@ -0,0 +1,416 @@
+use super::{
+    atlas::ShelfPacker,
+    asset::{Asset, AssetError, LoadContext},
+    decal::{Decal, SmallD},
+    og_engine::Rcode,
+    pixel::Pixel,
+    renderer::Renderer,
+    sprite::Sprite,
+    text::{TextLayout, TextOptions, TextStyle},
+    util::{Vf2d, Vi2d},
+};
+use ab_glyph::{Font as _, ScaleFont as _};
+use std::{collections::HashMap, sync::Arc};
+
+pub type FontId = usize;
+
+/// The 8x8 olc font built by `OGEngine::construct_font_sheet`.
+pub const DEFAULT_FONT: FontId = 0;
+pub const DEFAULT_FONT_SIZE: f32 = 8.0;
+
+const ATLAS_START_SIZE: u32 = 512;
+const ATLAS_MAX_SIZE: u32 = 4096;
+//Empty border around every glyph so linear sampling doesn't bleed
+const GLYPH_PADDING: u32 = 1;
+
+#[derive(Clone)]
+pub enum Font {
+    /// A fixed size sheet of 16 glyphs per row, starting at ' '.
+    Bitmap {
+        sheet: Sprite,
+        glyph_size: Vi2d,
+    },
+    Outline(ab_glyph::FontArc),
+}
+
+impl Font {
+    /// Loads a TTF or OTF font.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Rcode> {
+        ab_glyph::FontArc::try_from_vec(bytes)
+            .map(Font::Outline)
+            .map_err(|_| Rcode::Fail)
+    }
+
+    pub fn load_from_file(path: &str) -> Result<Self, Rcode> {
+        Self::from_bytes(std::fs::read(path).map_err(|_| Rcode::NoFile)?)
+    }
+
+    pub fn metrics(&self, size: f32) -> FontMetrics {
+        match self {
+            Font::Bitmap { glyph_size, .. } => {
+                let scale = size / glyph_size.y as f32;
+                FontMetrics {
+                    ascent: size,
+                    descent: 0.0,
+                    line_gap: 0.0,
+                    line_height: glyph_size.y as f32 * scale,
+                }
+            }
+            Font::Outline(font) => {
+                let scaled = font.as_scaled(size);
+                FontMetrics {
+                    ascent: scaled.ascent(),
+                    descent: scaled.descent(),
+                    line_gap: scaled.line_gap(),
+                    line_height: scaled.height() + scaled.line_gap(),
+                }
+            }
+        }
+    }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        match self {
+            Font::Bitmap { .. } => (' '..='\u{7f}').contains(&c),
+            Font::Outline(font) => font.glyph_id(c).0 != 0,
+        }
+    }
+
+    /// Horizontal distance to the next character, kerning not included.
+    pub fn advance(&self, c: char, size: f32) -> f32 {
+        match self {
+            Font::Bitmap { glyph_size, .. } => glyph_size.x as f32 * size / glyph_size.y as f32,
+            Font::Outline(font) => font.as_scaled(size).h_advance(font.glyph_id(c)),
+        }
+    }
+
+    pub fn kern(&self, a: char, b: char, size: f32) -> f32 {
+        match self {
+            Font::Bitmap { .. } => 0.0,
+            Font::Outline(font) => font
+                .as_scaled(size)
+                .kern(font.glyph_id(a), font.glyph_id(b)),
+        }
+    }
+}
+
+impl Asset for Font {
+    fn load(bytes: &[u8], ctx: &mut LoadContext) -> Result<Self, AssetError> {
+        Font::from_bytes(bytes.to_vec()).map_err(|_| AssetError::Decode(ctx.path().to_string()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FontMetrics {
+    /// Distance from the top of a line to the baseline.
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of a line, usually negative.
+    pub descent: f32,
+    pub line_gap: f32,
+    /// Distance between the tops of two lines.
+    pub line_height: f32,
+}
+
+/// Where a glyph lives in the atlas and how to place it relative to the
+/// pen position (top of the line).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AtlasGlyph {
+    pub source_pos: Vf2d,
+    pub source_size: Vf2d,
+    /// Offset from the pen position to the top left of the glyph.
+    pub offset: Vf2d,
+    /// Size on screen at scale 1. Differs from `source_size` for bitmap
+    /// fonts drawn at a size other than their native one.
+    pub size: Vf2d,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: FontId,
+    c: char,
+    //Quarter pixel steps so near identical sizes share glyphs
+    size: u32,
+}
+
+/// Rasterizes glyphs on demand into a single decal texture.
+pub struct GlyphAtlas {
+    decal: Decal,
+    sprite: Sprite,
+    packer: ShelfPacker,
+    glyphs: HashMap<GlyphKey, AtlasGlyph>,
+    //Region of `sprite` that changed since the last upload, as (min, max)
+    dirty: Option<(Vi2d, Vi2d)>,
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self {
+            decal: Decal::empty(),
+            sprite: Sprite::new(0, 0),
+            packer: ShelfPacker::default(),
+            glyphs: HashMap::new(),
+            dirty: None,
+        }
+    }
+}
+
+impl GlyphAtlas {
+    pub fn decal(&self) -> Arc<SmallD> {
+        self.decal.get()
+    }
+
+    pub fn size(&self) -> Vi2d {
+        Vi2d::new(self.sprite.width as i32, self.sprite.height as i32)
+    }
+
+    /// CPU copy of the atlas, for drawing text onto Image layers.
+    pub fn sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    //Starts over with an empty texture. Decal instances already queued this
+    // frame keep pointing at the old texture, so they still draw correctly.
+    fn reset(&mut self, renderer: &mut Renderer, size: u32) {
+        self.upload(renderer);
+        self.sprite = Sprite::new(size, size);
+        self.decal = Decal::create(Some(self.sprite.clone()), renderer);
+        self.packer = ShelfPacker::new(size, size);
+        self.glyphs.clear();
+        self.dirty = None;
+    }
+
+    //None for a glyph bigger than the largest atlas
+    fn insert(&mut self, renderer: &mut Renderer, w: u32, h: u32) -> Option<(u32, u32)> {
+        let (pw, ph) = (w + GLYPH_PADDING * 2, h + GLYPH_PADDING * 2);
+        if pw > ATLAS_MAX_SIZE || ph > ATLAS_MAX_SIZE {
+            return None;
+        }
+        if self.sprite.width == 0 {
+            self.reset(renderer, ATLAS_START_SIZE);
+        }
+        loop {
+            if let Some((x, y)) = self.packer.pack(pw, ph) {
+                return Some((x + GLYPH_PADDING, y + GLYPH_PADDING));
+            }
+            let size = (self.sprite.width * 2).min(ATLAS_MAX_SIZE);
+            if size == self.sprite.width && self.glyphs.is_empty() {
+                return None;
+            }
+            self.reset(renderer, size);
+        }
+    }
+
+    //Glyphs that don't fit are cached as empty, like whitespace, so they are
+    // skipped instead of rasterized again every frame
+    fn skip(&mut self, key: GlyphKey) -> AtlasGlyph {
+        log::warn!("Glyph {:?} is too big for the font atlas", key.c);
+        self.glyphs.insert(key, AtlasGlyph::default());
+        AtlasGlyph::default()
+    }
+
+    fn mark_dirty(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        let (min, max) = (
+            Vi2d::new(x as i32, y as i32),
+            Vi2d::new((x + w) as i32, (y + h) as i32),
+        );
+        self.dirty = Some(match self.dirty {
+            Some((a, b)) => (
+                Vi2d::new(a.x.min(min.x), a.y.min(min.y)),
+                Vi2d::new(b.x.max(max.x), b.y.max(max.y)),
+            ),
+            None => (min, max),
+        });
+    }
+
+    /// Sends any newly rasterized glyphs to the GPU.
+    pub fn upload(&mut self, renderer: &Renderer) {
+        let id = self.decal.get().id;
+        if let (Some((min, max)), true) = (self.dirty.take(), id >= 0) {
+            let size = max - min;
+            let region = self.sprite.get_region(min, size);
+            renderer.update_texture_region(
+                id as u32,
+                min.x as u32,
+                min.y as u32,
+                size.x as u32,
+                size.y as u32,
+                &region,
+            );
+        }
+    }
+}
+
+/// All loaded fonts plus the atlas their glyphs are cached in.
+pub struct FontSystem {
+    fonts: Vec<Font>,
+    /// Fonts to try, in order, when a font is missing a character.
+    pub fallbacks: Vec<FontId>,
+    pub atlas: GlyphAtlas,
+    font: FontId,
+    size: f32,
+}
+
+impl Default for FontSystem {
+    fn default() -> Self {
+        Self {
+            fonts: vec![],
+            fallbacks: vec![],
+            atlas: GlyphAtlas::default(),
+            font: DEFAULT_FONT,
+            size: DEFAULT_FONT_SIZE,
+        }
+    }
+}
+
+impl FontSystem {
+    /// Installs the font used when no other font is set.
+    pub fn set_default_font(&mut self, font: Font) {
+        if self.fonts.is_empty() {
+            self.fonts.insert(0, font);
+        } else {
+            self.fonts[DEFAULT_FONT] = font;
+        }
+    }
+
+    pub fn add_font(&mut self, font: Font) -> FontId {
+        self.fonts.insert(self.fonts.len(), font);
+        self.fonts.len() - 1
+    }
+
+    pub fn get_font(&self, id: FontId) -> Option<&Font> {
+        self.fonts.get(id)
+    }
+
+    /// The font and pixel size used by `draw_string_decal` and `get_text_size`.
+    pub fn set_font(&mut self, font: FontId, size: f32) {
+        self.font = font;
+        self.size = size;
+    }
+
+    pub fn current_font(&self) -> (FontId, f32) {
+        (self.font, self.size)
+    }
+
+    pub fn metrics(&self, font: FontId, size: f32) -> FontMetrics {
+        self.fonts
+            .get(font)
+            .map(|f| f.metrics(size))
+            .unwrap_or(FontMetrics {
+                ascent: size,
+                line_height: size,
+                ..Default::default()
+            })
+    }
+
+    /// The font that will actually draw `c`, after falling back if `font`
+    /// doesn't have it.
+    pub fn resolve(&self, font: FontId, c: char) -> FontId {
+        if self.fonts.get(font).map(|f| f.has_glyph(c)).unwrap_or(false) {
+            return font;
+        }
+        self.fallbacks
+            .iter()
+            .copied()
+            .chain(0..self.fonts.len())
+            .find(|id| self.fonts.get(*id).map(|f| f.has_glyph(c)).unwrap_or(false))
+            .unwrap_or(font)
+    }
+
+    /// Pen movement after drawing `c`, including kerning against `next`.
+    pub fn advance(&self, font: FontId, c: char, next: Option<char>, size: f32) -> f32 {
+        let id = self.resolve(font, c);
+        let f = match self.fonts.get(id) {
+            Some(f) => f,
+            None => return 0.0,
+        };
+        let kern = match next {
+            Some(n) if self.resolve(font, n) == id => f.kern(c, n, size),
+            _ => 0.0,
+        };
+        f.advance(c, size) + kern
+    }
+
+    /// Returns the atlas entry for `c`, rasterizing it first if needed.
+    /// Call `atlas.upload` before the frame is drawn.
+    pub fn glyph(&mut self, renderer: &mut Renderer, font: FontId, c: char, size: f32) -> Option<AtlasGlyph> {
+        let id = self.resolve(font, c);
+        let key = GlyphKey {
+            font: id,
+            c,
+            size: (size * 4.0).round() as u32,
+        };
+        if let Some(glyph) = self.atlas.glyphs.get(&key) {
+            return Some(*glyph);
+        }
+        let glyph = match self.fonts.get(id)? {
+            Font::Bitmap { sheet, glyph_size } => {
+                //Anything the sheet doesn't cover is drawn as '?'
+                let index = if (' '..='\u{7f}').contains(&c) { c as u32 - 32 } else { '?' as u32 - 32 };
+                let (gw, gh) = (glyph_size.x as u32, glyph_size.y as u32);
+                let (sx, sy) = ((index % 16) * gw, (index / 16) * gh);
+                let (x, y) = match self.atlas.insert(renderer, gw, gh) {
+                    Some(pos) => pos,
+                    None => return Some(self.atlas.skip(key)),
+                };
+                for py in 0..gh {
+                    for px in 0..gw {
+                        let p = sheet.get_pixel(sx + px, sy + py);
+                        self.atlas.sprite.set_pixel(x + px, y + py, p);
+                    }
+                }
+                self.atlas.mark_dirty(x, y, gw, gh);
+                let scale = size / gh as f32;
+                AtlasGlyph {
+                    source_pos: Vf2d::new(x as f32, y as f32),
+                    source_size: Vf2d::new(gw as f32, gh as f32),
+                    offset: Vf2d::new(0.0, 0.0),
+                    size: Vf2d::new(gw as f32 * scale, gh as f32 * scale),
+                }
+            }
+            Font::Outline(f) => {
+                let scaled = f.as_scaled(size);
+                let g = f
+                    .glyph_id(c)
+                    .with_scale_and_position(size, ab_glyph::point(0.0, scaled.ascent()));
+                match f.outline_glyph(g) {
+                    Some(outline) => {
+                        let bounds = outline.px_bounds();
+                        let (gw, gh) = (bounds.width() as u32, bounds.height() as u32);
+                        let (x, y) = match self.atlas.insert(renderer, gw, gh) {
+                            Some(pos) => pos,
+                            None => return Some(self.atlas.skip(key)),
+                        };
+                        let sprite = &mut self.atlas.sprite;
+                        outline.draw(|px, py, coverage| {
+                            let a = (coverage.clamp(0.0, 1.0) * 255.0) as u8;
+                            sprite.set_pixel(x + px, y + py, Pixel::rgba(255, 255, 255, a));
+                        });
+                        self.atlas.mark_dirty(x, y, gw, gh);
+                        AtlasGlyph {
+                            source_pos: Vf2d::new(x as f32, y as f32),
+                            source_size: Vf2d::new(gw as f32, gh as f32),
+                            offset: Vf2d::new(bounds.min.x, bounds.min.y),
+                            size: Vf2d::new(gw as f32, gh as f32),
+                        }
+                    }
+                    //Whitespace has nothing to draw
+                    None => AtlasGlyph::default(),
+                }
+            }
+        };
+        self.atlas.glyphs.insert(key, glyph);
+        Some(glyph)
+    }
+
+    /// Size of `text` in the current font, one line per '\n'.
+    pub fn text_size(&self, text: &str) -> Vf2d {
+        let options = TextOptions {
+            style: TextStyle {
+                font: self.font,
+                size: self.size,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        TextLayout::new(self, text, &options).size
+    }
+}
@@ -1,18 +1,30 @@
-//use crate::audio::AudioSystem;
 #[cfg(target_arch="wasm32")]
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use super::{
+    audio::{AudioSystem, Listener},
     og_engine::OGGame,
     og_engine::OGData,
     camera::Camera,
-    decal::Decal,
+    atlas::TextureAtlas,
+    decal::{Decal, DecalMode},
     engine::OGEngine,
     layer::{LayerDesc, LayerType, LayerFunc, LayerInfo},
-    platform::{PLATFORM_DATA, Platform, PlatformWindows},
+    pixel::PixelMode,
+    platform::{InputState, Platform, PlatformWindows, WindowState},
     renderer::Renderer,
-    util::{HWButton,  Vf2d, Vi2d, RoundTo},
+    util::{Vf2d, Vi2d, RoundTo},
+    asset::AssetServer,
+    hot_reload::HotReload,
+    font::FontSystem,
+    ui::InputCapture,
+    input::InputMap,
+    gamepad::Gamepads,
+    replay::Replay,
+    text_input::TextInput,
 };
+#[cfg(feature = "debug-gui")]
+use super::debug_gui::DebugGui;
 
 use std::time::UNIX_EPOCH;
 
@@ -61,20 +73,6 @@ pub fn construct<T: 'static + OGGame<D>, D: 'static + OGData>(
     #[cfg(target_arch = "wasm32")]
     console_log::init_with_level(log::Level::Warn);
 
-    unsafe {
-        PLATFORM_DATA.init();
-        PLATFORM_DATA.resolution = Some(Vi2d::from((
-            (screen_width / pixel_width) as i32,
-            (screen_height / pixel_height) as i32,
-        )));
-        if !full_screen {
-            PLATFORM_DATA.window_size =
-                Some(Vi2d::from((screen_width as i32, screen_height as i32)));
-        }
-        PLATFORM_DATA.full_screen = full_screen;
-        PLATFORM_DATA.title = app_name.into();
-        PLATFORM_DATA.pixel_size = Some(Vi2d::new(pixel_width as i32, pixel_height as i32));
-    };
     let game_start = start_game(
         game,
         game_data,
@@ -106,6 +104,7 @@ async fn finish_setup<D: 'static + OGData>(
     full_screen: bool,
     vsync: bool,
     window: winit::window::Window,
+    window_state: WindowState,
 ) -> OGEngine<D>{
 
 
@@ -122,10 +121,10 @@ async fn finish_setup<D: 'static + OGData>(
             .expect("Append canvas to HTML body");
     }
 
-    let renderer: Renderer = Renderer::new(&window).await;
+    let renderer: Renderer = Renderer::new(&window, window_state.window_size).await;
 
-    // let audio_system =
-    //         AudioSystem::create_system();
+    //Follows the camera unless the game turns follow_camera off
+    let audio_system = AudioSystem::create_system().with_listener(Listener::default());
 
     let mut engine = OGEngine {
         app_name: String::from(""),
@@ -146,9 +145,27 @@ async fn finish_setup<D: 'static + OGData>(
         draw_target: 0,
         mouse_position: Vi2d::from((0, 0)),
         font_decal: Decal::empty(),
+        atlas: TextureAtlas::default(),
+        decal_mode: DecalMode::Normal,
+        pixel_mode: PixelMode::Normal,
+        blend_factor: 1.0,
+        custom_pixel_func: None,
+        fonts: FontSystem::default(),
         depth_buffer: vec![],
         camera: Camera::default(),
-        //audio_system,
+        assets: AssetServer::default(),
+        hot_reload: HotReload::default(),
+        input: InputState::default(),
+        window_state,
+        input_capture: InputCapture::default(),
+        gamepads: Gamepads::default(),
+        input_map: InputMap::default(),
+        text_input: TextInput::default(),
+        replay: Replay::default(),
+        #[cfg(feature = "debug-gui")]
+        debug_gui: None,
+        audio_system,
+        audio_occluder: None,
         window,
     };
     engine.init(
@@ -160,6 +177,10 @@ async fn finish_setup<D: 'static + OGData>(
         full_screen,
         vsync,
     );
+    #[cfg(feature = "debug-gui")]
+    {
+        engine.debug_gui = Some(DebugGui::new(&engine.window, &engine.renderer));
+    }
     engine
 }
 
@@ -174,10 +195,18 @@ async fn start_game<T: OGGame<D> + 'static, D: OGData + 'static>(
     full_screen: bool,
     vsync: bool,
 ) {
+    let window_state = WindowState::new(
+        app_name,
+        screen_width,
+        screen_height,
+        pixel_width,
+        pixel_height,
+        full_screen,
+    );
     let (window, event_loop) = PlatformWindows::create_window_pane(
         Vi2d { x: 10, y: 10 },
-        unsafe { PLATFORM_DATA.window_size.unwrap() },
-        unsafe { PLATFORM_DATA.full_screen },
+        window_state.window_size,
+        window_state.full_screen,
     );
 
 
@@ -191,20 +220,17 @@ async fn start_game<T: OGGame<D> + 'static, D: OGData + 'static>(
         full_screen,
         vsync,
         window,
+        window_state,
     ).await;
 
-    unsafe {
-        if PLATFORM_DATA.full_screen {
-            let fwin = PLATFORM_DATA.window_size.unwrap_or_default().to_vf2d();
-            let fres = PLATFORM_DATA.resolution.unwrap_or_default().to_vf2d();
-            PLATFORM_DATA.pixel_size = Some(
-                (
-                    (fwin.x as f32 / fres.x as f32) as i32,
-                    (fwin.y as f32 / fres.y as f32) as i32,
-                )
-                    .into(),
-            );
-        }
+    if engine.window_state.full_screen {
+        let fwin = engine.window_state.window_size.to_vf2d();
+        let fres = engine.window_state.resolution.to_vf2d();
+        engine.window_state.pixel_size = (
+            (fwin.x as f32 / fres.x as f32) as i32,
+            (fwin.y as f32 / fres.y as f32) as i32,
+        )
+            .into();
     }
     engine.construct_font_sheet();
     engine.renderer.setup_layer_pipeline();
@@ -240,7 +266,17 @@ async fn start_game<T: OGGame<D> + 'static, D: OGData + 'static>(
                     if event == &WindowEvent::CloseRequested {
                         *control_flow = ControlFlow::Exit;
                     } else {
-                        PlatformWindows::handle_window_event(&engine.window, &top_event);
+                        //The game still sees the event, egui claims it through input_capture
+                        #[cfg(feature = "debug-gui")]
+                        if let Some(debug_gui) = engine.debug_gui.as_mut() {
+                            debug_gui.on_event(event);
+                        }
+                        PlatformWindows::handle_window_event(
+                            &engine.window,
+                            &top_event,
+                            &mut engine.input,
+                            &mut engine.window_state,
+                        );
                     }
                 }
             },
@@ -254,10 +290,9 @@ async fn start_game<T: OGGame<D> + 'static, D: OGData + 'static>(
                 for layer in engine.layers.iter_mut() {
                     if let LayerInfo::Image(image_info) = &mut layer.layer_info {
                         if layer.shown {
-                            engine
-                                .renderer
-                                .active_decals
-                                .insert(layer.id as usize, layer.id);
+                            let len = engine.renderer.active_decals.len();
+                            engine.renderer.active_decals.insert(len, layer.id);
+                            engine.renderer.layer_params.insert(layer.id, image_info.params());
                         }
                         if image_info.update {
                             engine
@@ -320,6 +355,7 @@ async fn start_game<T: OGGame<D> + 'static, D: OGData + 'static>(
                     },
                 );
                 let window_size = engine.get_window_size();
+                engine.renderer.window_size = window_size;
                 let size = wgpu::Extent3d {
                     width: window_size.x as u32,
                     height: window_size.y as u32,
@@ -351,8 +387,23 @@ async fn start_game<T: OGGame<D> + 'static, D: OGData + 'static>(
                     function.execute(layer, &engine.renderer, &mut engine.game_data, &mut encoder);
                 }
 
+                let decal_layers = engine
+                    .layers
+                    .iter()
+                    .filter(|layer| layer.shown)
+                    .map(|layer| (layer.id, layer.vec_decal_instance.as_slice()))
+                    .collect::<Vec<_>>();
+                engine.renderer.prepare_decals(&decal_layers);
+                for layer in engine.layers.iter_mut() {
+                    layer.vec_decal_instance.clear();
+                }
+
                 //This pass will draw to the screen
                 engine.renderer.draw_layers(&mut encoder);
+                #[cfg(feature = "debug-gui")]
+                if let Some(debug_gui) = engine.debug_gui.as_mut() {
+                    debug_gui.render(&engine.renderer, &mut encoder);
+                }
 
                 engine
                     .renderer
@@ -389,27 +440,45 @@ async fn start_game<T: OGGame<D> + 'static, D: OGData + 'static>(
                         + " ms"
                         + " -- FPS: "
                         + &engine.fps.to_string();
-                    PlatformWindows::set_window_title(&engine.window, sTitle);
+                    PlatformWindows::set_window_title(&engine.window, &mut engine.window_state, sTitle);
                     frame_count = 0;
                     frame_timer -= 1.0;
                 }
-                update_inputs(engine);
+                update_inputs(engine, &mut elapsed_time);
+                if engine.replay.exit_when_done && engine.replay.finished() {
+                    *control_flow = ControlFlow::Exit;
+                }
                 frame_processed = true;
             }
             _ => {
                 //#[cfg(not(target_arch = "wasm32"))]
-                PlatformWindows::handle_window_event(&engine.window, &top_event);
+                PlatformWindows::handle_window_event(
+                    &engine.window,
+                    &top_event,
+                    &mut engine.input,
+                    &mut engine.window_state,
+                );
             }
         }
 
         //Only run the engine if the last frame was drawn
         if frame_processed{
+            engine.update_assets(elapsed_time);
+            #[cfg(feature = "debug-gui")]
+            if let Some(mut debug_gui) = engine.debug_gui.take() {
+                debug_gui.run(engine, elapsed_time as f32);
+                engine.debug_gui = Some(debug_gui);
+            }
             if let Err(message) = game.on_engine_update(engine, elapsed_time) {
                 log::error!("{}", message);
                 println!("{}", message);
                 *control_flow = ControlFlow::Exit;
             }
-            //engine.audio_system.update();
+            engine.audio_system.follow_camera(&engine.camera);
+            if let Some(geometry) = &engine.audio_occluder {
+                engine.audio_system.update_occlusion(geometry.as_ref(), elapsed_time);
+            }
+            engine.audio_system.update(elapsed_time);
             engine.renderer.new_frame();
             engine.window.request_redraw();
             frame_processed = false;
@@ -421,71 +490,26 @@ async fn start_game<T: OGGame<D> + 'static, D: OGData + 'static>(
     });
 }
 
-fn update_inputs<D: OGData>(engine: &mut OGEngine<D>){
-    unsafe{
-        let hw_func = |keys: &mut Vec<HWButton>,
-        keys_old: &mut Vec<bool>,
-        keys_new: &mut Vec<bool>,
-        size: usize| {
-            for i in 0..size as usize {
-                keys[i].pressed = false;
-                keys[i].released = false;
-                if keys_new[i] != keys_old[i] {
-                    if keys_new[i] {
-                        keys[i].pressed = true;
-                        keys[i].released = false;
-                        keys[i].held = true;
-                    } else {
-                        keys[i].pressed = false;
-                        keys[i].released = true;
-                        keys[i].held = false;
-                    }
-                }
-                keys_old[i] = keys_new[i];
-            }
-        };
-        engine.clear_keys();
-        for (key, value_new) in PLATFORM_DATA.new_key_state_map.as_mut().unwrap() {
-            let value_old = PLATFORM_DATA
-                .old_key_state_map
-                .as_mut()
-                .unwrap()
-                .entry(*key)
-                .or_insert(false);
-            let current_key = PLATFORM_DATA
-                .key_map
-                .as_mut()
-                .unwrap()
-                .entry(*key)
-                .or_insert_with(HWButton::new);
-            if value_new != value_old {
-                if *value_new {
-                    (*current_key).pressed = true;
-                    (*current_key).released = false;
-                    (*current_key).held = true;
-                } else {
-                    (*current_key).pressed = false;
-                    (*current_key).released = true;
-                    (*current_key).held = false;
-                }
-            }
-            *value_old = *value_new
-        }
-
-        hw_func(
-            PLATFORM_DATA.mouse_map.as_mut().unwrap(),
-            PLATFORM_DATA.old_mouse_state_map.as_mut().unwrap(),
-            PLATFORM_DATA.new_mouse_state_map.as_mut().unwrap(),
-            3,
-        );
-    }
-    unsafe {
-
-        let window_size = unsafe { PLATFORM_DATA.window_size.as_ref().unwrap() };
-        if let Some(pos) = PLATFORM_DATA.mouse_position_cache {
-            PLATFORM_DATA.mouse_position = Some(pos);
-        }
-        PLATFORM_DATA.mouse_wheel_delta = PLATFORM_DATA.mouse_wheel_delta_cache;
-        PLATFORM_DATA.mouse_wheel_delta_cache = 0;
-    }
+fn update_inputs<D: OGData>(engine: &mut OGEngine<D>, elapsed_time: &mut f64){
+    //The UI claims input again when it is built this frame
+    engine.input_capture = InputCapture::default();
+    let mut pad_events = engine.gamepads.poll();
+    engine.replay.apply(
+        &mut engine.input,
+        &mut engine.gamepads,
+        &mut pad_events,
+        elapsed_time,
+    );
+    engine
+        .replay
+        .record(&engine.input, &engine.gamepads, &pad_events, *elapsed_time);
+    engine
+        .text_input
+        .begin_frame(std::mem::take(&mut engine.input.text_events));
+    engine.input.update();
+    engine.gamepads.update_with(pad_events);
+    let mut input_map = std::mem::take(&mut engine.input_map);
+    input_map.gamepad = engine.gamepads.frame();
+    input_map.update(engine);
+    engine.input_map = input_map;
 }
@@ -18,6 +18,19 @@ pub struct GameObject {
     pub active: bool,
     pub uid: i32,
     pub layer_mask: Mask,
+    pub name: String,
+    pub properties: HashMap<String, Property>,
+}
+
+/// Custom data attached to an object by the file it was loaded from
+/// (Blender custom properties, glTF extras).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Property {
+    Int(i32),
+    Float(f64),
+    String(String),
+    Array(Vec<Property>),
+    Group(HashMap<String, Property>),
 }
 
 impl Clone for GameObject {
@@ -30,6 +43,8 @@ impl Clone for GameObject {
             active: self.active,
             uid: -1,
             layer_mask: self.layer_mask,
+            name: self.name.clone(),
+            properties: self.properties.clone(),
         }
     }
 }
@@ -49,6 +64,8 @@ impl Default for GameObject {
             children: vec![],
             layer_mask: Mask::D3,
             uid: -1,
+            name: String::new(),
+            properties: HashMap::new(),
         }
     }
 }
@@ -388,6 +405,20 @@ impl GameObject {
         self.sprite.as_ref().unwrap()
     }
 
+    pub fn get_property(&self, name: &str) -> Option<&Property> {
+        self.properties.get(name)
+    }
+
+    pub fn find_child(&self, name: &str) -> Option<&GameObject> {
+        self.children.iter().find_map(|child| {
+            if child.name == name {
+                Some(child)
+            } else {
+                child.find_child(name)
+            }
+        })
+    }
+
     pub fn new(transform: Transform3, sprite: Option<Sprite>, meshes: Vec<Mesh>) -> GameObject {
         GameObject {
             transform,
@@ -397,6 +428,8 @@ impl GameObject {
             children: vec![],
             layer_mask: Mask::D3,
             uid: -1,
+            name: String::new(),
+            properties: HashMap::new(),
         }
     }
 
@@ -418,6 +451,8 @@ impl GameObject {
             active: false,
             children: vec![],
             uid: -1,
+            name: String::new(),
+            properties: HashMap::new(),
         }
     }
 }
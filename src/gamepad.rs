@@ -0,0 +1,636 @@
+use super::{
+    input::GamepadFrame,
+    og_engine::Rcode,
+    util::{HWButton, Vf2d},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+pub type GamepadId = usize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    /// 0 to 1.
+    LeftTrigger,
+    /// 0 to 1.
+    RightTrigger,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GamepadEvent {
+    Connected(GamepadId),
+    Disconnected(GamepadId),
+    Button(GamepadId, GamepadButton, bool),
+    /// Sticks go from -1 to 1 with y up, triggers from 0 to 1.
+    Axis(GamepadId, GamepadAxis, f32),
+}
+
+/// Where gamepad events come from. `poll` is called once per frame.
+pub trait GamepadBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent>;
+
+    fn name(&self, _id: GamepadId) -> Option<String> {
+        None
+    }
+
+    /// Vibrates `id` for `duration`. Strengths go from 0 to 1.
+    fn rumble(&mut self, _id: GamepadId, _strong: f32, _weak: f32, _duration: Duration) -> Result<(), Rcode> {
+        Err(Rcode::Fail)
+    }
+}
+
+/// No gamepads, used when no real backend is compiled in.
+pub struct NullBackend;
+
+impl GamepadBackend for NullBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        vec![]
+    }
+}
+
+#[derive(Default)]
+struct SyntheticState {
+    events: VecDeque<GamepadEvent>,
+    names: HashMap<GamepadId, String>,
+    next_id: GamepadId,
+    rumble: Vec<(GamepadId, f32, f32, Duration)>,
+}
+
+/// Backend driven from code, for tests and input scripts. Clones share the
+/// same devices, so keep one and give the other to `Gamepads::new`.
+#[derive(Clone, Default)]
+pub struct SyntheticBackend {
+    state: Arc<Mutex<SyntheticState>>,
+}
+
+impl SyntheticBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn connect(&self, name: &str) -> GamepadId {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.names.insert(id, name.to_string());
+        state.events.push_back(GamepadEvent::Connected(id));
+        id
+    }
+
+    pub fn disconnect(&self, id: GamepadId) {
+        let mut state = self.state.lock().unwrap();
+        state.names.remove(&id);
+        state.events.push_back(GamepadEvent::Disconnected(id));
+    }
+
+    pub fn set_button(&self, id: GamepadId, button: GamepadButton, down: bool) {
+        self.state
+            .lock()
+            .unwrap()
+            .events
+            .push_back(GamepadEvent::Button(id, button, down));
+    }
+
+    pub fn set_axis(&self, id: GamepadId, axis: GamepadAxis, value: f32) {
+        self.state
+            .lock()
+            .unwrap()
+            .events
+            .push_back(GamepadEvent::Axis(id, axis, value));
+    }
+
+    /// Rumble requests received so far, as (id, strong, weak, duration).
+    pub fn rumble_requests(&self) -> Vec<(GamepadId, f32, f32, Duration)> {
+        self.state.lock().unwrap().rumble.clone()
+    }
+}
+
+impl GamepadBackend for SyntheticBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        self.state.lock().unwrap().events.drain(..).collect()
+    }
+
+    fn name(&self, id: GamepadId) -> Option<String> {
+        self.state.lock().unwrap().names.get(&id).cloned()
+    }
+
+    fn rumble(&mut self, id: GamepadId, strong: f32, weak: f32, duration: Duration) -> Result<(), Rcode> {
+        let mut state = self.state.lock().unwrap();
+        if !state.names.contains_key(&id) {
+            return Err(Rcode::Fail);
+        }
+        let len = state.rumble.len();
+        state.rumble.insert(len, (id, strong, weak, duration));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gilrs")]
+pub struct GilrsBackend {
+    gilrs: gilrs::Gilrs,
+    ids: HashMap<GamepadId, gilrs::GamepadId>,
+    //Effects stop when dropped, so they are kept until they finish
+    effects: Vec<(gilrs::ff::Effect, std::time::Instant)>,
+}
+
+#[cfg(feature = "gilrs")]
+impl GilrsBackend {
+    pub fn new() -> Result<Self, Rcode> {
+        let gilrs = gilrs::Gilrs::new().map_err(|e| {
+            log::warn!("Gamepads unavailable: {}", e);
+            Rcode::Fail
+        })?;
+        Ok(Self {
+            gilrs,
+            ids: HashMap::new(),
+            effects: vec![],
+        })
+    }
+
+    fn button(button: gilrs::Button) -> Option<GamepadButton> {
+        use gilrs::Button as B;
+        Some(match button {
+            B::South => GamepadButton::South,
+            B::East => GamepadButton::East,
+            B::North => GamepadButton::North,
+            B::West => GamepadButton::West,
+            B::LeftTrigger => GamepadButton::LeftBumper,
+            B::RightTrigger => GamepadButton::RightBumper,
+            B::LeftTrigger2 => GamepadButton::LeftTrigger,
+            B::RightTrigger2 => GamepadButton::RightTrigger,
+            B::Select => GamepadButton::Select,
+            B::Start => GamepadButton::Start,
+            B::Mode => GamepadButton::Mode,
+            B::LeftThumb => GamepadButton::LeftStick,
+            B::RightThumb => GamepadButton::RightStick,
+            B::DPadUp => GamepadButton::DPadUp,
+            B::DPadDown => GamepadButton::DPadDown,
+            B::DPadLeft => GamepadButton::DPadLeft,
+            B::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+
+    fn axis(axis: gilrs::Axis) -> Option<GamepadAxis> {
+        use gilrs::Axis as A;
+        Some(match axis {
+            A::LeftStickX => GamepadAxis::LeftStickX,
+            A::LeftStickY => GamepadAxis::LeftStickY,
+            A::RightStickX => GamepadAxis::RightStickX,
+            A::RightStickY => GamepadAxis::RightStickY,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "gilrs")]
+impl GamepadBackend for GilrsBackend {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        use gilrs::EventType;
+        let now = std::time::Instant::now();
+        self.effects.retain(|(_, end)| *end > now);
+
+        let mut events = vec![];
+        while let Some(gilrs::Event { id: gilrs_id, event, .. }) = self.gilrs.next_event() {
+            let id: GamepadId = gilrs_id.into();
+            let event = match event {
+                EventType::Connected => {
+                    self.ids.insert(id, gilrs_id);
+                    Some(GamepadEvent::Connected(id))
+                }
+                EventType::Disconnected => {
+                    self.ids.remove(&id);
+                    Some(GamepadEvent::Disconnected(id))
+                }
+                EventType::ButtonPressed(button, _) => {
+                    Self::button(button).map(|b| GamepadEvent::Button(id, b, true))
+                }
+                EventType::ButtonReleased(button, _) => {
+                    Self::button(button).map(|b| GamepadEvent::Button(id, b, false))
+                }
+                //Analog triggers report through their button
+                EventType::ButtonChanged(gilrs::Button::LeftTrigger2, value, _) => {
+                    Some(GamepadEvent::Axis(id, GamepadAxis::LeftTrigger, value))
+                }
+                EventType::ButtonChanged(gilrs::Button::RightTrigger2, value, _) => {
+                    Some(GamepadEvent::Axis(id, GamepadAxis::RightTrigger, value))
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    Self::axis(axis).map(|a| GamepadEvent::Axis(id, a, value))
+                }
+                _ => None,
+            };
+            if let Some(event) = event {
+                events.insert(events.len(), event);
+            }
+        }
+        events
+    }
+
+    fn name(&self, id: GamepadId) -> Option<String> {
+        let gilrs_id = self.ids.get(&id)?;
+        Some(self.gilrs.gamepad(*gilrs_id).name().to_string())
+    }
+
+    fn rumble(&mut self, id: GamepadId, strong: f32, weak: f32, duration: Duration) -> Result<(), Rcode> {
+        use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Replay, Ticks};
+        let gilrs_id = *self.ids.get(&id).ok_or(Rcode::Fail)?;
+        if !self.gilrs.gamepad(gilrs_id).is_ff_supported() {
+            return Err(Rcode::Fail);
+        }
+        let scheduling = Replay {
+            play_for: Ticks::from_ms(duration.as_millis() as u32),
+            ..Default::default()
+        };
+        let magnitude = |v: f32| (v.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: magnitude(strong),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak {
+                    magnitude: magnitude(weak),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .gamepads(&[gilrs_id])
+            .finish(&mut self.gilrs)
+            .map_err(|_| Rcode::Fail)?;
+        effect.play().map_err(|_| Rcode::Fail)?;
+        self.effects
+            .insert(self.effects.len(), (effect, std::time::Instant::now() + duration));
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gamepad {
+    pub id: GamepadId,
+    pub name: String,
+    buttons: HashMap<GamepadButton, HWButton>,
+    down: HashMap<GamepadButton, bool>,
+    axes: HashMap<GamepadAxis, f32>,
+    //Went down during the current poll
+    went_down: HashSet<GamepadButton>,
+    //Pressed and released within one poll, held for a frame so the press
+    // isn't lost and released on the next update
+    release_next: HashSet<GamepadButton>,
+}
+
+impl Gamepad {
+    fn set_button(&mut self, button: GamepadButton, down: bool) {
+        if down {
+            self.went_down.insert(button);
+            self.release_next.remove(&button);
+            self.down.insert(button, true);
+        } else if self.went_down.contains(&button) {
+            self.release_next.insert(button);
+        } else {
+            self.down.insert(button, false);
+        }
+    }
+}
+
+/// Connected gamepads and their state this frame.
+///
+/// Real gamepads need the `gilrs` feature, which is off by default. Without
+/// it `Gamepads::default()` uses `NullBackend` and never sees a gamepad; a
+/// backend of your own can be set with `set_backend`.
+pub struct Gamepads {
+    backend: Box<dyn GamepadBackend>,
+    pads: Vec<Gamepad>,
+    suspended: Vec<Gamepad>,
+    /// Stick and trigger values closer to 0 than this read as 0.
+    pub dead_zone: f32,
+    /// Connects and disconnects from the last update.
+    pub events: Vec<GamepadEvent>,
+}
+
+impl Default for Gamepads {
+    #[cfg(feature = "gilrs")]
+    fn default() -> Self {
+        match GilrsBackend::new() {
+            Ok(backend) => Self::new(Box::new(backend)),
+            Err(_) => Self::new(Box::new(NullBackend)),
+        }
+    }
+
+    #[cfg(not(feature = "gilrs"))]
+    fn default() -> Self {
+        Self::new(Box::new(NullBackend))
+    }
+}
+
+impl Gamepads {
+    pub fn new(backend: Box<dyn GamepadBackend>) -> Self {
+        Self {
+            backend,
+            pads: vec![],
+            suspended: vec![],
+            dead_zone: 0.1,
+            events: vec![],
+        }
+    }
+
+    pub fn set_backend(&mut self, backend: Box<dyn GamepadBackend>) {
+        *self = Self {
+            dead_zone: self.dead_zone,
+            ..Self::new(backend)
+        };
+    }
+
+    pub fn update(&mut self) {
+        let events = self.poll();
+        self.update_with(events);
+    }
+
+    /// Events from the backend since the last poll, for `update_with`.
+    pub fn poll(&mut self) -> Vec<GamepadEvent> {
+        self.backend.poll()
+    }
+
+    /// Updates the pads from `events` instead of polling the backend, used
+    /// to replay recorded input.
+    pub fn update_with(&mut self, events: Vec<GamepadEvent>) {
+        self.events.clear();
+        for pad in &mut self.pads {
+            for button in std::mem::take(&mut pad.release_next) {
+                pad.down.insert(button, false);
+            }
+        }
+        for event in events {
+            match event {
+                GamepadEvent::Connected(id) => {
+                    if !self.pads.iter().any(|p| p.id == id) {
+                        let pad = Gamepad {
+                            id,
+                            name: self.backend.name(id).unwrap_or_default(),
+                            ..Default::default()
+                        };
+                        self.pads.insert(self.pads.len(), pad);
+                    }
+                    self.events.insert(self.events.len(), event);
+                }
+                GamepadEvent::Disconnected(id) => {
+                    self.pads.retain(|p| p.id != id);
+                    self.events.insert(self.events.len(), event);
+                }
+                GamepadEvent::Button(id, button, down) => {
+                    if let Some(pad) = self.pads.iter_mut().find(|p| p.id == id) {
+                        pad.set_button(button, down);
+                    }
+                }
+                GamepadEvent::Axis(id, axis, value) => {
+                    if let Some(pad) = self.pads.iter_mut().find(|p| p.id == id) {
+                        pad.axes.insert(axis, value);
+                    }
+                }
+            }
+        }
+
+        for pad in &mut self.pads {
+            pad.went_down.clear();
+            for (button, down) in &pad.down {
+                let state = pad.buttons.entry(*button).or_default();
+                state.pressed = *down && !state.held;
+                state.released = !*down && state.held;
+                state.held = *down;
+            }
+        }
+    }
+
+    /// Events that bring pads with nothing connected to the current state.
+    pub fn state_events(&self) -> Vec<GamepadEvent> {
+        let mut events = vec![];
+        for pad in &self.pads {
+            events.insert(events.len(), GamepadEvent::Connected(pad.id));
+            for (button, down) in &pad.down {
+                if *down {
+                    events.insert(events.len(), GamepadEvent::Button(pad.id, *button, true));
+                }
+            }
+            for (axis, value) in &pad.axes {
+                events.insert(events.len(), GamepadEvent::Axis(pad.id, *axis, *value));
+            }
+        }
+        events
+    }
+
+    /// Sets the connected pads aside with nothing held, so a replay starts
+    /// with no pads. `resume` brings them back.
+    pub fn suspend(&mut self) {
+        let mut pads = std::mem::take(&mut self.pads);
+        for pad in &mut pads {
+            pad.buttons.clear();
+            pad.down.clear();
+            pad.axes.clear();
+            pad.went_down.clear();
+            pad.release_next.clear();
+        }
+        self.suspended = pads;
+        self.events.clear();
+    }
+
+    /// Drops the pads a replay connected and restores the ones set aside by
+    /// `suspend`. Pads that connected or disconnected in between are missed.
+    pub fn resume(&mut self) {
+        self.pads = std::mem::take(&mut self.suspended);
+    }
+
+    pub fn connected(&self) -> Vec<GamepadId> {
+        self.pads.iter().map(|p| p.id).collect()
+    }
+
+    pub fn get(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.pads.iter().find(|p| p.id == id)
+    }
+
+    pub fn get_button(&self, id: GamepadId, button: GamepadButton) -> HWButton {
+        self.get(id)
+            .and_then(|p| p.buttons.get(&button).copied())
+            .unwrap_or_default()
+    }
+
+    /// Value of one axis with the dead zone applied.
+    pub fn get_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        let value = self.get_raw_axis(id, axis);
+        if value.abs() <= self.dead_zone {
+            0.0
+        } else {
+            value.signum() * (value.abs() - self.dead_zone) / (1.0 - self.dead_zone)
+        }
+    }
+
+    pub fn get_raw_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        self.get(id)
+            .and_then(|p| p.axes.get(&axis).copied())
+            .unwrap_or(0.0)
+    }
+
+    /// Both axes of a stick, with a round dead zone so diagonals aren't
+    /// snapped to the axes.
+    pub fn get_stick(&self, id: GamepadId, x: GamepadAxis, y: GamepadAxis) -> Vf2d {
+        let stick = Vf2d::new(self.get_raw_axis(id, x), self.get_raw_axis(id, y));
+        let length = (stick.x * stick.x + stick.y * stick.y).sqrt();
+        if length <= self.dead_zone {
+            return Vf2d::new(0.0, 0.0);
+        }
+        let scaled = ((length - self.dead_zone) / (1.0 - self.dead_zone)).min(1.0);
+        stick * (scaled / length)
+    }
+
+    pub fn rumble(&mut self, id: GamepadId, strong: f32, weak: f32, duration: Duration) -> Result<(), Rcode> {
+        self.backend.rumble(id, strong, weak, duration)
+    }
+
+    /// All pads merged, for input maps: a button is down if it is down on any
+    /// pad and each axis takes the value furthest from 0.
+    pub fn frame(&self) -> GamepadFrame {
+        let mut frame = GamepadFrame::default();
+        for pad in &self.pads {
+            for (button, down) in &pad.down {
+                *frame.buttons.entry(*button).or_default() |= *down;
+            }
+            for (axis, value) in &pad.axes {
+                let merged = frame.axes.entry(*axis).or_default();
+                if value.abs() > merged.abs() {
+                    *merged = *value;
+                }
+            }
+        }
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Hands out one batch of events per poll
+    struct ScriptedBackend {
+        polls: VecDeque<Vec<GamepadEvent>>,
+    }
+
+    impl GamepadBackend for ScriptedBackend {
+        fn poll(&mut self) -> Vec<GamepadEvent> {
+            self.polls.pop_front().unwrap_or_default()
+        }
+    }
+
+    fn scripted(polls: Vec<Vec<GamepadEvent>>) -> Gamepads {
+        Gamepads::new(Box::new(ScriptedBackend { polls: polls.into() }))
+    }
+
+    #[test]
+    fn buttons_report_edges() {
+        let south = GamepadButton::South;
+        let mut pads = scripted(vec![
+            vec![GamepadEvent::Connected(0), GamepadEvent::Button(0, south, true)],
+            vec![],
+            vec![GamepadEvent::Button(0, south, false)],
+        ]);
+        pads.update();
+        assert_eq!(pads.connected(), vec![0]);
+        let b = pads.get_button(0, south);
+        assert!(b.pressed && b.held && !b.released);
+        pads.update();
+        let b = pads.get_button(0, south);
+        assert!(!b.pressed && b.held && !b.released);
+        pads.update();
+        let b = pads.get_button(0, south);
+        assert!(!b.pressed && !b.held && b.released);
+    }
+
+    #[test]
+    fn tap_within_one_poll_is_not_lost() {
+        let east = GamepadButton::East;
+        let mut pads = scripted(vec![
+            vec![GamepadEvent::Connected(0)],
+            vec![GamepadEvent::Button(0, east, true), GamepadEvent::Button(0, east, false)],
+        ]);
+        pads.update();
+        pads.update();
+        let b = pads.get_button(0, east);
+        assert!(b.pressed && b.held);
+        assert_eq!(pads.frame().buttons.get(&east), Some(&true));
+        pads.update();
+        let b = pads.get_button(0, east);
+        assert!(!b.held && b.released);
+    }
+
+    #[test]
+    fn axes_apply_dead_zone() {
+        let mut pads = scripted(vec![vec![
+            GamepadEvent::Connected(0),
+            GamepadEvent::Axis(0, GamepadAxis::LeftStickX, 0.05),
+            GamepadEvent::Axis(0, GamepadAxis::LeftStickY, -1.0),
+        ]]);
+        pads.update();
+        assert_eq!(pads.get_axis(0, GamepadAxis::LeftStickX), 0.0);
+        assert_eq!(pads.get_axis(0, GamepadAxis::LeftStickY), -1.0);
+        assert_eq!(pads.get_raw_axis(0, GamepadAxis::LeftStickX), 0.05);
+        assert_eq!(pads.frame().axes.get(&GamepadAxis::LeftStickY), Some(&-1.0));
+    }
+
+    #[test]
+    fn default_backend_methods() {
+        let mut pads = scripted(vec![vec![GamepadEvent::Connected(3)]]);
+        pads.update();
+        assert_eq!(pads.get(3).map(|p| p.name.as_str()), Some(""));
+        assert!(pads.rumble(3, 1.0, 1.0, Duration::from_millis(10)).is_err());
+    }
+
+    #[test]
+    fn synthetic_backend_drives_gamepads() {
+        let backend = SyntheticBackend::new();
+        let mut pads = Gamepads::new(Box::new(backend.clone()));
+        let id = backend.connect("Test pad");
+        backend.set_button(id, GamepadButton::Start, true);
+        backend.set_axis(id, GamepadAxis::RightTrigger, 1.0);
+        pads.update();
+        assert_eq!(pads.get(id).map(|p| p.name.as_str()), Some("Test pad"));
+        assert!(pads.get_button(id, GamepadButton::Start).pressed);
+        assert_eq!(pads.get_axis(id, GamepadAxis::RightTrigger), 1.0);
+        assert!(pads.rumble(id, 0.5, 0.25, Duration::from_millis(100)).is_ok());
+        assert_eq!(backend.rumble_requests(), vec![(id, 0.5, 0.25, Duration::from_millis(100))]);
+
+        backend.disconnect(id);
+        pads.update();
+        assert!(pads.connected().is_empty());
+        assert_eq!(pads.events, vec![GamepadEvent::Disconnected(id)]);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{math_3d::*, pixel::Pixel, texture::Texture, transform::*};
+use super::{math_3d::*, og_engine::Rcode, pixel::Pixel, texture::Texture, transform::*};
 
 pub enum NormalMode {
     Flat,
@@ -439,9 +439,9 @@ impl Mesh {
         Mesh::read_buffer(std::io::BufReader::new(data.as_bytes()), has_texture)
     }
 
-    pub fn load_from_file(path: &str, has_texture: bool) -> Mesh {
-        let mesh_file = std::fs::File::open(path).expect("Couldn't open mesh file: {}");
-        Mesh::read_buffer(std::io::BufReader::new(mesh_file), has_texture)
+    pub fn load_from_file(path: &str, has_texture: bool) -> Result<Mesh, Rcode> {
+        let mesh_file = std::fs::File::open(path).map_err(|_| Rcode::NoFile)?;
+        Ok(Mesh::read_buffer(std::io::BufReader::new(mesh_file), has_texture))
     }
 
     pub fn calculate_normals(&mut self, mode: NormalMode) {
@@ -1,16 +1,20 @@
 use crate::texture::Texture;
 
-use super::{game_object::GameObject, geometry::*, math_3d::*, transform::*};
+use super::{game_object::GameObject, geometry::*, math_3d::*, og_engine::Rcode, sprite::Sprite, transform::*, util::rgba_to_sprite};
 
 
 pub fn get_game_objects(data: &[u8]) -> (Vec<GameObject>, Vec<gltf::image::Data>)  {
-    let (document, buffers, images) = gltf::import_slice(data).unwrap();
+    try_get_game_objects(data).unwrap()
+}
+
+pub fn try_get_game_objects(data: &[u8]) -> Result<(Vec<GameObject>, Vec<gltf::image::Data>), Rcode>  {
+    let (document, buffers, images) = gltf::import_slice(data).map_err(|_| Rcode::Fail)?;
     let mut skip_nodes: Vec<usize> = document.nodes().map(get_children_id).flatten().collect();
-    (document.nodes()
+    Ok((document.nodes()
      .filter(|node| !skip_nodes.contains(&node.index()))
      .map(|node| process_node(node, &buffers, &images))
      .collect(),
-     images)
+     images))
 }
 
 pub fn get_game_objects_from_file(data: &str) -> Vec<GameObject> {
@@ -76,20 +80,8 @@ pub fn extract_node<'a>(
             let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
             let material = primitive.material();
             let get_texture =
-                |texture: gltf::texture::Texture| -> Option<(crate::sprite::Sprite, usize)> {
-                    let image_index = texture.source().index();
-                    let image = &images[image_index];
-                    let mut spr = crate::sprite::Sprite::new(image.width, image.height);
-                    match image.format {
-                        gltf::image::Format::R8 => spr.set_data(&image.pixels, 1),
-                        gltf::image::Format::R8G8 => spr.set_data(&image.pixels, 2),
-                        gltf::image::Format::R8G8B8 => spr.set_data(&image.pixels, 3),
-                        gltf::image::Format::R8G8B8A8 => spr.set_data(&image.pixels, 4),
-                        gltf::image::Format::B8G8R8 => spr.set_data(&image.pixels, 3),
-                        gltf::image::Format::B8G8R8A8 => spr.set_data(&image.pixels, 4),
-                        _ => {}
-                    }
-                    Some((spr, image_index))
+                |texture: gltf::texture::Texture| -> Option<(Sprite, usize)> {
+                    texture_to_sprite(texture, images)
                 };
             let mut textures: Vec<PBRTexture> = vec![];
             let mut tex_coords: Vec<UV> = vec![];
@@ -203,32 +195,28 @@ pub fn extract_node<'a>(
 }
 
 
-pub fn texture_to_sprite (texture: gltf::texture::Texture, images: &[gltf::image::Data]) -> Option<(crate::sprite::Sprite, usize)> {
-        let image_index = texture.source().index();
-        let image = &images[image_index];
-        let mut spr = crate::sprite::Sprite::new(image.width, image.height);
-        match image.format {
-            gltf::image::Format::R8 => spr.set_data(&image.pixels, 1),
-            gltf::image::Format::R8G8 => spr.set_data(&image.pixels, 2),
-            gltf::image::Format::R8G8B8 => spr.set_data(&image.pixels, 3),
-            gltf::image::Format::R8G8B8A8 => spr.set_data(&image.pixels, 4),
-            gltf::image::Format::B8G8R8 => spr.set_data(&image.pixels, 3),
-            gltf::image::Format::B8G8R8A8 => spr.set_data(&image.pixels, 4),
-            _ => {}
-        }
-        Some((spr, image_index))
+pub fn texture_to_sprite (texture: gltf::texture::Texture, images: &[gltf::image::Data]) -> Option<(Sprite, usize)> {
+    let image_index = texture.source().index();
+    images.get(image_index).map(|image| (image_to_sprite(image), image_index))
 }
 
-pub fn image_to_sprite(image: &gltf::image::Data) -> crate::sprite::Sprite{
-    let mut spr = crate::sprite::Sprite::new(image.width, image.height);
-    match image.format {
-        gltf::image::Format::R8 => spr.set_data(&image.pixels, 1),
-        gltf::image::Format::R8G8 => spr.set_data(&image.pixels, 2),
-        gltf::image::Format::R8G8B8 => spr.set_data(&image.pixels, 3),
-        gltf::image::Format::R8G8B8A8 => spr.set_data(&image.pixels, 4),
-        gltf::image::Format::B8G8R8 => spr.set_data(&image.pixels, 3),
-        gltf::image::Format::B8G8R8A8 => spr.set_data(&image.pixels, 4),
-        _ => {}
+pub fn image_to_sprite(image: &gltf::image::Data) -> Sprite {
+    use gltf::image::Format;
+    let p = &image.pixels;
+    //Single and dual channel images come from greyscale (and alpha) files
+    let rgba: Vec<u8> = match image.format {
+        Format::R8 => p.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        Format::R8G8 => p.chunks_exact(2).flat_map(|c| [c[0], c[0], c[0], c[1]]).collect(),
+        Format::R8G8B8 => p.chunks_exact(3).flat_map(|c| [c[0], c[1], c[2], 255]).collect(),
+        Format::R8G8B8A8 => p.clone(),
+        Format::B8G8R8 => p.chunks_exact(3).flat_map(|c| [c[2], c[1], c[0], 255]).collect(),
+        Format::B8G8R8A8 => p.chunks_exact(4).flat_map(|c| [c[2], c[1], c[0], c[3]]).collect(),
+        //16 bit channels are little endian, keep the high byte
+        Format::R16 => p.chunks_exact(2).flat_map(|c| [c[1], c[1], c[1], 255]).collect(),
+        Format::R16G16 => p.chunks_exact(4).flat_map(|c| [c[1], c[1], c[1], c[3]]).collect(),
+        Format::R16G16B16 => p.chunks_exact(6).flat_map(|c| [c[1], c[3], c[5], 255]).collect(),
+        Format::R16G16B16A16 => p.chunks_exact(8).flat_map(|c| [c[1], c[3], c[5], c[7]]).collect(),
+        _ => vec![0; (image.width * image.height * 4) as usize],
     };
-    spr
+    rgba_to_sprite(image.width, image.height, &rgba)
 }
@@ -0,0 +1,169 @@
+use super::{
+    asset::{AssetChanged, GltfScene, Handle},
+    game_object::GameObject,
+    geometry::Mesh,
+    layer::Mask,
+    renderer::Renderer,
+    sprite::Sprite,
+    texture::Texture,
+};
+
+/// Keeps GPU resources in sync with assets that are reloaded from disk.
+/// Each binding ties an asset to the renderer texture or game object it was
+/// uploaded to, so a reload only swaps the data and leaves the rest of the
+/// object (transform, children, properties) alone.
+///
+/// Files are not watched, the game loop compares modification times every
+/// `poll_interval` seconds (see `AssetServer::check_for_changes`). Polling is
+/// on by default in debug builds and never happens on wasm. Only assets
+/// loaded through `engine.assets` are reloaded, and only the ones bound here
+/// are re-uploaded for you: load the asset, upload it as usual, then call the
+/// matching `bind_*` with the texture or game object index it went to.
+/// Everything else is reported through `OGEngine::asset_events`.
+pub struct HotReload {
+    textures: Vec<(Handle<Sprite>, usize)>,
+    meshes: Vec<(Handle<Mesh>, usize, usize)>,
+    scenes: Vec<(Handle<GltfScene>, usize, usize)>,
+    /// Assets that were reloaded this frame.
+    pub events: Vec<AssetChanged>,
+    /// Seconds between checks for changed files, None turns polling off.
+    pub poll_interval: Option<f64>,
+    since_poll: f64,
+}
+
+impl Default for HotReload {
+    fn default() -> Self {
+        Self {
+            textures: vec![],
+            meshes: vec![],
+            scenes: vec![],
+            events: vec![],
+            poll_interval: if cfg!(debug_assertions) && !cfg!(target_arch = "wasm32") {
+                Some(0.5)
+            } else {
+                None
+            },
+            since_poll: 0.0,
+        }
+    }
+}
+
+impl HotReload {
+    //True once every poll_interval
+    pub(crate) fn poll_due(&mut self, elapsed_time: f64) -> bool {
+        let interval = match self.poll_interval {
+            Some(interval) => interval,
+            None => return false,
+        };
+        self.since_poll += elapsed_time;
+        if self.since_poll < interval {
+            return false;
+        }
+        self.since_poll = 0.0;
+        true
+    }
+
+    /// Re-upload `renderer.textures[texture]` whenever `handle` changes.
+    pub fn bind_texture(&mut self, handle: Handle<Sprite>, texture: usize) {
+        self.textures.insert(self.textures.len(), (handle, texture));
+    }
+
+    /// Replace `meshes[mesh]` of `renderer.game_objects[object]` whenever
+    /// `handle` changes.
+    pub fn bind_mesh(&mut self, handle: Handle<Mesh>, object: usize, mesh: usize) {
+        self.meshes.insert(self.meshes.len(), (handle, object, mesh));
+    }
+
+    /// Replace the meshes of `renderer.game_objects[object]` and its children
+    /// with those of `scene.objects[root]` whenever `handle` changes.
+    pub fn bind_scene(&mut self, handle: Handle<GltfScene>, object: usize, root: usize) {
+        self.scenes.insert(self.scenes.len(), (handle, object, root));
+    }
+
+    pub fn unbind_all(&mut self) {
+        self.textures.clear();
+        self.meshes.clear();
+        self.scenes.clear();
+    }
+
+    pub fn apply(&mut self, events: Vec<AssetChanged>, renderer: &mut Renderer) {
+        let mut dirty = Mask::empty();
+        for event in &events {
+            for (handle, index) in self.textures.iter().filter(|(h, _)| h.id() == event.id) {
+                if let Some(spr) = handle.get() {
+                    dirty |= reload_texture(renderer, *index, &spr);
+                }
+            }
+            for (handle, object, mesh) in self.meshes.iter().filter(|(h, _, _)| h.id() == event.id) {
+                if let (Some(new_mesh), Some(go)) =
+                    (handle.get(), renderer.game_objects.get_mut(*object))
+                {
+                    if let Some(old_mesh) = go.meshes.get_mut(*mesh) {
+                        let textures = std::mem::take(&mut old_mesh.textures);
+                        *old_mesh = (*new_mesh).clone();
+                        if old_mesh.textures.is_empty() {
+                            old_mesh.textures = textures;
+                        }
+                        go.set_buffer_indices(0);
+                        dirty |= go.layer_mask;
+                    }
+                }
+            }
+            for (handle, object, root) in self.scenes.iter().filter(|(h, _, _)| h.id() == event.id) {
+                if let (Some(scene), Some(go)) =
+                    (handle.get(), renderer.game_objects.get_mut(*object))
+                {
+                    if let Some(fresh) = scene.objects.get(*root) {
+                        replace_meshes(go, fresh);
+                        go.set_buffer_indices(0);
+                        dirty |= go.layer_mask;
+                    }
+                }
+            }
+        }
+
+        if !dirty.is_empty() {
+            let masks: Vec<Mask> = renderer
+                .draw_data
+                .iter()
+                .map(|dd| dd.mask)
+                .filter(|mask| mask.intersects(dirty))
+                .collect();
+            for mask in masks {
+                renderer.update_layer_draw_data(mask);
+            }
+        }
+        self.events = events;
+    }
+}
+
+//Returns the layers that need their draw data rebuilt
+fn reload_texture(renderer: &mut Renderer, index: usize, spr: &Sprite) -> Mask {
+    let (same_size, format) = match renderer.textures.get(index) {
+        Some(tex) => (
+            tex.data.width == spr.width && tex.data.height == spr.height,
+            tex.texture_bundle.as_ref().map(|b| b.format),
+        ),
+        None => return Mask::empty(),
+    };
+    if same_size {
+        let tex = &mut renderer.textures[index];
+        tex.update(&renderer.queue, spr);
+        tex.data = spr.clone();
+        Mask::empty()
+    } else {
+        //The old texture view is baked into the draw data bind groups
+        let format = format.unwrap_or(renderer.preferred_texture_format);
+        let tex = Texture::new_from_sprite(renderer, spr.clone(), format);
+        renderer.textures[index] = tex;
+        Mask::all()
+    }
+}
+
+//Scenes don't carry stable ids, so children are matched by position
+fn replace_meshes(live: &mut GameObject, fresh: &GameObject) {
+    live.meshes = fresh.meshes.clone();
+    for (child, fresh_child) in live.children.iter_mut().zip(fresh.children.iter()) {
+        replace_meshes(child, fresh_child);
+    }
+}
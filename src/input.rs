@@ -0,0 +1,386 @@
+use super::{
+    engine::OGEngine,
+    gamepad::{GamepadAxis, GamepadButton},
+    og_engine::{OGData, Rcode},
+    platform::Key,
+    ui::InputCapture,
+    util::{HWButton, Mouse, Vf2d},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A physical input that can trigger an action.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(Key),
+    Mouse(Mouse),
+    WheelUp,
+    WheelDown,
+    GamepadButton(GamepadButton),
+    /// An analog axis pushed past `threshold` in one direction.
+    GamepadAxis {
+        axis: GamepadAxis,
+        positive: bool,
+        #[serde(default = "default_threshold")]
+        threshold: f32,
+    },
+}
+
+//Kept apart so input the UI captured can be left out when an action is read
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Device {
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+const DEVICES: [Device; 3] = [Device::Keyboard, Device::Mouse, Device::Gamepad];
+
+impl Binding {
+    fn device(&self) -> Device {
+        match self {
+            Binding::Key(_) => Device::Keyboard,
+            Binding::Mouse(_) | Binding::WheelUp | Binding::WheelDown => Device::Mouse,
+            Binding::GamepadButton(_) | Binding::GamepadAxis { .. } => Device::Gamepad,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct AxisState {
+    //Buttons and sticks, per device
+    analog: [f32; 3],
+    //Wheel and mouse movement
+    relative: f32,
+}
+
+fn default_threshold() -> f32 {
+    0.5
+}
+
+fn default_dead_zone() -> f32 {
+    0.15
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+/// `input` while all of `modifiers` are held, e.g. Ctrl+S.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActionBinding {
+    pub input: Binding,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<Key>,
+}
+
+impl From<Binding> for ActionBinding {
+    fn from(input: Binding) -> Self {
+        Self {
+            input,
+            modifiers: vec![],
+        }
+    }
+}
+
+/// A source of values for an axis. All sources of an axis are summed, with
+/// buttons and sticks together clamped to -1..1.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AxisBinding {
+    /// -1 while `negative` is held, 1 while `positive` is.
+    Buttons { negative: Binding, positive: Binding },
+    Gamepad {
+        axis: GamepadAxis,
+        /// Values closer to 0 than this read as 0, the rest is rescaled so
+        /// the axis still covers the full range.
+        #[serde(default = "default_dead_zone")]
+        dead_zone: f32,
+        #[serde(default = "default_scale")]
+        scale: f32,
+    },
+    /// Wheel clicks this frame times `scale`.
+    Wheel {
+        #[serde(default = "default_scale")]
+        scale: f32,
+    },
+    /// Mouse movement in pixels this frame times `scale`.
+    MouseX {
+        #[serde(default = "default_scale")]
+        scale: f32,
+    },
+    MouseY {
+        #[serde(default = "default_scale")]
+        scale: f32,
+    },
+}
+
+/// The saveable part of an `InputMap`.
+///
+/// ```yaml
+/// actions:
+///   interact:
+///     - input: !Key E
+///     - input: !GamepadButton South
+///   save:
+///     - input: !Key S
+///       modifiers: [LControl]
+/// axes:
+///   move_x:
+///     - !Buttons { negative: !Key A, positive: !Key D }
+///     - !Gamepad { axis: LeftStickX, dead_zone: 0.2 }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputBindings {
+    #[serde(default)]
+    pub actions: HashMap<String, Vec<ActionBinding>>,
+    #[serde(default)]
+    pub axes: HashMap<String, Vec<AxisBinding>>,
+}
+
+/// Gamepad values for the current frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GamepadFrame {
+    pub buttons: HashMap<GamepadButton, bool>,
+    pub axes: HashMap<GamepadAxis, f32>,
+}
+
+/// Named actions and axes on top of the raw key, mouse and gamepad state.
+/// Updated by the engine at the start of every frame, before
+/// `on_engine_update`. Like `get_key` and `get_mouse`, actions and axes
+/// ignore keyboard and mouse input the UI has captured, so build the UI
+/// before reading them.
+#[derive(Default)]
+pub struct InputMap {
+    pub bindings: InputBindings,
+    /// Current gamepad state, read by gamepad bindings.
+    pub gamepad: GamepadFrame,
+    actions: HashMap<String, [HWButton; 3]>,
+    axes: HashMap<String, AxisState>,
+    last_mouse: Option<Vf2d>,
+    //Set through OGEngine::capture_input
+    pub(crate) capture: InputCapture,
+}
+
+impl InputMap {
+    pub fn new(bindings: InputBindings) -> Self {
+        Self {
+            bindings,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self, Rcode> {
+        let bindings = serde_yaml::from_str(yaml).map_err(|e| {
+            log::error!("Invalid input bindings: {}", e);
+            Rcode::Fail
+        })?;
+        Ok(Self::new(bindings))
+    }
+
+    pub fn to_yaml(&self) -> Result<String, Rcode> {
+        serde_yaml::to_string(&self.bindings).map_err(|_| Rcode::Fail)
+    }
+
+    pub fn load(path: &str) -> Result<Self, Rcode> {
+        let yaml = std::fs::read_to_string(path).map_err(|_| Rcode::NoFile)?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Replaces the bindings with those in `path`, keeping action states.
+    pub fn reload(&mut self, path: &str) -> Result<(), Rcode> {
+        self.bindings = Self::load(path)?.bindings;
+        Ok(())
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Rcode> {
+        std::fs::write(path, self.to_yaml()?).map_err(|_| Rcode::Fail)
+    }
+
+    pub fn bind_action(&mut self, action: &str, binding: impl Into<ActionBinding>) {
+        let bindings = self.bindings.actions.entry(action.to_string()).or_default();
+        bindings.insert(bindings.len(), binding.into());
+    }
+
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        let bindings = self.bindings.axes.entry(axis.to_string()).or_default();
+        bindings.insert(bindings.len(), binding);
+    }
+
+    /// Replaces every binding of `action`, for rebinding menus.
+    pub fn rebind_action(&mut self, action: &str, binding: impl Into<ActionBinding>) {
+        self.bindings
+            .actions
+            .insert(action.to_string(), vec![binding.into()]);
+    }
+
+    pub fn unbind_action(&mut self, action: &str) {
+        self.bindings.actions.remove(action);
+        self.actions.remove(action);
+    }
+
+    pub fn unbind_axis(&mut self, axis: &str) {
+        self.bindings.axes.remove(axis);
+        self.axes.remove(axis);
+    }
+
+    /// State of `action` this frame. Unknown actions are never pressed.
+    pub fn get(&self, action: &str) -> HWButton {
+        let states = match self.actions.get(action) {
+            Some(states) => states,
+            None => return HWButton::new(),
+        };
+        let live: Vec<HWButton> = DEVICES
+            .iter()
+            .filter(|device| !self.is_captured(**device))
+            .map(|device| states[*device as usize])
+            .collect();
+        let held = live.iter().any(|s| s.held);
+        //A second device going down while the action is already held isn't a new press
+        let already_held = live.iter().any(|s| s.held && !s.pressed);
+        HWButton {
+            pressed: !already_held && live.iter().any(|s| s.pressed),
+            released: !held && live.iter().any(|s| s.released),
+            held,
+        }
+    }
+
+    fn is_captured(&self, device: Device) -> bool {
+        match device {
+            Device::Keyboard => self.capture.keyboard,
+            Device::Mouse => self.capture.mouse,
+            Device::Gamepad => false,
+        }
+    }
+
+    pub fn pressed(&self, action: &str) -> bool {
+        self.get(action).pressed
+    }
+
+    pub fn held(&self, action: &str) -> bool {
+        self.get(action).held
+    }
+
+    pub fn released(&self, action: &str) -> bool {
+        self.get(action).released
+    }
+
+    /// Value of `axis` this frame. Between -1 and 1 unless it has wheel or
+    /// mouse bindings.
+    pub fn axis(&self, axis: &str) -> f32 {
+        let state = match self.axes.get(axis) {
+            Some(state) => state,
+            None => return 0.0,
+        };
+        let analog: f32 = DEVICES
+            .iter()
+            .filter(|device| !self.is_captured(**device))
+            .map(|device| state.analog[*device as usize])
+            .sum();
+        let relative = if self.capture.mouse { 0.0 } else { state.relative };
+        analog.clamp(-1.0, 1.0) + relative
+    }
+
+    /// First input that went down this frame, for "press a key" prompts.
+    pub fn pressed_binding<D: OGData + 'static>(&self, engine: &OGEngine<D>) -> Option<Binding> {
+        let key = engine
+            .pressed_keys()
+            .into_iter()
+            .next()
+            .map(Binding::Key);
+        let mouse = [Mouse::Left, Mouse::Right, Mouse::Middle]
+            .into_iter()
+            .find(|b| engine.get_mouse(*b).pressed)
+            .map(Binding::Mouse);
+        key.or(mouse)
+    }
+
+    pub fn update<D: OGData + 'static>(&mut self, engine: &OGEngine<D>) {
+        self.capture = engine.input_capture;
+        let mouse = engine.get_mouse_pos();
+        let mouse_delta = self.last_mouse.map(|last| mouse - last).unwrap_or_default();
+        self.last_mouse = Some(mouse);
+
+        for (name, bindings) in &self.bindings.actions {
+            let down = DEVICES.map(|device| {
+                bindings.iter().any(|b| {
+                    b.input.device() == device
+                        && b.modifiers.iter().all(|m| engine.get_raw_key(*m).held)
+                        && self.binding_down(engine, &b.input)
+                })
+            });
+            let states = self.actions.entry(name.clone()).or_default();
+            for device in DEVICES {
+                let down = down[device as usize];
+                let state = &mut states[device as usize];
+                let was_down = state.held;
+                state.pressed = down && !was_down;
+                state.released = !down && was_down;
+                state.held = down;
+            }
+        }
+        //Actions that were unbound while held
+        self.actions
+            .retain(|name, _| self.bindings.actions.contains_key(name));
+
+        for (name, bindings) in &self.bindings.axes {
+            let mut state = AxisState::default();
+            for binding in bindings {
+                match binding {
+                    AxisBinding::Buttons { negative, positive } => {
+                        state.analog[positive.device() as usize] += self.binding_down(engine, positive) as i32 as f32;
+                        state.analog[negative.device() as usize] -= self.binding_down(engine, negative) as i32 as f32;
+                    }
+                    AxisBinding::Gamepad {
+                        axis,
+                        dead_zone,
+                        scale,
+                    } => {
+                        state.analog[Device::Gamepad as usize] +=
+                            apply_dead_zone(self.gamepad_axis(*axis), *dead_zone) * scale
+                    }
+                    AxisBinding::Wheel { scale } => state.relative += engine.get_raw_mouse_wheel() as f32 * scale,
+                    AxisBinding::MouseX { scale } => state.relative += mouse_delta.x * scale,
+                    AxisBinding::MouseY { scale } => state.relative += mouse_delta.y * scale,
+                }
+            }
+            self.axes.insert(name.clone(), state);
+        }
+        self.axes.retain(|name, _| self.bindings.axes.contains_key(name));
+    }
+
+    fn gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepad.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    fn binding_down<D: OGData + 'static>(&self, engine: &OGEngine<D>, binding: &Binding) -> bool {
+        match binding {
+            Binding::Key(key) => engine.get_raw_key(*key).held,
+            Binding::Mouse(button) => engine.get_raw_mouse(*button).held,
+            Binding::WheelUp => engine.get_raw_mouse_wheel() > 0,
+            Binding::WheelDown => engine.get_raw_mouse_wheel() < 0,
+            Binding::GamepadButton(button) => self.gamepad.buttons.get(button).copied().unwrap_or(false),
+            Binding::GamepadAxis {
+                axis,
+                positive,
+                threshold,
+            } => {
+                let value = self.gamepad_axis(*axis);
+                if *positive {
+                    value > *threshold
+                } else {
+                    value < -*threshold
+                }
+            }
+        }
+    }
+}
+
+/// Zero inside `dead_zone`, rescaled to reach 1 at the edge outside it.
+pub fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= dead_zone || dead_zone >= 1.0 {
+        0.0
+    } else {
+        value.signum() * ((magnitude - dead_zone) / (1.0 - dead_zone)).min(1.0)
+    }
+}
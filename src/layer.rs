@@ -1,5 +1,5 @@
 use super::{
-    decal::DecalInstance,
+    decal::{DecalInstance, DecalMode},
     game_object::GameObject,
     geometry::{PBRTexture, Vertex},
     og_engine::OGData,
@@ -51,13 +51,84 @@ pub struct LayerDesc<D: OGData + 'static> {
     pub vec_decal_instance: Vec<DecalInstance>,
 }
 
-#[derive(Default)]
 pub struct Image {
     pub sprite: Sprite,
     pub update: bool,
     pub offset: Vf2d,
     pub scale: Vf2d,
     pub tint: Pixel,
+    pub opacity: f32,
+    pub blend: DecalMode,
+}
+
+impl Default for Image {
+    fn default() -> Self {
+        Self {
+            sprite: Sprite::default(),
+            update: false,
+            offset: Vf2d::new(0.0, 0.0),
+            scale: Vf2d::new(1.0, 1.0),
+            tint: Pixel::WHITE,
+            opacity: 1.0,
+            blend: DecalMode::Normal,
+        }
+    }
+}
+
+impl Image {
+    pub fn params(&self) -> LayerParams {
+        LayerParams {
+            offset: self.offset,
+            scale: self.scale,
+            tint: self.tint,
+            opacity: self.opacity,
+            blend: self.blend,
+        }
+    }
+}
+
+/// How a layer is drawn over the ones under it. Offset and scale move the
+/// layer's texture coordinates, so an offset of 0.5 shifts it half a screen.
+/// Decals drawn on the layer move with it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayerParams {
+    pub offset: Vf2d,
+    pub scale: Vf2d,
+    pub tint: Pixel,
+    pub opacity: f32,
+    pub blend: DecalMode,
+}
+
+impl Default for LayerParams {
+    fn default() -> Self {
+        Self {
+            offset: Vf2d::new(0.0, 0.0),
+            scale: Vf2d::new(1.0, 1.0),
+            tint: Pixel::WHITE,
+            opacity: 1.0,
+            blend: DecalMode::Normal,
+        }
+    }
+}
+
+impl LayerParams {
+    //Matches LayerUniforms in layer.wgsl, padded to 16 bytes
+    pub fn uniform_data(&self) -> [f32; 12] {
+        [
+            self.offset.x,
+            self.offset.y,
+            self.scale.x,
+            self.scale.y,
+            self.tint.r() as f32 / 255.0,
+            self.tint.g() as f32 / 255.0,
+            self.tint.b() as f32 / 255.0,
+            self.tint.a() as f32 / 255.0,
+            self.opacity,
+            0.0,
+            0.0,
+            0.0,
+        ]
+    }
 }
 
 pub struct Render<D: OGData + 'static> {
@@ -137,8 +208,10 @@ const fn default_image() -> Image {
         },
         update: false,
         offset: Vf2d { x: 0.0, y: 0.0 },
-        scale: Vf2d { x: 0.0, y: 0.0 },
-        tint: Pixel::BLANK,
+        scale: Vf2d { x: 1.0, y: 1.0 },
+        tint: Pixel::WHITE,
+        opacity: 1.0,
+        blend: DecalMode::Normal,
     }
 }
 
@@ -149,11 +222,8 @@ impl<D: OGData + 'static> LayerDesc<D> {
                 id: 0,
                 shown: false,
                 layer_info: LayerInfo::Image(Image {
-                    offset: Default::default(),
-                    scale: Default::default(),
-                    tint: Pixel::rgb(0, 0, 0),
                     sprite: Sprite::new(0, 0),
-                    update: false,
+                    ..Default::default()
                 }),
                 vec_decal_instance: vec![],
             },
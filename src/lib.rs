@@ -8,56 +8,94 @@
 
 pub mod og_engine;
 // pub mod app;
-//pub mod audio;
+pub mod animation;
+pub mod audio;
+pub mod audio_bus;
+pub mod audio_decode;
+pub mod audio_occlusion;
+pub mod asset;
+pub mod atlas;
+pub mod blend_ext;
 pub mod camera;
 pub mod collision;
-// pub mod debug_gui;
+#[cfg(feature = "debug-gui")]
+pub mod debug_gui;
 pub mod decal;
 pub mod engine;
+pub mod font;
 pub mod game;
+pub mod gamepad;
 pub mod game_object;
 pub mod geometry;
 pub mod gltf_ext;
+pub mod hot_reload;
+pub mod input;
 pub mod layer;
 pub mod math_3d;
 pub mod math_4d;
 pub mod pixel;
 pub mod platform;
 pub mod renderer;
+pub mod replay;
 pub mod sprite;
-//pub mod steam_audio;
+#[cfg(feature = "steam-audio")]
+pub mod steam_audio;
+#[cfg(feature = "steam-audio")]
+pub mod steam_audio_bindgen;
+pub mod text;
+pub mod text_effect;
+pub mod text_input;
 pub mod texture;
 pub mod transform;
+pub mod ui;
 pub mod util;
 
-//pub mod steam_audio_bindgen;
 //use olc_pge_macros as macros;
 
 pub mod prelude {
     pub use crate::{
-        //audio, audio::*,
+        animation, animation::*,
+        audio, audio::*,
+        audio_bus, audio_bus::*,
+        audio_decode, audio_decode::*,
+        audio_occlusion, audio_occlusion::*,
+        asset, asset::*,
+        atlas, atlas::*,
+        blend_ext,
         camera, camera::*,
         collision, collision::*,
         decal, decal::*,
         engine, engine::*,
+        font, font::*,
         game, game::*,
+        gamepad, gamepad::*,
         game_object, game_object::*,
         geometry, geometry::*,
         gltf_ext, gltf_ext::*,
+        hot_reload, hot_reload::*,
+        input, input::*,
         layer, layer::*,
         math_3d, math_3d::*,
         math_4d, math_4d::*,
         pixel, pixel::*,
         platform, platform::*,
         renderer, renderer::*,
+        replay, replay::*,
         sprite, sprite::*,
+        text, text::*,
+        text_effect, text_effect::*,
+        text_input, text_input::*,
         texture, texture::*,
         transform, transform::*,
+        ui, ui::*,
         util, util::*,
-        //steam_audio_bindgen as phonon, steam_audio as effects,
         og_engine::OGGame,
         og_engine::OGData,
         og_engine::OGFuture,
         og_engine::Rcode,
     };
+    #[cfg(feature = "debug-gui")]
+    pub use crate::{debug_gui, debug_gui::*};
+    #[cfg(feature = "steam-audio")]
+    pub use crate::{steam_audio as effects, steam_audio::SteamAudioSpatializer, steam_audio_bindgen as phonon};
 }
@@ -65,6 +65,10 @@ impl From<(u8, u8, u8)> for Pixel {
     }
 }
 
+/// How `OGEngine::draw` combines a pixel with the one already there.
+/// `Mask` only draws opaque pixels, `Alpha` blends by the pixel's alpha and
+/// the engine's blend factor, `Custom` calls the function given to
+/// `set_custom_pixel_mode`.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PixelMode {
     Normal,
@@ -73,6 +77,9 @@ pub enum PixelMode {
     Custom,
 }
 
+/// (x, y, source, destination) -> the pixel to write.
+pub type PixelFunc = Box<dyn Fn(i32, i32, Pixel, Pixel) -> Pixel>;
+
 impl Pixel {
     /// Creates a new pixel with RGBA value.
     pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
@@ -148,32 +155,23 @@ impl Pixel {
         }
     }
 
+    /// `self` drawn over `rhs`. Colours are straight, not premultiplied, and
+    /// the result's alpha is `a1 + a2 * (1 - a1)`.
     pub fn alpha_blend(&self, rhs: &Pixel) -> Pixel {
-        unsafe {
-            let a1 = self.a() as f32 / 255.0;
-            let a2 = rhs.a() as f32 / 255.0;
-            if a1 == 0.0 {
-                return *rhs;
-            }
-
-            let a1p = 1.0 - a1;
-            let (r1, g1, b1) = (
-                self.r() as f32 * a1,
-                self.g() as f32 * a1,
-                self.b() as f32 * a1,
-            );
-            let (r2, g2, b2) = (
-                (rhs.r() as f32 * a2) * a1p,
-                (rhs.g() as f32 * a2) * a1p,
-                (rhs.b() as f32 * a2) * a1p,
-            );
-            Pixel::rgb(
-                (r1 + r2) as u8,
-                (g1 + g2) as u8,
-                (b1 + b2) as u8,
-                //(a1 + a2) as u8,
-            )
+        let a1 = self.a() as f32 / 255.0;
+        if a1 == 0.0 {
+            return *rhs;
         }
+        //What shows of rhs through self
+        let a2 = rhs.a() as f32 / 255.0 * (1.0 - a1);
+        let out_a = a1 + a2;
+        let mix = |s: u8, d: u8| ((s as f32 * a1 + d as f32 * a2) / out_a).round() as u8;
+        Pixel::rgba(
+            mix(self.r(), rhs.r()),
+            mix(self.g(), rhs.g()),
+            mix(self.b(), rhs.b()),
+            (out_a * 255.0).round() as u8,
+        )
     }
 
     pub fn r(&self) -> u8 {
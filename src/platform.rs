@@ -1,5 +1,6 @@
 use super::{
     og_engine::Rcode,
+    text_input::{clipboard_text, TextEvent},
     util::{HWButton, Vf2d, Vi2d},
 };
 use std::collections::hash_map::HashMap;
@@ -15,7 +16,7 @@ use winit::platform::windows::WindowBuilderExtWindows;
 use winit::platform::x11::WindowBuilderExtX11;
 
 use winit::{
-    event::{ElementState, Event, MouseScrollDelta, WindowEvent},
+    event::{ElementState, Event, Ime, MouseScrollDelta, WindowEvent},
     event_loop::EventLoop,
     window::{Window, WindowBuilder},
 };
@@ -49,10 +50,15 @@ pub trait Platform {
     ) -> Rcode {
         Rcode::Ok
     }
-    fn set_window_title(window: &Window, title: String) -> Rcode {
+    fn set_window_title(window: &Window, state: &mut WindowState, title: String) -> Rcode {
         Rcode::Ok
     }
-    fn handle_window_event(window: &Window, event: &Event<()>);
+    fn handle_window_event(
+        window: &Window,
+        event: &Event<()>,
+        input: &mut InputState,
+        state: &mut WindowState,
+    );
     fn handle_system_event_loop(&self) -> Rcode {
         Rcode::Ok
     }
@@ -74,30 +80,59 @@ pub struct PlatformWeb {
 }
 */
 
-pub static mut PLATFORM_DATA: PlatformData = PlatformData::create();
-
-//this is only ever updated from the Platform thread,
-// so immutable references to it are thread safe
 pub type Key = winit::event::VirtualKeyCode;
-pub struct PlatformData {
+
+/// Keyboard, mouse and text input of one engine. The platform layer writes
+/// events into it as they arrive and `update` turns them into this frame's
+/// `HWButton`s.
+#[derive(Clone, Debug)]
+pub struct InputState {
     pub mouse_focus: bool,
     pub key_focus: bool,
-    pub new_key_state_map: Option<HashMap<Key, bool>>,
-    pub old_key_state_map: Option<HashMap<Key, bool>>,
-    pub new_mouse_state_map: Option<Vec<bool>>,
-    pub old_mouse_state_map: Option<Vec<bool>>,
-    pub key_map: Option<HashMap<Key, HWButton>>,
-    pub mouse_map: Option<Vec<HWButton>>,
+    pub new_key_state_map: HashMap<Key, bool>,
+    pub old_key_state_map: HashMap<Key, bool>,
+    pub new_mouse_state_map: Vec<bool>,
+    pub old_mouse_state_map: Vec<bool>,
+    pub key_map: HashMap<Key, HWButton>,
+    pub mouse_map: Vec<HWButton>,
     pub mouse_wheel_delta: i32,
     pub mouse_wheel_delta_cache: i32,
-    pub mouse_position: Option<Vf2d>,
-    pub raw_mouse_position: Option<Vf2d>,
-    pub view_position: Option<Vi2d>,
-    pub window_size: Option<Vi2d>,
-    pub resolution: Option<Vi2d>,
-    pub screen_size: Option<Vi2d>,
-    pub pixel_size: Option<Vi2d>,
+    pub mouse_position: Vf2d,
+    pub raw_mouse_position: Vf2d,
     pub mouse_position_cache: Option<Vf2d>,
+    /// Typed text since the last frame, moved to `OGEngine::text_input`.
+    pub text_events: Vec<TextEvent>,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            mouse_focus: false,
+            key_focus: false,
+            new_key_state_map: HashMap::default(),
+            old_key_state_map: HashMap::default(),
+            new_mouse_state_map: vec![false; 3],
+            old_mouse_state_map: vec![false; 3],
+            key_map: HashMap::default(),
+            mouse_map: vec![HWButton::new(); 3],
+            mouse_wheel_delta: 0,
+            mouse_wheel_delta_cache: 0,
+            mouse_position: Vf2d::new(0.0, 0.0),
+            raw_mouse_position: Vf2d::new(0.0, 0.0),
+            mouse_position_cache: None,
+            text_events: vec![],
+        }
+    }
+}
+
+/// Size, position and settings of the window an engine draws to.
+#[derive(Clone, Debug)]
+pub struct WindowState {
+    pub view_position: Vi2d,
+    pub window_size: Vi2d,
+    pub resolution: Vi2d,
+    pub screen_size: Vi2d,
+    pub pixel_size: Vi2d,
     pub window_alive: bool,
     pub full_screen: bool,
     pub vsync: bool,
@@ -106,72 +141,124 @@ pub struct PlatformData {
     pub y_up_direction: f32,
 }
 
-impl PlatformData {
-    pub const fn create() -> Self {
-        #[cfg(target_arch = "wasm32")]
-        let y_up_direction = 1.0;
-        #[cfg(not(target_arch = "wasm32"))]
-        let y_up_direction = 1.0;
+impl Default for WindowState {
+    fn default() -> Self {
         Self {
-            mouse_focus: false,
-            key_focus: false,
-            new_key_state_map: None,
-            old_key_state_map: None,
-            new_mouse_state_map: None,
-            old_mouse_state_map: None,
-            key_map: None,
-            mouse_map: None,
-            mouse_wheel_delta: 0,
-            mouse_wheel_delta_cache: 0,
-            mouse_position: None,
-            raw_mouse_position: None,
-            view_position: None,
-            window_size: None,
-            pixel_size: None,
-            resolution: None,
-            screen_size: None,
-            mouse_position_cache: None,
+            view_position: Vi2d::new(0, 0),
+            window_size: Vi2d::new(0, 0),
+            resolution: Vi2d::new(0, 0),
+            screen_size: Vi2d::new(0, 0),
+            pixel_size: Vi2d::new(0, 0),
             window_alive: true,
             full_screen: false,
             vsync: false,
             title: String::new(),
             running: true,
-            y_up_direction,
+            y_up_direction: 1.0,
+        }
+    }
+}
+
+impl WindowState {
+    pub fn new(
+        title: &str,
+        screen_width: u32,
+        screen_height: u32,
+        pixel_width: u32,
+        pixel_height: u32,
+        full_screen: bool,
+    ) -> Self {
+        let mut state = Self {
+            resolution: Vi2d::new(
+                (screen_width / pixel_width) as i32,
+                (screen_height / pixel_height) as i32,
+            ),
+            full_screen,
+            title: title.into(),
+            pixel_size: Vi2d::new(pixel_width as i32, pixel_height as i32),
+            ..Default::default()
+        };
+        if !full_screen {
+            state.window_size = Vi2d::new(screen_width as i32, screen_height as i32);
+        }
+        state
+    }
+
+    pub fn update_window_size(&mut self, width: u32, height: u32) {
+        self.window_size = Vi2d::new(width as i32, height as i32);
+    }
+    pub fn update_window_position(&mut self, x: i32, y: i32) {
+        self.view_position = Vi2d::new(x, y);
+    }
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turns the events since the last call into pressed, held and released
+    /// states. Called once per frame.
+    pub fn update(&mut self) {
+        self.clear_keys();
+        for (key, value_new) in &self.new_key_state_map {
+            let value_old = self.old_key_state_map.entry(*key).or_insert(false);
+            let current_key = self.key_map.entry(*key).or_insert_with(HWButton::new);
+            if value_new != value_old {
+                current_key.pressed = *value_new;
+                current_key.released = !*value_new;
+                current_key.held = *value_new;
+            }
+            *value_old = *value_new
+        }
+
+        for i in 0..self.mouse_map.len() {
+            let button = &mut self.mouse_map[i];
+            button.pressed = false;
+            button.released = false;
+            if self.new_mouse_state_map[i] != self.old_mouse_state_map[i] {
+                button.pressed = self.new_mouse_state_map[i];
+                button.released = !self.new_mouse_state_map[i];
+                button.held = self.new_mouse_state_map[i];
+            }
+            self.old_mouse_state_map[i] = self.new_mouse_state_map[i];
+        }
+
+        if let Some(pos) = self.mouse_position_cache {
+            self.mouse_position = pos;
+        }
+        self.mouse_wheel_delta = self.mouse_wheel_delta_cache;
+        self.mouse_wheel_delta_cache = 0;
+    }
+
+    pub fn clear_keys(&mut self) {
+        for value in self.key_map.values_mut() {
+            value.pressed = false;
+            value.released = false;
         }
     }
-    pub fn init(&mut self) {
-        self.new_key_state_map = Some(HashMap::default());
-        self.old_key_state_map = Some(HashMap::default());
-        self.new_mouse_state_map = Some(vec![false; 3]);
-        self.old_mouse_state_map = Some(vec![false; 3]);
-        self.key_map = Some(HashMap::default());
-        self.mouse_map = Some(vec![HWButton::new(); 3]);
-        self.mouse_position = Some((0.0, 0.0).into());
-        self.view_position = Some((0, 0).into());
-        self.window_size = Some((0, 0).into());
-        self.resolution = Some((0, 0).into());
-        self.screen_size = Some((0, 0).into());
-        self.pixel_size = Some((0, 0).into());
-        self.mouse_position_cache = Some((0.0, 0.0).into());
+
+    pub fn get_key(&self, k: Key) -> HWButton {
+        self.key_map.get(&k).copied().unwrap_or_default()
     }
 
-    pub fn update_mouse(&mut self, mut x: i32, mut y: i32) {
-        self.raw_mouse_position = Some(
-            (
-                x as f32 + self.view_position.unwrap_or_default().x as f32,
-                y as f32 + self.view_position.unwrap_or_default().y as f32,
-            )
-                .into(),
+    pub fn get_mouse(&self, i: usize) -> HWButton {
+        self.mouse_map.get(i).copied().unwrap_or_default()
+    }
+
+    pub fn update_mouse(&mut self, window: &WindowState, x: i32, y: i32) {
+        self.raw_mouse_position = Vf2d::new(
+            x as f32 + window.view_position.x as f32,
+            y as f32 + window.view_position.y as f32,
         );
         self.mouse_focus = true;
-        let px_i = self.pixel_size.unwrap_or_default();
-        let px: Vf2d = (px_i.x as f32, px_i.y as f32).into();
+        let px: Vf2d = (window.pixel_size.x as f32, window.pixel_size.y as f32).into();
         let mut temp_mouse: Vi2d = ((x as f32 / px.x) as i32, (y as f32 / px.y) as i32).into();
-        if temp_mouse.x >= (self.window_size.unwrap_or_default().x as f32 / px.x).floor() as i32 {
-            temp_mouse.x = (self.window_size.unwrap_or_default().x as f32 / px.x) as i32 - 1
+        if temp_mouse.x >= (window.window_size.x as f32 / px.x).floor() as i32 {
+            temp_mouse.x = (window.window_size.x as f32 / px.x) as i32 - 1
         }
-        if temp_mouse.y >= (self.window_size.unwrap_or_default().y as f32 / px.y).floor() as i32 {
-            temp_mouse.y = (self.window_size.unwrap_or_default().y as f32 / px.y) as i32 - 1
+        if temp_mouse.y >= (window.window_size.y as f32 / px.y).floor() as i32 {
+            temp_mouse.y = (window.window_size.y as f32 / px.y) as i32 - 1
         }
         //log::trace!("{:x?}", temp_mouse);
         if temp_mouse.x < 0 {
@@ -182,12 +269,6 @@ impl PlatformData {
         }
         self.mouse_position_cache = Some((temp_mouse.x as f32, temp_mouse.y as f32).into());
     }
-    pub fn update_window_size(&mut self, width: u32, height: u32) {
-        self.window_size = Some(Vi2d::from(((width as i32), (height as i32))));
-    }
-    pub fn update_window_position(&mut self, x: i32, y: i32) {
-        self.view_position = Some(Vi2d::from(((x as i32), (y as i32))));
-    }
     pub fn update_mouse_wheel(&mut self, delta: i32) {
         self.mouse_wheel_delta_cache += delta;
     }
@@ -198,15 +279,62 @@ impl PlatformData {
         self.key_focus = b
     }
     pub fn update_key_state(&mut self, k: Key, b: bool) {
-        *self
-            .new_key_state_map
-            .as_mut()
-            .unwrap()
-            .entry(k)
-            .or_insert(b) = b;
+        self.new_key_state_map.insert(k, b);
     }
     pub fn update_mouse_state(&mut self, i: i32, b: bool) {
-        self.new_mouse_state_map.as_mut().unwrap()[i as usize] = b;
+        //Only left, right and middle are tracked
+        if let Some(state) = self.new_mouse_state_map.get_mut(i as usize) {
+            *state = b;
+        }
+    }
+    fn key_down(&self, k: Key) -> bool {
+        self.new_key_state_map.get(&k).copied().unwrap_or(false)
+    }
+    //Ctrl without Alt, since AltGr reports as Ctrl+Alt on Windows
+    fn command_held(&self) -> bool {
+        let ctrl = self.key_down(Key::LControl) || self.key_down(Key::RControl);
+        let alt = self.key_down(Key::LAlt) || self.key_down(Key::RAlt);
+        let cmd = self.key_down(Key::LWin) || self.key_down(Key::RWin);
+        (ctrl && !alt) || (cfg!(target_os = "macos") && cmd)
+    }
+    pub fn update_text_key(&mut self, k: Key) {
+        let event = match k {
+            Key::Back => TextEvent::Backspace,
+            Key::Delete => TextEvent::Delete,
+            Key::Left => TextEvent::Left,
+            Key::Right => TextEvent::Right,
+            Key::Home => TextEvent::Home,
+            Key::End => TextEvent::End,
+            Key::Return | Key::NumpadEnter => TextEvent::Submit,
+            Key::Escape => TextEvent::Cancel,
+            Key::V if self.command_held() => match clipboard_text() {
+                Some(text) => TextEvent::Paste(text),
+                None => return,
+            },
+            _ => return,
+        };
+        self.text_events.insert(self.text_events.len(), event);
+    }
+    pub fn update_received_character(&mut self, c: char) {
+        //Editing keys come from update_text_key, which also sees key repeats
+        if !c.is_control() && !self.command_held() {
+            self.text_events.insert(self.text_events.len(), TextEvent::Char(c));
+        }
+    }
+    pub fn update_ime(&mut self, ime: &Ime) {
+        let event = match ime {
+            Ime::Preedit(text, cursor) => TextEvent::Preedit {
+                text: text.clone(),
+                cursor: *cursor,
+            },
+            Ime::Commit(text) => TextEvent::Commit(text.clone()),
+            Ime::Disabled => TextEvent::Preedit {
+                text: String::new(),
+                cursor: None,
+            },
+            Ime::Enabled => return,
+        };
+        self.text_events.insert(self.text_events.len(), event);
     }
 }
 
@@ -244,77 +372,88 @@ impl Platform for PlatformWindows {
         (window, event_loop)
     }
 
-    fn set_window_title(window: &Window, title: String) -> Rcode {
+    fn set_window_title(window: &Window, state: &mut WindowState, title: String) -> Rcode {
         window.set_title(&title);
-        unsafe { PLATFORM_DATA.title = title }
+        state.title = title;
         Rcode::Ok
     }
 
-    fn handle_window_event(window: &Window, event: &Event<()>) {
-        unsafe {
-            if let Event::WindowEvent {
-                window_id: _,
-                ref event,
-            } = event
-            {
-                match event {
-                    WindowEvent::CursorMoved {
-                        device_id: _,
-                        position,
-                        modifiers: _,
-                    } => {
-                        PLATFORM_DATA.update_mouse(position.x as i32, position.y as i32);
-                    }
-                    WindowEvent::Resized(size) => {
-                        PLATFORM_DATA.update_window_size(size.width, size.height);
-                    }
-                    WindowEvent::Moved(position) => {
-                        PLATFORM_DATA.update_window_position(position.x, position.y);
-                    }
-                    WindowEvent::MouseWheel {
-                        device_id: _,
-                        delta: MouseScrollDelta::LineDelta(h, v),
-                        phase,
-                        modifiers: _,
-                    } => {
-                        PLATFORM_DATA.update_mouse_wheel(*v as i32);
+    fn handle_window_event(
+        window: &Window,
+        event: &Event<()>,
+        input: &mut InputState,
+        state: &mut WindowState,
+    ) {
+        if let Event::WindowEvent {
+            window_id: _,
+            ref event,
+        } = event
+        {
+            match event {
+                WindowEvent::CursorMoved {
+                    device_id: _,
+                    position,
+                    modifiers: _,
+                } => {
+                    input.update_mouse(state, position.x as i32, position.y as i32);
+                }
+                WindowEvent::Resized(size) => {
+                    state.update_window_size(size.width, size.height);
+                }
+                WindowEvent::Moved(position) => {
+                    state.update_window_position(position.x, position.y);
+                }
+                WindowEvent::MouseWheel {
+                    device_id: _,
+                    delta: MouseScrollDelta::LineDelta(h, v),
+                    phase,
+                    modifiers: _,
+                } => {
+                    input.update_mouse_wheel(*v as i32);
+                }
+                WindowEvent::CursorLeft { device_id: _ } => {
+                    input.update_mouse_focus(false);
+                }
+                WindowEvent::Focused(focus) => {
+                    input.update_key_focus(*focus);
+                }
+                WindowEvent::KeyboardInput {
+                    device_id: _,
+                    input: key_input,
+                    is_synthetic,
+                } => {
+                    if let Some(key) = key_input.virtual_keycode {
+                        input.update_key_state(key, key_input.state == ElementState::Pressed);
+                        if key_input.state == ElementState::Pressed {
+                            input.update_text_key(key);
+                        }
                     }
-                    WindowEvent::CursorLeft { device_id: _ } => {
-                        PLATFORM_DATA.update_mouse_focus(false);
+                }
+                WindowEvent::ReceivedCharacter(c) => {
+                    input.update_received_character(*c);
+                }
+                WindowEvent::Ime(ime) => {
+                    input.update_ime(ime);
+                }
+                WindowEvent::MouseInput {
+                    device_id: _,
+                    state,
+                    button,
+                    modifiers: _,
+                } => match button {
+                    winit::event::MouseButton::Left => {
+                        input.update_mouse_state(0, state == &ElementState::Pressed)
                     }
-                    WindowEvent::Focused(focus) => {
-                        PLATFORM_DATA.update_key_focus(*focus);
+                    winit::event::MouseButton::Right => {
+                        input.update_mouse_state(1, state == &ElementState::Pressed)
                     }
-                    WindowEvent::KeyboardInput {
-                        device_id: _,
-                        input,
-                        is_synthetic,
-                    } => {
-                        if let Some(key) = input.virtual_keycode {
-                            PLATFORM_DATA
-                                .update_key_state(key, input.state == ElementState::Pressed);
-                        }
+                    winit::event::MouseButton::Middle => {
+                        input.update_mouse_state(2, state == &ElementState::Pressed)
                     }
-                    WindowEvent::MouseInput {
-                        device_id: _,
-                        state,
-                        button,
-                        modifiers: _,
-                    } => match button {
-                        winit::event::MouseButton::Left => {
-                            PLATFORM_DATA.update_mouse_state(0, state == &ElementState::Pressed)
-                        }
-                        winit::event::MouseButton::Right => {
-                            PLATFORM_DATA.update_mouse_state(1, state == &ElementState::Pressed)
-                        }
-                        winit::event::MouseButton::Middle => {
-                            PLATFORM_DATA.update_mouse_state(2, state == &ElementState::Pressed)
-                        }
-                        winit::event::MouseButton::Other(b) => PLATFORM_DATA
-                            .update_mouse_state(*b as i32, state == &ElementState::Pressed),
-                    },
-                    _ => {}
-                }
+                    winit::event::MouseButton::Other(b) => input
+                        .update_mouse_state(*b as i32, state == &ElementState::Pressed),
+                },
+                _ => {}
             }
         }
     }
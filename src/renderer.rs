@@ -1,17 +1,17 @@
 use super::{
     camera::{Camera, RawMat},
-    decal::DecalInstance,
+    decal::{DecalBatch, DecalBatcher, DecalInstance, DecalMode},
     game_object::GameObject,
     geometry::{Mesh, Primitives, Triangle, Vertex},
-    layer::{DrawData, LayerMask, Mask},
+    layer::{DrawData, LayerMask, LayerParams, Mask},
     math_3d::Vector3,
     pixel::Pixel,
-    platform::PLATFORM_DATA,
     sprite::Sprite,
     texture::Texture,
     util::{Vf2d, Vi2d},
     og_engine::Rcode,
 };
+use std::collections::HashMap;
 use wgpu::{util::DeviceExt, InstanceFlags};
 
 #[cfg(target_arch = "wasm32")]
@@ -22,6 +22,8 @@ use winit::platform::web::WindowExtWebSys;
 pub const VERT_BUFFER_SIZE: usize = 150 /*MB*/ * 1024 * 1024 / std::mem::size_of::<Vertex>();
 pub const MAX_VERTICES: usize = VERT_BUFFER_SIZE;
 pub const INDEX_BUFFER_SIZE: usize = 15 /*MB*/ * 1024 * 1024 / std::mem::size_of::<u32>();
+//Vertices the decal buffer starts with, it grows as needed
+const DECAL_VERTEX_START: usize = 6 * 1024;
 
 pub struct Renderer {
     pub surface: wgpu::Surface,
@@ -30,13 +32,42 @@ pub struct Renderer {
     pub size: winit::dpi::PhysicalSize<u32>,
     pub surface_texture: Option<wgpu::SurfaceTexture>,
     pub surface_texture_view: wgpu::TextureView,
-    pub render_pipeline: Option<wgpu::RenderPipeline>,
+    /// Composites layers, one per blend mode.
+    pub layer_pipelines: HashMap<DecalMode, wgpu::RenderPipeline>,
+    custom_layer_pipelines: HashMap<u32, HashMap<DecalMode, wgpu::RenderPipeline>>,
+    //Sources of the custom layer shaders, rebuilt with the layer pipelines
+    custom_layer_shaders: HashMap<u32, String>,
+    layer_pipeline_layout: Option<wgpu::PipelineLayout>,
+    layer_uniform_layout: Option<wgpu::BindGroupLayout>,
+    layer_uniforms: HashMap<u32, (wgpu::Buffer, wgpu::BindGroup)>,
+    //Used for the frame texture and decals drawn outside of layers
+    default_layer_uniform: Option<(wgpu::Buffer, wgpu::BindGroup)>,
+    /// Offset, scale, tint and blending of each layer, set every frame.
+    pub layer_params: HashMap<u32, LayerParams>,
+    //Repeats, so scrolled layers wrap around
+    layer_sampler: wgpu::Sampler,
     pub render_3D_pipeline_indexed: Option<wgpu::RenderPipeline>,
     pub decal_buffer: wgpu::Buffer,
     pub decals: Vec<Texture>,
     pub active_decals: Vec<u32>,
     pub decal_counter: i32,
     pub layer_textures: Option<Vec<wgpu::BindGroup>>,
+    /// Layer ids of `layer_textures` after the frame texture.
+    pub layer_texture_ids: Vec<u32>,
+    /// One per blend mode.
+    pub decal_pipelines: HashMap<DecalMode, wgpu::RenderPipeline>,
+    pub decal_shader: wgpu::ShaderModule,
+    pub decal_vertex_buffer: wgpu::Buffer,
+    decal_vertex_capacity: usize,
+    decal_batcher: DecalBatcher,
+    //Batches of each layer this frame
+    layer_decal_batches: HashMap<u32, std::ops::Range<usize>>,
+    //Keyed by texture id, -1 is plain white for untextured decals
+    decal_bind_groups: HashMap<i32, wgpu::BindGroup>,
+    white_texture: Texture,
+    /// Group decals by texture and blend mode before drawing. Fewer draw
+    /// calls, but overlapping decals from different textures can swap.
+    pub sort_decals: bool,
     pub texture_sampler: Option<wgpu::Sampler>,
     pub bind_group_layout: Option<wgpu::BindGroupLayout>,
     pub bind_group: Option<wgpu::BindGroup>,
@@ -61,10 +92,12 @@ pub struct Renderer {
     pub camera: Camera,
     pub preferred_texture_format: wgpu::TextureFormat,
     pub surface_config: wgpu::SurfaceConfiguration,
+    /// Window size in physical pixels, kept in sync with `WindowState`.
+    pub window_size: Vi2d,
 }
 
 impl Renderer {
-    pub async fn new(window: &winit::window::Window) -> Self {
+    pub async fn new(window: &winit::window::Window, window_size: Vi2d) -> Self {
         let size = window.inner_size();
         // BackendBit::PRIMARY => Vulkan + Metal + DX12 + Browser WebGPU
 
@@ -139,6 +172,17 @@ impl Renderer {
             source: wgpu::ShaderSource::Wgsl(include_str!("shaders/indexed.wgsl").into()),
         });
 
+        let decal_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("decal_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/decal.wgsl").into()),
+        });
+        let decal_vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Decal Vertex Buffer"),
+            size: (DECAL_VERTEX_START * std::mem::size_of::<Vertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         let decal_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Decal Buffer"),
             usage: wgpu::BufferUsages::VERTEX,
@@ -205,6 +249,8 @@ impl Renderer {
         );
 
         default_texture.update(&queue, &spr);
+        let mut white_texture = Texture::new(&device, 1, 1, preferred_texture_format);
+        white_texture.update(&queue, &Sprite::new_with_data(1, 1, vec![Pixel::WHITE], preferred_texture_format));
         let default_texture_bind = device.create_bind_group(&wgpu::BindGroupDescriptor {
             entries: &[wgpu::BindGroupEntry {
                 binding: 0,
@@ -232,6 +278,11 @@ impl Renderer {
             ],
         });*/
         let decal_sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+        let layer_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            ..Default::default()
+        });
 
         let decals = vec![];
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -276,7 +327,6 @@ impl Renderer {
             ],
         });
         let mut cam_data: Vec<u8> = bytemuck::cast_slice(&[RawMat::default()]).into();
-        let window_size = unsafe { PLATFORM_DATA.window_size.as_ref().unwrap() };
         let x_bytes: [u8; 4] = window_size.x.to_ne_bytes();
         let y_bytes: [u8; 4] = window_size.y.to_ne_bytes();
         let padding: [u8; 8] = [0; 8];
@@ -311,7 +361,6 @@ impl Renderer {
                 ],
             }));
 
-        let window_size = unsafe { PLATFORM_DATA.window_size.unwrap() };
         let depth_texture = Texture::new(
             &device,
             window_size.x as u32,
@@ -340,7 +389,15 @@ impl Renderer {
             size,
             surface_texture,
             surface_texture_view,
-            render_pipeline: None,
+            layer_pipelines: HashMap::new(),
+            custom_layer_pipelines: HashMap::new(),
+            custom_layer_shaders: HashMap::new(),
+            layer_pipeline_layout: None,
+            layer_uniform_layout: None,
+            layer_uniforms: HashMap::new(),
+            default_layer_uniform: None,
+            layer_params: HashMap::new(),
+            layer_sampler,
             render_3D_pipeline_indexed: None,
             decal_buffer,
             decals,
@@ -350,6 +407,16 @@ impl Renderer {
             bind_group_layout: None,
             bind_group: None,
             layer_textures: None,
+            layer_texture_ids: vec![],
+            decal_pipelines: HashMap::new(),
+            decal_shader,
+            decal_vertex_buffer,
+            decal_vertex_capacity: DECAL_VERTEX_START,
+            decal_batcher: DecalBatcher::default(),
+            layer_decal_batches: HashMap::new(),
+            decal_bind_groups: HashMap::new(),
+            white_texture,
+            sort_decals: true,
             camera_buffer,
             cam_sampler_uniform_group,
             meshes: vec![],
@@ -371,6 +438,7 @@ impl Renderer {
             camera: Camera::default(),
             preferred_texture_format,
             surface_config,
+            window_size,
         }
     }
 
@@ -423,60 +491,154 @@ impl Renderer {
                         },
                     ],
                 });
+        let layer_uniform_layout =
+            self.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("layer uniform layout"),
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                });
         let render_pipeline_layout =
             self.device
                 .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                     label: Some("Render Pipeline Layout"),
-                    bind_group_layouts: &[&bind_group_layout],
+                    bind_group_layouts: &[&bind_group_layout, &layer_uniform_layout],
                     push_constant_ranges: &[],
                 });
-        let vert_descriptor = wgpu::VertexState {
-            module: &self.layer_shader,
-            entry_point: "vs_main",     // 1.
-            buffers: &[Vertex::desc()], // 2.
-        };
-        let sc_desc = &[Some(wgpu::ColorTargetState {
-            format: self.preferred_texture_format,
-            blend: Some(wgpu::BlendState {
-                color: wgpu::BlendComponent {
-                    operation: wgpu::BlendOperation::Add,
-                    src_factor: wgpu::BlendFactor::SrcAlpha,
-                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                },
-                alpha: wgpu::BlendComponent {
-                    operation: wgpu::BlendOperation::Add,
-                    src_factor: wgpu::BlendFactor::One,
-                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                },
-            }),
-            write_mask: wgpu::ColorWrites::ALL,
-        })];
-        let pipe_line_desc = wgpu::RenderPipelineDescriptor {
-            label: Some("Setup Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: vert_descriptor,
-            fragment: Some(wgpu::FragmentState {
-                // 3.
-                module: &self.layer_shader,
-                entry_point: "fs_main",
-                targets: sc_desc,
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList, // 1.
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw, // 2.
-                cull_mode: Some(wgpu::Face::Back),
-                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-                unclipped_depth: false,
-            },
-            depth_stencil: None, // 1.
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-        };
+        self.layer_pipelines = self.create_2d_pipelines(&render_pipeline_layout, &self.layer_shader);
+        self.decal_pipelines = self.create_2d_pipelines(&render_pipeline_layout, &self.decal_shader);
+        self.default_layer_uniform = Some(self.create_layer_uniform(&layer_uniform_layout, &LayerParams::default()));
+        self.custom_layer_pipelines = self
+            .custom_layer_shaders
+            .iter()
+            .map(|(id, source)| {
+                let shader = self.create_shader_module(source);
+                (*id, self.create_2d_pipelines(&render_pipeline_layout, &shader))
+            })
+            .collect();
         self.bind_group_layout = Some(bind_group_layout);
-        self.render_pipeline = Some(self.device.create_render_pipeline(&pipe_line_desc));
+        self.layer_uniform_layout = Some(layer_uniform_layout);
+        self.layer_pipeline_layout = Some(render_pipeline_layout);
+        self.layer_uniforms.clear();
+        self.decal_bind_groups.clear();
+    }
+
+    //A pipeline per blend mode for drawing 2D triangles with `shader`
+    fn create_2d_pipelines(
+        &self,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+    ) -> HashMap<DecalMode, wgpu::RenderPipeline> {
+        DecalMode::ALL
+            .iter()
+            .map(|mode| {
+                let targets = &[Some(wgpu::ColorTargetState {
+                    format: self.preferred_texture_format,
+                    blend: Some(mode.blend_state()),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })];
+                let pipeline = self.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("2D Pipeline"),
+                    layout: Some(layout),
+                    vertex: wgpu::VertexState {
+                        module: shader,
+                        entry_point: "vs_main",
+                        buffers: &[Vertex::desc()],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: shader,
+                        entry_point: "fs_main",
+                        targets,
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face: wgpu::FrontFace::Ccw,
+                        //Decals drawn with a negative scale are mirrored
+                        cull_mode: None,
+                        polygon_mode: wgpu::PolygonMode::Fill,
+                        conservative: false,
+                        unclipped_depth: false,
+                    },
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                });
+                (*mode, pipeline)
+            })
+            .collect()
+    }
+
+    fn create_layer_uniform(
+        &self,
+        layout: &wgpu::BindGroupLayout,
+        params: &LayerParams,
+    ) -> (wgpu::Buffer, wgpu::BindGroup) {
+        let buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Layer Uniform Buffer"),
+            contents: bytemuck::cast_slice(&params.uniform_data()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("layer uniform group"),
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+        (buffer, bind_group)
+    }
+
+    /// Composites `layer_id` with a shader of its own instead of
+    /// `shaders/layer.wgsl`. It has the same bindings and entry points:
+    /// the layer texture and sampler in group 0 and the `LayerParams`
+    /// uniform in group 1. Set before the layer pipeline is set up, it is
+    /// built along with it.
+    pub fn set_layer_shader(&mut self, layer_id: u32, source: &str) {
+        self.custom_layer_shaders.insert(layer_id, source.to_string());
+        match self.layer_pipeline_layout.as_ref() {
+            Some(layout) => {
+                let shader = self.create_shader_module(source);
+                let pipelines = self.create_2d_pipelines(layout, &shader);
+                self.custom_layer_pipelines.insert(layer_id, pipelines);
+            }
+            None => log::info!("Shader for layer {} is built with the layer pipeline", layer_id),
+        }
+    }
+
+    pub fn clear_layer_shader(&mut self, layer_id: u32) {
+        self.custom_layer_shaders.remove(&layer_id);
+        self.custom_layer_pipelines.remove(&layer_id);
+    }
+
+    //Writes this frame's `layer_params` to each layer's uniform buffer
+    fn update_layer_uniforms(&mut self) {
+        let layout = match self.layer_uniform_layout.as_ref() {
+            Some(layout) => layout,
+            None => return,
+        };
+        for id in &self.layer_texture_ids {
+            let params = self.layer_params.get(id).copied().unwrap_or_default();
+            match self.layer_uniforms.get(id) {
+                Some((buffer, _)) => {
+                    self.queue
+                        .write_buffer(buffer, 0, bytemuck::cast_slice(&params.uniform_data()))
+                }
+                None => {
+                    let uniform = self.create_layer_uniform(layout, &params);
+                    self.layer_uniforms.insert(*id, uniform);
+                }
+            }
+        }
     }
 
     pub fn setup_3D_pipeline(&mut self) {
@@ -566,6 +728,12 @@ impl Renderer {
     }
 
     pub fn update_layer_texture_groups(&mut self) {
+        self.layer_texture_ids = self
+            .active_decals
+            .iter()
+            .copied()
+            .filter(|k| self.decals[*k as usize].texture_bundle.is_some())
+            .collect();
         //Put the frame_texture as the first thing drawn
         self.layer_textures = Some(
             std::iter::once(
@@ -609,7 +777,7 @@ impl Renderer {
                                         wgpu::BindGroupEntry {
                                             binding: 1,
                                             resource: wgpu::BindingResource::Sampler(
-                                                self.texture_sampler.as_ref().unwrap(),
+                                                &self.layer_sampler,
                                             ),
                                         },
                                     ],
@@ -828,7 +996,7 @@ impl Renderer {
             return;
         }
         let mut cam_data: Vec<u8> = bytemuck::cast_slice(&[camera.mat]).into();
-        let window_size = unsafe { PLATFORM_DATA.window_size.as_ref().unwrap() };
+        let window_size = self.window_size;
         let (x, y) = (window_size.x as f32, window_size.y as f32);
         let x_bytes: [u8; 4] = x.to_ne_bytes();
         let y_bytes: [u8; 4] = y.to_ne_bytes();
@@ -907,6 +1075,7 @@ impl Renderer {
 
     pub fn draw_layers(&mut self, encoder: &mut wgpu::CommandEncoder) -> Rcode {
         //let frame = self.get_frame().expect("Couldn't get frame");
+        self.update_layer_uniforms();
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
@@ -922,14 +1091,42 @@ impl Renderer {
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
-            if let Some(render_pipeline) = self.render_pipeline.as_ref() {
-                render_pass.set_pipeline(render_pipeline);
-            }
             if let Some(textures) = self.layer_textures.as_ref() {
-                for tex_group in textures {
+                for (i, tex_group) in textures.iter().enumerate() {
+                    //The frame texture comes first and isn't a layer
+                    let id = i.checked_sub(1).and_then(|i| self.layer_texture_ids.get(i));
+                    let params = id.and_then(|id| self.layer_params.get(id)).copied().unwrap_or_default();
+                    let uniform = id
+                        .and_then(|id| self.layer_uniforms.get(id))
+                        .or(self.default_layer_uniform.as_ref());
+                    let pipeline = id
+                        .and_then(|id| self.custom_layer_pipelines.get(id))
+                        .unwrap_or(&self.layer_pipelines)
+                        .get(&params.blend);
+                    let (pipeline, (_, uniform)) = match (pipeline, uniform) {
+                        (Some(pipeline), Some(uniform)) => (pipeline, uniform),
+                        _ => continue,
+                    };
+                    render_pass.set_pipeline(pipeline);
                     render_pass.set_bind_group(0, tex_group, &[]);
+                    render_pass.set_bind_group(1, uniform, &[]);
                     render_pass.set_vertex_buffer(0, self.decal_buffer.slice(..));
                     render_pass.draw(0..6, 0..1);
+                    //Each layer's decals go on top of its image
+                    let batches = id.and_then(|id| self.layer_decal_batches.get(id));
+                    if let Some(batches) = batches {
+                        render_pass.set_vertex_buffer(0, self.decal_vertex_buffer.slice(..));
+                        let mut mode = None;
+                        for batch in &self.decal_batcher.batches[batches.clone()] {
+                            if mode != Some(batch.mode) {
+                                if let Some(pipeline) = self.decal_pipelines.get(&batch.mode) {
+                                    render_pass.set_pipeline(pipeline);
+                                }
+                                mode = Some(batch.mode);
+                            }
+                            self.draw_decal_batch(batch, &mut render_pass);
+                        }
+                    }
                 }
             }
         }
@@ -978,8 +1175,6 @@ impl Renderer {
         let texture = Texture::new(&self.device, width, height, format);
 
         self.decals.insert(self.decal_counter as usize, texture);
-        self.active_decals
-            .insert(self.active_decals.len(), self.decal_counter as u32);
         self.decal_counter += 1;
         //return the newly created layer's id
         self.decal_counter - 1
@@ -1098,57 +1293,154 @@ impl Renderer {
             );
         }
     }
-    pub fn draw_decal_quad(decal: &mut DecalInstance) {
-        //I'm wrapping this whole thing in unsafe because
-        // it accesses Union values
-        /*unsafe {
-            if decal.decal.is_none() {
-                (GL.glBindTexture)(GL_TEXTURE_2D, 0);
-
-                (GL.glBegin)(GL_QUADS);
-                (GL.glColor4ub)(decal.tint[0].rgba.0, decal.tint[0].rgba.1,
-                                decal.tint[0].rgba.2, decal.tint[0].rgba.3);
-                (GL.glTexCoord4f)(decal.uv[0].x, decal.uv[0].y, 0.0, decal.w[0]);
-                (GL.glVertex2f)(decal.pos[0].x, decal.pos[0].y);
-
-                (GL.glColor4ub)(decal.tint[1].rgba.0, decal.tint[1].rgba.1,
-                                decal.tint[1].rgba.2, decal.tint[1].rgba.3);
-                (GL.glTexCoord4f)(decal.uv[1].x, decal.uv[1].y, 0.0, decal.w[1]);
-                (GL.glVertex2f)(decal.pos[1].x, decal.pos[1].y);
-
-                (GL.glColor4ub)(decal.tint[2].rgba.0, decal.tint[2].rgba.1,
-                                decal.tint[2].rgba.2, decal.tint[2].rgba.3);
-                (GL.glTexCoord4f)(decal.uv[2].x, decal.uv[2].y, 0.0, decal.w[2]);
-                (GL.glVertex2f)(decal.pos[2].x, decal.pos[2].y);
-
-                (GL.glColor4ub)(decal.tint[3].rgba.0, decal.tint[3].rgba.1,
-                                decal.tint[3].rgba.2, decal.tint[3].rgba.3);
-                (GL.glTexCoord4f)(decal.uv[3].x, decal.uv[3].y, 0.0, decal.w[3]);
-                (GL.glVertex2f)(decal.pos[3].x, decal.pos[3].y);
-                (GL.glEnd)();
-            } else {
-                let decal_id = decal.get().id;
-                (GL.glBindTexture)(GL_TEXTURE_2D, decal_id as u32);
-                (GL.glBegin)(GL_QUADS);
-                (GL.glColor4ub)(decal.tint[0].rgba.0, decal.tint[0].rgba.1,
-                                decal.tint[0].rgba.2, decal.tint[0].rgba.3);
-
-                (GL.glTexCoord4f)(decal.uv[0].x, decal.uv[0].y, 0.0, decal.w[0]);
-                (GL.glVertex2f)(decal.pos[0].x, decal.pos[0].y);
-
-                (GL.glTexCoord4f)(decal.uv[1].x, decal.uv[1].y, 0.0, decal.w[1]);
-                (GL.glVertex2f)(decal.pos[1].x, decal.pos[1].y);
-
-                (GL.glTexCoord4f)(decal.uv[2].x, decal.uv[2].y, 0.0, decal.w[2]);
-                (GL.glVertex2f)(decal.pos[2].x, decal.pos[2].y);
-
-                (GL.glTexCoord4f)(decal.uv[3].x, decal.uv[3].y, 0.0, decal.w[3]);
-                (GL.glVertex2f)(decal.pos[3].x, decal.pos[3].y);
-                (GL.glEnd)();
+    fn texture_bind_group(&self, id: i32) -> Option<wgpu::BindGroup> {
+        let texture = if id < 0 {
+            &self.white_texture
+        } else {
+            self.decals.get(id as usize)?
+        };
+        let bundle = texture.texture_bundle.as_ref()?;
+        Some(self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&bundle.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(self.texture_sampler.as_ref().unwrap()),
+                },
+            ],
+            layout: self.bind_group_layout.as_ref()?,
+            label: Some("decal bind group"),
+        }))
+    }
+
+    /// Batches the decals queued on each layer and uploads their vertices.
+    /// Called once a frame before `draw_layers`.
+    pub fn prepare_decals(&mut self, layers: &[(u32, &[DecalInstance])]) {
+        self.decal_batcher.clear();
+        self.layer_decal_batches.clear();
+        for (id, instances) in layers {
+            if !instances.is_empty() {
+                let batches = self.decal_batcher.add(instances, self.sort_decals);
+                self.layer_decal_batches.insert(*id, batches);
+            }
+        }
+        let vertices = &self.decal_batcher.vertices;
+        if vertices.len() > self.decal_vertex_capacity {
+            self.decal_vertex_capacity = vertices.len().next_power_of_two();
+            self.decal_vertex_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Decal Vertex Buffer"),
+                size: (self.decal_vertex_capacity * std::mem::size_of::<Vertex>()) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+        }
+        if !vertices.is_empty() {
+            self.queue
+                .write_buffer(&self.decal_vertex_buffer, 0, bytemuck::cast_slice(vertices.as_slice()));
+        }
+        for batch in &self.decal_batcher.batches {
+            if !self.decal_bind_groups.contains_key(&batch.texture) {
+                if let Some(bind_group) = self.texture_bind_group(batch.texture) {
+                    self.decal_bind_groups.insert(batch.texture, bind_group);
+                }
+            }
+        }
+    }
+
+    /// Draws a batch from `prepare_decals`, the pipeline for its mode and
+    /// the decal vertex buffer must be set.
+    pub fn draw_decal_batch<'a>(&'a self, batch: &DecalBatch, render_pass: &mut wgpu::RenderPass<'a>) {
+        if let Some(bind_group) = self.decal_bind_groups.get(&batch.texture) {
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.draw(batch.vertices.clone(), 0..1);
+        }
+    }
+
+    /// Draws one decal straight onto `target`, outside of any layer.
+    pub fn draw_decal_quad(&self, decal: &DecalInstance, target: &Texture) {
+        self.draw_decals_to(std::slice::from_ref(decal), target);
+    }
+
+    /// Draws triangles in clip space straight onto `target` with the
+    /// texture `texture`, -1 for none. Tex coords are (u, v, w).
+    pub fn draw_triangles(&self, triangles: &[Triangle], texture: i32, target: &Texture) {
+        let texture = (texture >= 0).then(|| {
+            std::sync::Arc::new(super::decal::SmallD {
+                id: texture,
+                sprite: Sprite::new(0, 0),
+                uv_scale: Vf2d::new(1.0, 1.0),
+                uv_offset: Vf2d::new(0.0, 0.0),
+            })
+        });
+        let decals: Vec<DecalInstance> = triangles
+            .iter()
+            .map(|tri| DecalInstance {
+                decal: texture.clone(),
+                ..DecalInstance::from(tri)
+            })
+            .collect();
+        self.draw_decals_to(&decals, target);
+    }
+
+    fn draw_decals_to(&self, decals: &[DecalInstance], target: &Texture) {
+        let view = match target.texture_bundle.as_ref() {
+            Some(bundle) => &bundle.view,
+            None => return,
+        };
+        let mut batcher = DecalBatcher::default();
+        batcher.add(decals, false);
+        if batcher.vertices.is_empty() {
+            return;
+        }
+        let bind_groups: HashMap<i32, wgpu::BindGroup> = batcher
+            .batches
+            .iter()
+            .filter_map(|batch| Some((batch.texture, self.texture_bind_group(batch.texture)?)))
+            .collect();
+        let buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Decal Quad Buffer"),
+            contents: bytemuck::cast_slice(batcher.vertices.as_slice()),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Decal Encoder"),
+            });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Decal Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            if let Some((_, uniform)) = self.default_layer_uniform.as_ref() {
+                render_pass.set_bind_group(1, uniform, &[]);
+            }
+            for batch in &batcher.batches {
+                if let (Some(pipeline), Some(bind_group)) =
+                    (self.decal_pipelines.get(&batch.mode), bind_groups.get(&batch.texture))
+                {
+                    render_pass.set_pipeline(pipeline);
+                    render_pass.set_bind_group(0, bind_group, &[]);
+                    render_pass.draw(batch.vertices.clone(), 0..1);
+                }
             }
-        }*/
+        }
+        self.queue.submit(std::iter::once(encoder.finish()));
     }
-    pub fn draw_triangles(triangles: &[Triangle], texture: u32) {}
 
     pub fn get_texture(&self, index: i32) -> Option<&Texture>{
         if index >= 0 {
@@ -0,0 +1,676 @@
+use super::{
+    gamepad::{GamepadAxis, GamepadButton, GamepadEvent, Gamepads},
+    og_engine::Rcode,
+    platform::{InputState, Key},
+    text_input::TextEvent,
+    util::Vf2d,
+};
+use std::collections::HashMap;
+
+const MAGIC: &[u8; 4] = b"OGIR";
+//Version 2 added typed text, 3 gamepad events
+const VERSION: u32 = 3;
+
+const FLAG_POSITION: u8 = 1 << 3;
+const FLAG_WHEEL: u8 = 1 << 4;
+const FLAG_KEYS: u8 = 1 << 5;
+const FLAG_TEXT: u8 = 1 << 6;
+const FLAG_GAMEPAD: u8 = 1 << 7;
+//Set on a key index when the key went down
+const KEY_DOWN: u16 = 1 << 15;
+
+//Gamepad buttons and axes are stored as their index in these
+const GAMEPAD_BUTTONS: [GamepadButton; 17] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::LeftBumper,
+    GamepadButton::RightBumper,
+    GamepadButton::LeftTrigger,
+    GamepadButton::RightTrigger,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::Mode,
+    GamepadButton::LeftStick,
+    GamepadButton::RightStick,
+    GamepadButton::DPadUp,
+    GamepadButton::DPadDown,
+    GamepadButton::DPadLeft,
+    GamepadButton::DPadRight,
+];
+const GAMEPAD_AXES: [GamepadAxis; 6] = [
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+    GamepadAxis::LeftTrigger,
+    GamepadAxis::RightTrigger,
+];
+
+/// Raw input for one frame, as it was before `update_inputs` turned it into
+/// `HWButton`s.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputFrame {
+    /// Frame time passed to `on_engine_update`.
+    pub elapsed: f64,
+    /// Keys that changed since the last frame.
+    pub keys: Vec<(Key, bool)>,
+    pub mouse_buttons: [bool; 3],
+    /// Only set when the mouse moved.
+    pub mouse_position: Option<Vf2d>,
+    pub wheel: i32,
+    /// Typed text, see `TextInput`.
+    pub text: Vec<TextEvent>,
+    /// Gamepad events polled this frame. The first recorded frame starts
+    /// with the state of the pads that were already connected.
+    pub gamepad: Vec<GamepadEvent>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputRecording {
+    pub frames: Vec<InputFrame>,
+}
+
+impl InputRecording {
+    /// Total time covered by the recording in seconds.
+    pub fn duration(&self) -> f64 {
+        self.frames.iter().map(|f| f.elapsed).sum()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        //Keys are stored once by name so the file doesn't depend on winit's
+        // enum order
+        let mut keys: Vec<Key> = vec![];
+        for frame in &self.frames {
+            for (key, _) in &frame.keys {
+                if !keys.contains(key) {
+                    keys.insert(keys.len(), *key);
+                }
+            }
+        }
+
+        let mut out = vec![];
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&(keys.len() as u16).to_le_bytes());
+        for key in &keys {
+            let name = serde_yaml::to_string(key).unwrap_or_default();
+            let name = name.trim_start_matches("---").trim();
+            out.insert(out.len(), name.len() as u8);
+            out.extend_from_slice(name.as_bytes());
+        }
+
+        out.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        for frame in &self.frames {
+            let mut flags = 0;
+            for (i, down) in frame.mouse_buttons.iter().enumerate() {
+                if *down {
+                    flags |= 1 << i;
+                }
+            }
+            if frame.mouse_position.is_some() {
+                flags |= FLAG_POSITION;
+            }
+            if frame.wheel != 0 {
+                flags |= FLAG_WHEEL;
+            }
+            if !frame.keys.is_empty() {
+                flags |= FLAG_KEYS;
+            }
+            if !frame.text.is_empty() {
+                flags |= FLAG_TEXT;
+            }
+            if !frame.gamepad.is_empty() {
+                flags |= FLAG_GAMEPAD;
+            }
+            out.extend_from_slice(&frame.elapsed.to_le_bytes());
+            out.insert(out.len(), flags);
+            if let Some(pos) = frame.mouse_position {
+                out.extend_from_slice(&pos.x.to_le_bytes());
+                out.extend_from_slice(&pos.y.to_le_bytes());
+            }
+            if frame.wheel != 0 {
+                out.extend_from_slice(&frame.wheel.to_le_bytes());
+            }
+            if !frame.keys.is_empty() {
+                out.extend_from_slice(&(frame.keys.len() as u16).to_le_bytes());
+                for (key, down) in &frame.keys {
+                    let mut index = keys.iter().position(|k| k == key).unwrap() as u16;
+                    if *down {
+                        index |= KEY_DOWN;
+                    }
+                    out.extend_from_slice(&index.to_le_bytes());
+                }
+            }
+            if !frame.text.is_empty() {
+                out.extend_from_slice(&(frame.text.len() as u16).to_le_bytes());
+                for event in &frame.text {
+                    write_text_event(&mut out, event);
+                }
+            }
+            if !frame.gamepad.is_empty() {
+                out.extend_from_slice(&(frame.gamepad.len() as u16).to_le_bytes());
+                for event in &frame.gamepad {
+                    write_gamepad_event(&mut out, event);
+                }
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Rcode> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC || reader.u32()? > VERSION {
+            return Err(Rcode::Fail);
+        }
+        let key_count = reader.u16()?;
+        let mut keys = vec![];
+        for _ in 0..key_count {
+            let len = reader.u8()? as usize;
+            let name = std::str::from_utf8(reader.take(len)?).map_err(|_| Rcode::Fail)?;
+            let key: Key = serde_yaml::from_str(name).map_err(|_| Rcode::Fail)?;
+            keys.insert(keys.len(), key);
+        }
+
+        let frame_count = reader.u32()?;
+        let mut frames = Vec::with_capacity(frame_count as usize);
+        for _ in 0..frame_count {
+            let elapsed = f64::from_le_bytes(reader.take(8)?.try_into().unwrap());
+            let flags = reader.u8()?;
+            let mut frame = InputFrame {
+                elapsed,
+                mouse_buttons: [flags & 1 != 0, flags & 2 != 0, flags & 4 != 0],
+                ..Default::default()
+            };
+            if flags & FLAG_POSITION != 0 {
+                frame.mouse_position = Some(Vf2d::new(reader.f32()?, reader.f32()?));
+            }
+            if flags & FLAG_WHEEL != 0 {
+                frame.wheel = reader.u32()? as i32;
+            }
+            if flags & FLAG_KEYS != 0 {
+                for _ in 0..reader.u16()? {
+                    let index = reader.u16()?;
+                    let key = *keys.get((index & !KEY_DOWN) as usize).ok_or(Rcode::Fail)?;
+                    frame.keys.insert(frame.keys.len(), (key, index & KEY_DOWN != 0));
+                }
+            }
+            if flags & FLAG_TEXT != 0 {
+                for _ in 0..reader.u16()? {
+                    let event = reader.text_event()?;
+                    frame.text.insert(frame.text.len(), event);
+                }
+            }
+            if flags & FLAG_GAMEPAD != 0 {
+                for _ in 0..reader.u16()? {
+                    let event = reader.gamepad_event()?;
+                    frame.gamepad.insert(frame.gamepad.len(), event);
+                }
+            }
+            frames.insert(frames.len(), frame);
+        }
+        Ok(Self { frames })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Rcode> {
+        std::fs::write(path, self.to_bytes()).map_err(|_| Rcode::Fail)
+    }
+
+    pub fn load(path: &str) -> Result<Self, Rcode> {
+        let bytes = std::fs::read(path).map_err(|_| Rcode::NoFile)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_text_event(out: &mut Vec<u8>, event: &TextEvent) {
+    let tag = match event {
+        TextEvent::Char(_) => 0,
+        TextEvent::Backspace => 1,
+        TextEvent::Delete => 2,
+        TextEvent::Left => 3,
+        TextEvent::Right => 4,
+        TextEvent::Home => 5,
+        TextEvent::End => 6,
+        TextEvent::Submit => 7,
+        TextEvent::Cancel => 8,
+        TextEvent::Paste(_) => 9,
+        TextEvent::Preedit { .. } => 10,
+        TextEvent::Commit(_) => 11,
+    };
+    out.insert(out.len(), tag);
+    match event {
+        TextEvent::Char(c) => out.extend_from_slice(&(*c as u32).to_le_bytes()),
+        TextEvent::Paste(s) | TextEvent::Commit(s) => write_string(out, s),
+        TextEvent::Preedit { text, cursor } => {
+            write_string(out, text);
+            out.insert(out.len(), cursor.is_some() as u8);
+            if let Some((start, end)) = cursor {
+                out.extend_from_slice(&(*start as u32).to_le_bytes());
+                out.extend_from_slice(&(*end as u32).to_le_bytes());
+            }
+        }
+        _ => {}
+    }
+}
+
+fn write_gamepad_event(out: &mut Vec<u8>, event: &GamepadEvent) {
+    let (tag, id) = match event {
+        GamepadEvent::Connected(id) => (0, id),
+        GamepadEvent::Disconnected(id) => (1, id),
+        GamepadEvent::Button(id, _, _) => (2, id),
+        GamepadEvent::Axis(id, _, _) => (3, id),
+    };
+    out.insert(out.len(), tag);
+    out.extend_from_slice(&(*id as u32).to_le_bytes());
+    match event {
+        GamepadEvent::Button(_, button, down) => {
+            let index = GAMEPAD_BUTTONS.iter().position(|b| b == button).unwrap_or_default();
+            out.insert(out.len(), index as u8);
+            out.insert(out.len(), *down as u8);
+        }
+        GamepadEvent::Axis(_, axis, value) => {
+            let index = GAMEPAD_AXES.iter().position(|a| a == axis).unwrap_or_default();
+            out.insert(out.len(), index as u8);
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        _ => {}
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Rcode> {
+        let out = self.bytes.get(self.pos..self.pos + len).ok_or(Rcode::Fail)?;
+        self.pos += len;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, Rcode> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Rcode> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, Rcode> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, Rcode> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String, Rcode> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| Rcode::Fail)
+    }
+
+    fn text_event(&mut self) -> Result<TextEvent, Rcode> {
+        Ok(match self.u8()? {
+            0 => TextEvent::Char(char::from_u32(self.u32()?).ok_or(Rcode::Fail)?),
+            1 => TextEvent::Backspace,
+            2 => TextEvent::Delete,
+            3 => TextEvent::Left,
+            4 => TextEvent::Right,
+            5 => TextEvent::Home,
+            6 => TextEvent::End,
+            7 => TextEvent::Submit,
+            8 => TextEvent::Cancel,
+            9 => TextEvent::Paste(self.string()?),
+            10 => {
+                let text = self.string()?;
+                let cursor = if self.u8()? != 0 {
+                    Some((self.u32()? as usize, self.u32()? as usize))
+                } else {
+                    None
+                };
+                TextEvent::Preedit { text, cursor }
+            }
+            11 => TextEvent::Commit(self.string()?),
+            _ => return Err(Rcode::Fail),
+        })
+    }
+
+    fn gamepad_event(&mut self) -> Result<GamepadEvent, Rcode> {
+        let tag = self.u8()?;
+        let id = self.u32()? as usize;
+        Ok(match tag {
+            0 => GamepadEvent::Connected(id),
+            1 => GamepadEvent::Disconnected(id),
+            2 => {
+                let button = *GAMEPAD_BUTTONS.get(self.u8()? as usize).ok_or(Rcode::Fail)?;
+                GamepadEvent::Button(id, button, self.u8()? != 0)
+            }
+            3 => {
+                let axis = *GAMEPAD_AXES.get(self.u8()? as usize).ok_or(Rcode::Fail)?;
+                GamepadEvent::Axis(id, axis, self.f32()?)
+            }
+            _ => return Err(Rcode::Fail),
+        })
+    }
+}
+
+enum ReplayState {
+    Idle,
+    Recording {
+        recording: InputRecording,
+        keys: HashMap<Key, bool>,
+        mouse_position: Option<Vf2d>,
+    },
+    Playing {
+        recording: InputRecording,
+        frame: usize,
+        keys: HashMap<Key, bool>,
+        mouse_position: Option<Vf2d>,
+    },
+}
+
+impl Default for ReplayState {
+    fn default() -> Self {
+        ReplayState::Idle
+    }
+}
+
+/// Records the raw input of every frame, or plays a recording back in place
+/// of the real input. Replayed frames also use the recorded frame times, so
+/// a session replays identically.
+///
+/// Input held when a replay starts or stops is released, and gamepads that
+/// were connected are set aside while it plays.
+#[derive(Default)]
+pub struct Replay {
+    state: ReplayState,
+    /// Close the game once a replay ends, for running replays as tests.
+    pub exit_when_done: bool,
+    finished: bool,
+    //A replay stopped, real input takes over on the next apply
+    restore_input: bool,
+}
+
+impl Replay {
+    pub fn start_recording(&mut self) {
+        self.state = ReplayState::Recording {
+            recording: InputRecording::default(),
+            keys: HashMap::new(),
+            mouse_position: None,
+        };
+    }
+
+    /// Stops recording and returns what was recorded.
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        match std::mem::take(&mut self.state) {
+            ReplayState::Recording { recording, .. } => Some(recording),
+            state => {
+                self.state = state;
+                None
+            }
+        }
+    }
+
+    pub fn start_replay(&mut self, recording: InputRecording) {
+        self.finished = false;
+        //Restarting puts the real pads back before setting them aside again
+        self.restore_input |= self.is_replaying();
+        self.state = ReplayState::Playing {
+            recording,
+            frame: 0,
+            keys: HashMap::new(),
+            mouse_position: None,
+        };
+    }
+
+    pub fn stop_replay(&mut self) {
+        if self.is_replaying() {
+            self.state = ReplayState::Idle;
+            self.restore_input = true;
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state, ReplayState::Recording { .. })
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        matches!(self.state, ReplayState::Playing { .. })
+    }
+
+    /// The last replay played every frame.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Called before input is processed, with the gamepad events polled this
+    /// frame. While replaying this overwrites the raw input in `input`,
+    /// `gamepad_events` and `elapsed` with the next recorded frame.
+    pub fn apply(
+        &mut self,
+        input: &mut InputState,
+        gamepads: &mut Gamepads,
+        gamepad_events: &mut Vec<GamepadEvent>,
+        elapsed: &mut f64,
+    ) {
+        if std::mem::take(&mut self.restore_input) {
+            release_all(input);
+            gamepads.resume();
+        }
+        let done = match &mut self.state {
+            ReplayState::Playing {
+                recording,
+                frame,
+                keys,
+                mouse_position,
+            } => match recording.frames.get(*frame) {
+                Some(recorded) => {
+                    if *frame == 0 {
+                        //Release keys held before the replay, the recording
+                        // only has the keys it pressed
+                        for key in input.old_key_state_map.keys().chain(input.new_key_state_map.keys()) {
+                            keys.insert(*key, false);
+                        }
+                        gamepads.suspend();
+                    }
+                    for (key, down) in &recorded.keys {
+                        keys.insert(*key, *down);
+                    }
+                    if recorded.mouse_position.is_some() {
+                        *mouse_position = recorded.mouse_position;
+                    }
+                    //Real input that arrived since the last frame is dropped
+                    input.new_key_state_map = keys.clone();
+                    for (i, down) in recorded.mouse_buttons.iter().enumerate() {
+                        input.new_mouse_state_map[i] = *down;
+                    }
+                    input.mouse_position_cache = *mouse_position;
+                    input.mouse_wheel_delta_cache = recorded.wheel;
+                    input.text_events = recorded.text.clone();
+                    *gamepad_events = recorded.gamepad.clone();
+                    *elapsed = recorded.elapsed;
+                    *frame += 1;
+                    false
+                }
+                None => true,
+            },
+            _ => false,
+        };
+        if done {
+            self.state = ReplayState::Idle;
+            self.finished = true;
+            release_all(input);
+            gamepads.resume();
+        }
+    }
+
+    /// Called after `apply`, before input is processed. While recording this
+    /// stores the raw input in `input`, the gamepad events and the frame time.
+    pub fn record(
+        &mut self,
+        input: &InputState,
+        gamepads: &Gamepads,
+        gamepad_events: &[GamepadEvent],
+        elapsed: f64,
+    ) {
+        if let ReplayState::Recording {
+            recording,
+            keys,
+            mouse_position,
+        } = &mut self.state
+        {
+            let mut frame = InputFrame {
+                elapsed,
+                wheel: input.mouse_wheel_delta_cache,
+                text: input.text_events.clone(),
+                ..Default::default()
+            };
+            if recording.frames.is_empty() {
+                frame.gamepad = gamepads.state_events();
+            }
+            frame.gamepad.extend_from_slice(gamepad_events);
+            for (key, down) in &input.new_key_state_map {
+                if keys.get(key).copied().unwrap_or(false) != *down {
+                    keys.insert(*key, *down);
+                    frame.keys.insert(frame.keys.len(), (*key, *down));
+                }
+            }
+            for (i, down) in input.new_mouse_state_map.iter().take(3).enumerate() {
+                frame.mouse_buttons[i] = *down;
+            }
+            if input.mouse_position_cache != *mouse_position {
+                *mouse_position = input.mouse_position_cache;
+                frame.mouse_position = input.mouse_position_cache;
+            }
+            recording.frames.insert(recording.frames.len(), frame);
+        }
+    }
+}
+
+//Lets go of every key and mouse button, real input that is still held
+// reads as pressed again once it changes
+fn release_all(input: &mut InputState) {
+    for down in input.new_key_state_map.values_mut() {
+        *down = false;
+    }
+    for down in input.new_mouse_state_map.iter_mut() {
+        *down = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamepad::SyntheticBackend;
+    use crate::util::HWButton;
+
+    #[derive(Debug, PartialEq)]
+    struct Observed {
+        elapsed: f64,
+        keys: [HWButton; 2],
+        mouse: HWButton,
+        mouse_position: Vf2d,
+        wheel: i32,
+        text: Vec<TextEvent>,
+        pads: Vec<usize>,
+        south: HWButton,
+        stick: f32,
+    }
+
+    //Runs the input steps of update_inputs and reads back what a game sees
+    fn step(replay: &mut Replay, input: &mut InputState, pads: &mut Gamepads, mut elapsed: f64) -> Observed {
+        let mut pad_events = pads.poll();
+        replay.apply(input, pads, &mut pad_events, &mut elapsed);
+        replay.record(input, pads, &pad_events, elapsed);
+        let text = std::mem::take(&mut input.text_events);
+        input.update();
+        pads.update_with(pad_events);
+        let id = pads.connected().first().copied().unwrap_or_default();
+        Observed {
+            elapsed,
+            keys: [input.get_key(Key::A), input.get_key(Key::B)],
+            mouse: input.get_mouse(0),
+            mouse_position: input.mouse_position,
+            wheel: input.mouse_wheel_delta,
+            text,
+            pads: pads.connected(),
+            south: pads.get_button(id, GamepadButton::South),
+            stick: pads.get_axis(id, GamepadAxis::LeftStickX),
+        }
+    }
+
+    #[test]
+    fn playback_matches_recording() {
+        let backend = SyntheticBackend::new();
+        let mut pads = Gamepads::new(Box::new(backend.clone()));
+        let mut input = InputState::new();
+        let mut replay = Replay::default();
+        replay.start_recording();
+        let mut recorded = vec![];
+        for i in 0..5 {
+            match i {
+                0 => {
+                    input.new_key_state_map.insert(Key::A, true);
+                    input.mouse_position_cache = Some(Vf2d::new(10.0, 20.0));
+                    let id = backend.connect("Pad");
+                    backend.set_button(id, GamepadButton::South, true);
+                }
+                1 => {
+                    input.new_mouse_state_map[0] = true;
+                    input.mouse_wheel_delta_cache = 1;
+                    input.text_events.insert(0, TextEvent::Commit("hi".to_string()));
+                    backend.set_axis(0, GamepadAxis::LeftStickX, 0.5);
+                }
+                2 => {
+                    input.new_key_state_map.insert(Key::A, false);
+                    input.new_key_state_map.insert(Key::B, true);
+                    input.mouse_position_cache = Some(Vf2d::new(30.0, 40.0));
+                    backend.set_button(0, GamepadButton::South, false);
+                }
+                3 => {
+                    input.new_mouse_state_map[0] = false;
+                    input.new_key_state_map.insert(Key::B, false);
+                }
+                _ => {}
+            }
+            let observed = step(&mut replay, &mut input, &mut pads, 0.016 + i as f64 * 0.001);
+            recorded.insert(recorded.len(), observed);
+        }
+        assert!(recorded[0].south.pressed && recorded[1].stick > 0.0);
+        let recording = replay.stop_recording().unwrap();
+        let loaded = InputRecording::from_bytes(&recording.to_bytes()).unwrap();
+        assert_eq!(loaded, recording);
+
+        //Input held before the replay must be released, not leak into it
+        let real = SyntheticBackend::new();
+        let mut playback_pads = Gamepads::new(Box::new(real.clone()));
+        real.connect("Real pad");
+        let held = real.connect("Held pad");
+        real.set_button(held, GamepadButton::South, true);
+        let mut playback_input = InputState::new();
+        playback_input.new_key_state_map.insert(Key::Space, true);
+        step(&mut replay, &mut playback_input, &mut playback_pads, 0.0);
+        assert!(playback_input.get_key(Key::Space).held);
+
+        replay.start_replay(loaded);
+        for (i, expected) in recorded.iter().enumerate() {
+            let observed = step(&mut replay, &mut playback_input, &mut playback_pads, 1.0);
+            assert_eq!(&observed, expected, "frame {}", i);
+            if i == 0 {
+                assert!(playback_input.get_key(Key::Space).released);
+            }
+        }
+        assert!(!replay.finished());
+        step(&mut replay, &mut playback_input, &mut playback_pads, 1.0);
+        assert!(replay.finished());
+        assert_eq!(playback_pads.connected(), vec![0, held]);
+        assert!(!playback_pads.get_button(held, GamepadButton::South).held);
+    }
+}
@@ -0,0 +1,50 @@
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coord: vec3<f32>,
+    @location(3) color: vec4<f32>,
+};
+
+struct VertexOutput{
+    @builtin(position) pos: vec4<f32>,
+    @location(0) tex_coord: vec3<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var r_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var r_sampler: sampler;
+
+struct LayerUniforms{
+    offset: vec2<f32>,
+    scale: vec2<f32>,
+    tint: vec4<f32>,
+    opacity: f32,
+};
+
+// Decals take the offset, scale, tint and opacity of their layer
+@group(1) @binding(0)
+var<uniform> layer: LayerUniforms;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput
+{
+    var v_out: VertexOutput;
+    // The layer shows its texture at uv * scale + offset, so what is drawn
+    // at uv lands at (uv - offset) / scale on screen
+    let uv = vec2<f32>(in.position.x * 0.5 + 0.5, 0.5 - in.position.y * 0.5);
+    let screen = (uv - layer.offset) / layer.scale;
+    v_out.pos = vec4<f32>(screen.x * 2.0 - 1.0, 1.0 - screen.y * 2.0, 0.0, 1.0);
+    v_out.tex_coord = in.tex_coord;
+    v_out.color = in.color;
+    return v_out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>{
+    // Warped decals scale uv and w together, dividing here after
+    // interpolation keeps them perspective correct
+    let uv = in.tex_coord.xy / in.tex_coord.z;
+    let color = textureSample(r_texture, r_sampler, uv) * in.color * layer.tint;
+    return vec4<f32>(color.rgb, color.a * layer.opacity);
+}
@@ -3,11 +3,21 @@ struct VertexOutput{
     @location(0) tex_coord: vec2<f32>,
 };
 
+struct LayerUniforms{
+    offset: vec2<f32>,
+    scale: vec2<f32>,
+    tint: vec4<f32>,
+    opacity: f32,
+};
+
 @group(0) @binding(0)
 var r_texture: texture_2d<f32>;
 @group(0) @binding(1)
 var r_sampler: sampler;
 
+@group(1) @binding(0)
+var<uniform> layer: LayerUniforms;
+
 @vertex
 fn vs_main(
            @location(0) in_position: vec3<f32>,
@@ -16,11 +26,12 @@ fn vs_main(
 {
     var v_out: VertexOutput;
     v_out.pos = vec4<f32>(in_position, 1.0);
-    v_out.tex_coord = in_tex_coord;
+    v_out.tex_coord = in_tex_coord * layer.scale + layer.offset;
     return v_out;
 }
 
 @fragment
 fn fs_main( in: VertexOutput) -> @location(0) vec4<f32>{
-    return textureSample(r_texture, r_sampler, in.tex_coord);
+    let color = textureSample(r_texture, r_sampler, in.tex_coord) * layer.tint;
+    return vec4<f32>(color.rgb, color.a * layer.opacity);
 }
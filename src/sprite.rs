@@ -157,4 +157,5 @@ pub enum SpriteFlip {
     None,
     Horiz,
     Vert,
+    Both,
 }
@@ -1,14 +1,12 @@
+use super::audio::{EmitterId, SpatialParams, Spatializer};
 use super::steam_audio_bindgen::*;
-use olc_pge_macros::*;
-use std::ptr::slice_from_raw_parts;
-use std::sync::atomic::AtomicPtr;
-use std::sync::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::mem::MaybeUninit;
 
 pub type Context = IPLContext;
 pub type AudioSettings = IPLAudioSettings;
 pub type DefaultSampleFormat = f32;
 pub const SAMPLESIZE: usize = std::mem::size_of::<DefaultSampleFormat>();
-pub const PTRWIDTH: usize = std::mem::size_of::<usize>();
 type Hrtf = IPLHRTF;
 
 const IPL_CONTEXT_SETTINGS: IPLContextSettings = IPLContextSettings {
@@ -27,14 +25,11 @@ pub struct AudioProcessor {
     hrtf: Hrtf,
 }
 
+/// Deinterleaved audio of one frame, the layout Steam Audio works on.
 pub struct Buffer {
-    frame_size: i32,
-    sample_rate: i32,
-    channels: i32,
-    frame_position: i32,
-
-    cursor: std::io::Cursor<Vec<DefaultSampleFormat>>,
-    data_ptr: AtomicPtr<DefaultSampleFormat>,
+    data: Vec<Vec<DefaultSampleFormat>>,
+    //Points at each channel in `data`, `ipl.data` points here
+    pointers: Vec<*mut DefaultSampleFormat>,
     ipl: IPLAudioBuffer,
 }
 
@@ -99,41 +94,6 @@ pub enum ReflectionEffectType {}
 pub enum EffectParameters {}
 pub struct SpeakerLayout;
 
-#[ipl]
-#[derive(Effect)]
-pub struct AmbisonicsBinaural {}
-#[ipl]
-#[derive(Effect)]
-pub struct AmbisonicsEncode {}
-#[ipl]
-#[derive(Effect)]
-pub struct AmbisonicsDecode {}
-#[ipl]
-#[derive(Effect)]
-pub struct AmbisonicsPanning {}
-#[ipl]
-#[derive(Effect)]
-pub struct AmbisonicsRotation {}
-#[ipl]
-#[derive(Effect)]
-pub struct Binaural {}
-#[ipl]
-#[derive(Effect)]
-pub struct Direct {}
-#[ipl]
-#[derive(Effect)]
-pub struct Panning {}
-#[ipl]
-#[derive(Effect)]
-pub struct Path {}
-#[ipl]
-#[derive(Effect)]
-pub struct VirtualSurround {}
-
-//These take 5 parameters, so we'll manually implement this
-#[ipl]
-pub struct Reflection {}
-
 pub trait Effect {
     type Settings;
     type Params;
@@ -143,6 +103,166 @@ pub trait Effect {
     fn reset(&mut self);
 }
 
+//Effects whose apply takes the effect, params and two buffers
+macro_rules! ipl_effect {
+    ($name:ident, $effect:ty, $settings:ty, $params:ty, $create:ident, $apply:ident, $reset:ident, $release:ident) => {
+        pub struct $name {
+            settings: $settings,
+            effect: $effect,
+        }
+
+        impl Effect for $name {
+            type Settings = $settings;
+            type Params = $params;
+            fn create(audio_processor: &mut AudioProcessor, mut settings: Self::Settings) -> Self {
+                let effect = unsafe {
+                    let mut effect = MaybeUninit::uninit();
+                    assert_eq!(
+                        IPLerror::IPL_STATUS_SUCCESS,
+                        $create(
+                            audio_processor.context,
+                            &mut audio_processor.audio_settings,
+                            &mut settings,
+                            effect.as_mut_ptr(),
+                        )
+                    );
+                    effect.assume_init()
+                };
+                Self { settings, effect }
+            }
+
+            fn apply(&self, mut params: Self::Params, in_buffer: &mut Buffer, out_buffer: &mut Buffer) -> IPLAudioEffectState {
+                unsafe { $apply(self.effect, &mut params, in_buffer.ipl(), out_buffer.ipl()) }
+            }
+
+            fn reset(&mut self) {
+                unsafe {
+                    $reset(self.effect);
+                }
+            }
+
+            fn drop_effect(&mut self) {
+                unsafe {
+                    $release(&mut self.effect);
+                }
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.drop_effect();
+            }
+        }
+    };
+}
+
+ipl_effect!(
+    AmbisonicsBinaural,
+    IPLAmbisonicsBinauralEffect,
+    IPLAmbisonicsBinauralEffectSettings,
+    IPLAmbisonicsBinauralEffectParams,
+    iplAmbisonicsBinauralEffectCreate,
+    iplAmbisonicsBinauralEffectApply,
+    iplAmbisonicsBinauralEffectReset,
+    iplAmbisonicsBinauralEffectRelease
+);
+ipl_effect!(
+    AmbisonicsEncode,
+    IPLAmbisonicsEncodeEffect,
+    IPLAmbisonicsEncodeEffectSettings,
+    IPLAmbisonicsEncodeEffectParams,
+    iplAmbisonicsEncodeEffectCreate,
+    iplAmbisonicsEncodeEffectApply,
+    iplAmbisonicsEncodeEffectReset,
+    iplAmbisonicsEncodeEffectRelease
+);
+ipl_effect!(
+    AmbisonicsDecode,
+    IPLAmbisonicsDecodeEffect,
+    IPLAmbisonicsDecodeEffectSettings,
+    IPLAmbisonicsDecodeEffectParams,
+    iplAmbisonicsDecodeEffectCreate,
+    iplAmbisonicsDecodeEffectApply,
+    iplAmbisonicsDecodeEffectReset,
+    iplAmbisonicsDecodeEffectRelease
+);
+ipl_effect!(
+    AmbisonicsPanning,
+    IPLAmbisonicsPanningEffect,
+    IPLAmbisonicsPanningEffectSettings,
+    IPLAmbisonicsPanningEffectParams,
+    iplAmbisonicsPanningEffectCreate,
+    iplAmbisonicsPanningEffectApply,
+    iplAmbisonicsPanningEffectReset,
+    iplAmbisonicsPanningEffectRelease
+);
+ipl_effect!(
+    AmbisonicsRotation,
+    IPLAmbisonicsRotationEffect,
+    IPLAmbisonicsRotationEffectSettings,
+    IPLAmbisonicsRotationEffectParams,
+    iplAmbisonicsRotationEffectCreate,
+    iplAmbisonicsRotationEffectApply,
+    iplAmbisonicsRotationEffectReset,
+    iplAmbisonicsRotationEffectRelease
+);
+ipl_effect!(
+    Binaural,
+    IPLBinauralEffect,
+    IPLBinauralEffectSettings,
+    IPLBinauralEffectParams,
+    iplBinauralEffectCreate,
+    iplBinauralEffectApply,
+    iplBinauralEffectReset,
+    iplBinauralEffectRelease
+);
+ipl_effect!(
+    Direct,
+    IPLDirectEffect,
+    IPLDirectEffectSettings,
+    IPLDirectEffectParams,
+    iplDirectEffectCreate,
+    iplDirectEffectApply,
+    iplDirectEffectReset,
+    iplDirectEffectRelease
+);
+ipl_effect!(
+    Panning,
+    IPLPanningEffect,
+    IPLPanningEffectSettings,
+    IPLPanningEffectParams,
+    iplPanningEffectCreate,
+    iplPanningEffectApply,
+    iplPanningEffectReset,
+    iplPanningEffectRelease
+);
+ipl_effect!(
+    Path,
+    IPLPathEffect,
+    IPLPathEffectSettings,
+    IPLPathEffectParams,
+    iplPathEffectCreate,
+    iplPathEffectApply,
+    iplPathEffectReset,
+    iplPathEffectRelease
+);
+ipl_effect!(
+    VirtualSurround,
+    IPLVirtualSurroundEffect,
+    IPLVirtualSurroundEffectSettings,
+    IPLVirtualSurroundEffectParams,
+    iplVirtualSurroundEffectCreate,
+    iplVirtualSurroundEffectApply,
+    iplVirtualSurroundEffectReset,
+    iplVirtualSurroundEffectRelease
+);
+
+//Reflection also takes a mixer, so it's implemented by hand
+pub struct Reflection {
+    settings: IPLReflectionEffectSettings,
+    effect: IPLReflectionEffect,
+}
+
 impl AudioProcessor {
     pub fn new(sample_rate: i32, frame_size: i32) -> Self {
         AudioProcessor::create_context(sample_rate, frame_size)
@@ -157,10 +277,10 @@ impl AudioProcessor {
 
     pub fn create_context(sample_rate: i32, frame_size: i32) -> Self {
         let context = unsafe {
-            let mut ipl_context = std::mem::MaybeUninit::uninit();
+            let mut ipl_context = MaybeUninit::uninit();
             assert_eq!(
                 IPLerror::IPL_STATUS_SUCCESS,
-                iplContextCreate(&IPL_CONTEXT_SETTINGS, ipl_context.as_mut_ptr())
+                iplContextCreate(&mut { IPL_CONTEXT_SETTINGS }, ipl_context.as_mut_ptr())
             );
             ipl_context.assume_init()
         };
@@ -171,7 +291,7 @@ impl AudioProcessor {
         };
 
         let hrtf = unsafe {
-            let mut hrtf = std::mem::MaybeUninit::uninit();
+            let mut hrtf = MaybeUninit::uninit();
             assert_eq!(
                 IPLerror::IPL_STATUS_SUCCESS,
                 iplHRTFCreate(
@@ -201,252 +321,77 @@ impl AudioProcessor {
 
 impl Buffer {
     pub fn empty(audio_processor: &AudioProcessor, num_channels: i32) -> Self {
-        let ipl = unsafe {
-            let mut buffer = std::mem::MaybeUninit::uninit();
-            iplAudioBufferAllocate(
-                audio_processor.context(),
-                num_channels,
-                audio_processor.audio_settings.frameSize,
-                buffer.as_mut_ptr(),
-            );
-            buffer.assume_init()
-        };
-        let mut empty_buf = vec![];
-        Self {
-            cursor: std::io::Cursor::new(empty_buf),
-            ipl,
-            channels: num_channels,
-            sample_rate: audio_processor.audio_settings.samplingRate,
-            frame_size: audio_processor.audio_settings.frameSize,
-            frame_position: 0,
-            data_ptr: AtomicPtr::new(std::ptr::null_mut()),
-        }
+        Self::new(num_channels as usize, audio_processor.audio_settings.frameSize as usize)
     }
 
+    /// `data` holds the channels one after the other.
     pub fn new_with_data(
         audio_processor: &AudioProcessor,
         num_channels: i32,
         data: Vec<DefaultSampleFormat>,
     ) -> Self {
-        let mut buf = Self {
-            cursor: std::io::Cursor::new(data),
-            ipl: IPLAudioBuffer {
-                numChannels: num_channels,
-                numSamples: audio_processor.audio_settings.frameSize,
-                data: AtomicPtr::new(std::ptr::null_mut()),
-            },
-            channels: num_channels,
-            sample_rate: audio_processor.audio_settings.samplingRate,
-            frame_size: audio_processor.audio_settings.frameSize,
-            data_ptr: AtomicPtr::new(std::ptr::null_mut()),
-            frame_position: 0,
-        };
-        buf.update_ipl_data();
-        buf
-    }
-
-    #[inline]
-    pub fn advance_frame(&mut self) {
-        self.advance_cursor(self.frame_size as u64);
-        self.frame_position = 0;
-        self.update_ipl_data();
-    }
-
-    #[inline]
-    pub fn sample(&mut self) -> Option<DefaultSampleFormat> {
-        if self.cursor.get_ref().is_empty() {
-            self.try_read_value_from_ipl()
-        } else {
-            let mut sample_buffer = [0_u8; SAMPLESIZE];
-            if let Ok(bytes) = self.read(&mut sample_buffer) {
-                if bytes == SAMPLESIZE {
-                    Some(bytemuck::cast(sample_buffer))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+        let mut buf = Self::empty(audio_processor, num_channels);
+        let frame_size = buf.frame_size();
+        for (channel, samples) in buf.data.iter_mut().zip(data.chunks(frame_size)) {
+            channel[..samples.len()].copy_from_slice(samples);
         }
+        buf
     }
 
-    #[inline]
-    fn advance_cursor(&mut self, adv: u64) {
-        let p: u64 = self.cursor.position() as u64;
-        self.cursor.set_position(p + adv);
-    }
-
-    #[inline]
-    fn update_ipl_data(&mut self) {
-        self.update_data_pointer();
-        self.ipl.data = AtomicPtr::new(&mut self.data_ptr);
-    }
-
-    #[inline]
-    fn update_data_pointer(&mut self) {
-        let start_data = self.cursor.get_mut().as_mut_ptr();
-        let pos = self.cursor.position() as usize * SAMPLESIZE;
-        self.data_ptr =
-            AtomicPtr::new(unsafe { (start_data as usize + pos) as *mut DefaultSampleFormat });
+    pub fn new(channels: usize, frame_size: usize) -> Self {
+        let mut data = vec![vec![0.0; frame_size]; channels.max(1)];
+        let mut pointers: Vec<*mut DefaultSampleFormat> = data.iter_mut().map(|c| c.as_mut_ptr()).collect();
+        let ipl = IPLAudioBuffer {
+            numChannels: data.len() as i32,
+            numSamples: frame_size as i32,
+            data: pointers.as_mut_ptr(),
+        };
+        Self { data, pointers, ipl }
     }
 
-    #[inline]
-    pub fn get_data_pointer(&mut self) -> *mut DefaultSampleFormat {
-        *self.data_ptr.get_mut()
+    pub fn channels(&self) -> usize {
+        self.data.len()
     }
 
-    #[inline]
-    pub fn try_read_value_from_ipl(&mut self) -> Option<DefaultSampleFormat> {
-        self.update_ipl_data();
-        let start_data = unsafe { (*(*self.ipl.data.get_mut())).get_mut() };
-        let pos = self.cursor.position() + self.frame_position as u64;
-        let data_ptr =
-            unsafe { (*start_data as usize + (pos as usize)) as *mut DefaultSampleFormat };
-        if !data_ptr.is_null() {
-            let v = unsafe { *data_ptr };
-            if (-1.0..=1.0).contains(&v) {
-                Some(v)
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+    pub fn frame_size(&self) -> usize {
+        self.ipl.numSamples as usize
     }
 
-    #[inline]
-    fn bytes_left(&self) -> i32 {
-        (self.cursor.get_ref().len() as i32
-            - (self.cursor.position() + self.frame_position as u64) as i32)
-            * SAMPLESIZE as i32
+    pub fn channel_samples(&self, channel: usize) -> &[DefaultSampleFormat] {
+        &self.data[channel]
     }
 
-    #[inline]
-    fn advance_frame_position(&mut self, pos: i32) {
-        self.frame_position += 1;
-        if self.frame_position > self.frame_size {
-            self.advance_frame();
-            self.frame_position = 0;
-        }
+    pub fn channel_samples_mut(&mut self, channel: usize) -> &mut [DefaultSampleFormat] {
+        &mut self.data[channel]
     }
 
-    pub fn clear_ipl(&mut self){
-
+    /// Every channel, one after the other.
+    pub fn all_samples(&self) -> Vec<DefaultSampleFormat> {
+        self.data.concat()
     }
 
-    pub fn all_samples(&mut self) -> Vec<DefaultSampleFormat> {
-        let vec = self.cursor.get_ref();
-        if !vec.is_empty() {
-            vec.clone()
-        } else {
-            let mut vec = Vec::with_capacity((self.channels * self.frame_size) as usize);
-            for c in 0..self.channels as usize {
-                let d_ptr = self.ipl.data.load(std::sync::atomic::Ordering::Relaxed);
-                let mut channel_ptr: *mut DefaultSampleFormat = unsafe {
-                    let d_ptr = d_ptr as *mut *mut f32;
-                    *((d_ptr as usize + (c * PTRWIDTH))
-                        as *mut *mut DefaultSampleFormat)
-                };
-                unsafe {
-                    let mut c_v = (*slice_from_raw_parts(
-                        channel_ptr,
-                        self.frame_size as usize,
-                    )).to_vec();
-                    vec.append(&mut c_v);
-                }
-            }
-            vec
+    pub fn clear(&mut self) {
+        for channel in self.data.iter_mut() {
+            channel.iter_mut().for_each(|s| *s = 0.0);
         }
     }
 
-    pub fn channel_samples(&mut self, channel: usize) -> Vec<DefaultSampleFormat> {
-        let vec = self.cursor.get_ref();
-        if !vec.is_empty() {
-            vec.clone()
-        } else {
-            let d_ptr = self.ipl.data.load(std::sync::atomic::Ordering::Relaxed);
-            let mut channel_ptr: *mut DefaultSampleFormat = unsafe {
-                let d_ptr = d_ptr as *mut *mut f32;
-                *((d_ptr as usize + (channel * SAMPLESIZE))
-                  as *mut *mut DefaultSampleFormat)
-            };
-            unsafe {
-                (*slice_from_raw_parts(
-                    channel_ptr,
-                    self.frame_size as usize,
-                )).to_vec()
-            }
-        }
-    }
-}
-
-impl std::io::Read for Buffer {
-    #[inline]
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let bytes_left = self.bytes_left();
-        let buf_len = buf.len();
-        let n: i32 = bytes_left - buf_len as i32;
-        let p: usize = self.cursor.position() as usize;
-        let c: usize = (n as usize).min(buf_len) / SAMPLESIZE;
-        let o: usize = (p + c);
-        if n > SAMPLESIZE as i32 {
-            bytemuck::cast_slice::<DefaultSampleFormat, u8>(
-                &self.cursor.get_ref().as_slice()[p..o],
-            )
-            .iter()
-            .enumerate()
-            .for_each(|(i, b)| {
-                buf[i] = *b;
-            });
-            self.advance_frame_position(c as i32);
-            Ok(c * SAMPLESIZE)
-        } else {
-            Ok(0)
-        }
-    }
-}
-
-impl std::io::Write for Buffer {
-    #[inline]
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.cursor
-            .get_mut()
-            .extend_from_slice(bytemuck::cast_slice(buf));
-        Ok(buf.len())
-    }
-
-    fn flush(&mut self) -> std::io::Result<()> {
-        unimplemented!()
-    }
-}
-
-use std::io::Read;
-impl std::iter::Iterator for Buffer {
-    type Item = DefaultSampleFormat;
-
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        self.advance_frame_position(1);
-        self.sample()
+    //The vectors only move on the heap if resized, which never happens, so
+    //the pointers stay valid when the Buffer moves
+    fn ipl(&mut self) -> *mut IPLAudioBuffer {
+        self.ipl.data = self.pointers.as_mut_ptr();
+        &mut self.ipl
     }
 }
 
 pub mod utils {
     #[inline]
-    pub fn interleave(
-        audio_processor: &super::AudioProcessor,
-        mut output_audio_frame: Vec<f32>,
-        in_buffer: &mut super::Buffer,
-    ) -> Vec<f32> {
+    pub fn interleave(audio_processor: &super::AudioProcessor, in_buffer: &mut super::Buffer) -> Vec<f32> {
+        let mut out = vec![0.0; in_buffer.channels() * in_buffer.frame_size()];
         unsafe {
-            let (frame_ptr, frame_len, frame_capacity) = output_audio_frame.into_raw_parts();
-            super::iplAudioBufferInterleave(
-                audio_processor.context(),
-                &mut in_buffer.ipl,
-                frame_ptr,
-            );
-            (*std::slice::from_raw_parts(frame_ptr, frame_capacity)).to_vec()
+            super::iplAudioBufferInterleave(audio_processor.context(), in_buffer.ipl(), out.as_mut_ptr());
         }
+        out
     }
 }
 
@@ -455,7 +400,7 @@ impl Effect for Reflection {
     type Params = IPLReflectionEffectParams;
     fn create(audio_processor: &mut AudioProcessor, mut settings: Self::Settings) -> Self {
         let effect = unsafe {
-            let mut effect = std::mem::MaybeUninit::uninit();
+            let mut effect = MaybeUninit::uninit();
             assert_eq!(
                 IPLerror::IPL_STATUS_SUCCESS,
                 iplReflectionEffectCreate(
@@ -472,15 +417,13 @@ impl Effect for Reflection {
 
     fn apply(&self, mut params: Self::Params, in_buffer: &mut Buffer, out_buffer: &mut Buffer) -> IPLAudioEffectState {
         unsafe {
-            let state = iplReflectionEffectApply(
+            iplReflectionEffectApply(
                 self.effect,
                 &mut params,
-                &mut in_buffer.ipl,
-                &mut out_buffer.ipl,
+                in_buffer.ipl(),
+                out_buffer.ipl(),
                 std::ptr::null_mut(),
-            );
-            out_buffer.data_ptr = AtomicPtr::new(*(*(*out_buffer.ipl.data.get_mut())).get_mut());
-            state
+            )
         }
     }
 
@@ -506,8 +449,8 @@ impl Drop for Reflection {
 impl Drop for AudioProcessor {
     fn drop(&mut self) {
         unsafe {
-            iplContextRelease(&mut self.context);
             iplHRTFRelease(&mut self.hrtf);
+            iplContextRelease(&mut self.context);
         }
     }
 }
@@ -520,3 +463,261 @@ impl From<crate::math_3d::Vector3> for IPLVector3 {
         }
     }
 }
+
+/// How `SteamAudioSpatializer` renders emitters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpatialMode {
+    /// An HRTF per emitter.
+    Binaural,
+    /// Encoded to ambisonics of an order, 1 to 3, and decoded with the HRTF.
+    Ambisonics(i32),
+}
+
+//Effects and queued audio of one emitter. Steam Audio works on whole frames
+//so audio is queued until one is full, which delays it by a frame
+struct EmitterEffects {
+    binaural: Option<Binaural>,
+    encode: Option<AmbisonicsEncode>,
+    decode: Option<AmbisonicsDecode>,
+    reflection: Option<(Reflection, AmbisonicsDecode)>,
+    input: VecDeque<f32>,
+    output: VecDeque<f32>,
+}
+
+/// HRTF and reverb through Steam Audio, set with
+/// `AudioSystem::set_spatializer`. Needs the `steam-audio` feature.
+pub struct SteamAudioSpatializer {
+    processor: AudioProcessor,
+    pub mode: SpatialMode,
+    /// Parametric reverb times in seconds for low, mid and high frequencies,
+    /// none for no reflections.
+    pub reverb: Option<[f32; 3]>,
+    pub reverb_gain: f32,
+    emitters: HashMap<EmitterId, EmitterEffects>,
+    mono: Buffer,
+    ambisonics: Buffer,
+    //Reflections are always at REFLECTION_ORDER, whatever the render order
+    reflection_ambisonics: Buffer,
+    stereo: Buffer,
+    reverb_stereo: Buffer,
+}
+
+//Order of the ambisonics reflections are rendered to
+const REFLECTION_ORDER: i32 = 1;
+
+fn ambisonic_channels(order: i32) -> usize {
+    ((order + 1) * (order + 1)) as usize
+}
+
+//Steam Audio looks down -z, the mixer down +z
+fn ipl_direction(params: &SpatialParams) -> IPLVector3 {
+    IPLVector3 {
+        x: params.direction.x,
+        y: params.direction.y,
+        z: -params.direction.z,
+    }
+}
+
+fn listener_space() -> IPLCoordinateSpace3 {
+    IPLCoordinateSpace3 {
+        right: IPLVector3 { x: 1.0, y: 0.0, z: 0.0 },
+        up: IPLVector3 { x: 0.0, y: 1.0, z: 0.0 },
+        ahead: IPLVector3 { x: 0.0, y: 0.0, z: -1.0 },
+        origin: IPLVector3 { x: 0.0, y: 0.0, z: 0.0 },
+    }
+}
+
+impl SteamAudioSpatializer {
+    pub fn new(sample_rate: u32, mode: SpatialMode) -> Self {
+        let frame_size = crate::audio::FRAME_SIZE;
+        let processor = AudioProcessor::new(sample_rate as i32, frame_size as i32);
+        let order = match mode {
+            SpatialMode::Ambisonics(order) => order.clamp(1, 3),
+            SpatialMode::Binaural => REFLECTION_ORDER,
+        };
+        Self {
+            processor,
+            mode,
+            reverb: None,
+            reverb_gain: 0.3,
+            emitters: HashMap::new(),
+            mono: Buffer::new(1, frame_size),
+            ambisonics: Buffer::new(ambisonic_channels(order), frame_size),
+            reflection_ambisonics: Buffer::new(ambisonic_channels(REFLECTION_ORDER), frame_size),
+            stereo: Buffer::new(2, frame_size),
+            reverb_stereo: Buffer::new(2, frame_size),
+        }
+    }
+
+    pub fn with_reverb(mut self, reverb_times: [f32; 3]) -> Self {
+        self.reverb = Some(reverb_times);
+        self
+    }
+
+    fn decoder(processor: &mut AudioProcessor, order: i32) -> AmbisonicsDecode {
+        let hrtf = processor.hrtf();
+        AmbisonicsDecode::create(
+            processor,
+            IPLAmbisonicsDecodeEffectSettings {
+                speakerLayout: IPLSpeakerLayout {
+                    type_: IPLSpeakerLayoutType::IPL_SPEAKERLAYOUTTYPE_STEREO,
+                    numSpeakers: 2,
+                    speakers: std::ptr::null_mut(),
+                },
+                hrtf,
+                maxOrder: order,
+            },
+        )
+    }
+
+    fn create_effects(&mut self) -> EmitterEffects {
+        let processor = &mut self.processor;
+        let hrtf = processor.hrtf();
+        let frame_size = processor.audio_settings.frameSize as usize;
+        let mut effects = EmitterEffects {
+            binaural: None,
+            encode: None,
+            decode: None,
+            reflection: None,
+            input: VecDeque::new(),
+            output: std::iter::repeat(0.0).take(frame_size * 2).collect(),
+        };
+        match self.mode {
+            SpatialMode::Binaural => {
+                effects.binaural = Some(Binaural::create(processor, IPLBinauralEffectSettings { hrtf }));
+            }
+            SpatialMode::Ambisonics(order) => {
+                let order = order.clamp(1, 3);
+                effects.encode = Some(AmbisonicsEncode::create(
+                    processor,
+                    IPLAmbisonicsEncodeEffectSettings { maxOrder: order },
+                ));
+                effects.decode = Some(Self::decoder(processor, order));
+            }
+        }
+        if self.reverb.is_some() {
+            let ir_size = processor.audio_settings.samplingRate * 2;
+            let reflection = Reflection::create(
+                processor,
+                IPLReflectionEffectSettings {
+                    type_: IPLReflectionEffectType::IPL_REFLECTIONEFFECTTYPE_PARAMETRIC,
+                    irSize: ir_size,
+                    numChannels: ambisonic_channels(REFLECTION_ORDER) as i32,
+                },
+            );
+            effects.reflection = Some((reflection, Self::decoder(processor, REFLECTION_ORDER)));
+        }
+        effects
+    }
+
+    //Renders one full frame of `mono` into `stereo`
+    fn render(&mut self, id: EmitterId, params: &SpatialParams) {
+        let hrtf = self.processor.hrtf();
+        let direction = ipl_direction(params);
+        let effects = self.emitters.get_mut(&id).unwrap();
+        self.stereo.clear();
+        match self.mode {
+            SpatialMode::Binaural => {
+                if let Some(binaural) = &effects.binaural {
+                    binaural.apply(
+                        IPLBinauralEffectParams {
+                            direction,
+                            interpolation: IPLHRTFInterpolation::IPL_HRTFINTERPOLATION_NEAREST,
+                            spatialBlend: 1.0,
+                            hrtf,
+                        },
+                        &mut self.mono,
+                        &mut self.stereo,
+                    );
+                }
+            }
+            SpatialMode::Ambisonics(order) => {
+                let order = order.clamp(1, 3);
+                if let (Some(encode), Some(decode)) = (&effects.encode, &effects.decode) {
+                    encode.apply(
+                        IPLAmbisonicsEncodeEffectParams { direction, order },
+                        &mut self.mono,
+                        &mut self.ambisonics,
+                    );
+                    decode.apply(
+                        IPLAmbisonicsDecodeEffectParams {
+                            order,
+                            hrtf,
+                            orientation: listener_space(),
+                            binaural: IPLbool::IPL_TRUE,
+                        },
+                        &mut self.ambisonics,
+                        &mut self.stereo,
+                    );
+                }
+            }
+        }
+        if let (Some((reflection, decode)), Some(reverb_times)) = (&effects.reflection, self.reverb) {
+            self.reflection_ambisonics.clear();
+            self.reverb_stereo.clear();
+            reflection.apply(
+                IPLReflectionEffectParams {
+                    type_: IPLReflectionEffectType::IPL_REFLECTIONEFFECTTYPE_PARAMETRIC,
+                    ir: std::ptr::null_mut(),
+                    reverbTimes: reverb_times,
+                    eq: [1.0, 1.0, 1.0],
+                    delay: 0,
+                    numChannels: ambisonic_channels(REFLECTION_ORDER) as i32,
+                    irSize: self.processor.audio_settings.samplingRate * 2,
+                    tanDevice: std::ptr::null_mut(),
+                    tanSlot: 0,
+                },
+                &mut self.mono,
+                &mut self.reflection_ambisonics,
+            );
+            decode.apply(
+                IPLAmbisonicsDecodeEffectParams {
+                    order: REFLECTION_ORDER,
+                    hrtf,
+                    orientation: listener_space(),
+                    binaural: IPLbool::IPL_TRUE,
+                },
+                &mut self.reflection_ambisonics,
+                &mut self.reverb_stereo,
+            );
+            for channel in 0..2 {
+                let reverb = self.reverb_stereo.channel_samples(channel);
+                for (out, r) in self.stereo.channel_samples_mut(channel).iter_mut().zip(reverb) {
+                    *out += r * self.reverb_gain;
+                }
+            }
+        }
+        let (left, right) = (self.stereo.channel_samples(0), self.stereo.channel_samples(1));
+        for (l, r) in left.iter().zip(right) {
+            effects.output.push_back(*l);
+            effects.output.push_back(*r);
+        }
+    }
+}
+
+impl Spatializer for SteamAudioSpatializer {
+    fn spatialize(&mut self, emitter: EmitterId, params: &SpatialParams, input: &[f32], output: &mut [f32]) {
+        if !self.emitters.contains_key(&emitter) {
+            let effects = self.create_effects();
+            self.emitters.insert(emitter, effects);
+        }
+        let frame_size = self.mono.frame_size();
+        let effects = self.emitters.get_mut(&emitter).unwrap();
+        effects.input.extend(input.iter().map(|s| s * params.gain));
+        while self.emitters[&emitter].input.len() >= frame_size {
+            let effects = self.emitters.get_mut(&emitter).unwrap();
+            for (sample, queued) in self.mono.channel_samples_mut(0).iter_mut().zip(effects.input.drain(..frame_size)) {
+                *sample = queued;
+            }
+            self.render(emitter, params);
+        }
+        let effects = self.emitters.get_mut(&emitter).unwrap();
+        for sample in output.iter_mut() {
+            *sample += effects.output.pop_front().unwrap_or(0.0);
+        }
+    }
+
+    fn remove_emitter(&mut self, emitter: EmitterId) {
+        self.emitters.remove(&emitter);
+    }
+}
@@ -0,0 +1,454 @@
+use super::{
+    font::{FontId, FontSystem, DEFAULT_FONT, DEFAULT_FONT_SIZE},
+    pixel::Pixel,
+    util::Vf2d,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// Stretches the spaces of every wrapped line to fill the width. The last
+    /// line of a paragraph is left aligned.
+    Justify,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextStyle {
+    pub font: FontId,
+    /// Font size in pixels.
+    pub size: f32,
+    pub color: Pixel,
+    pub scale: Vf2d,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            font: DEFAULT_FONT,
+            size: DEFAULT_FONT_SIZE,
+            color: Pixel::WHITE,
+            scale: Vf2d::new(1.0, 1.0),
+        }
+    }
+}
+
+/// How a block of text is laid out.
+///
+/// With `markup` enabled the text may contain `[color=#rrggbb]`,
+/// `[color=#rrggbbaa]`, `[color=red]`, `[size=16]`, `[scale=2]` and
+/// `[font=1]` tags. `[/]` (or any closing tag) returns to the previous style
+/// and `[[` is a literal `[`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextOptions {
+    pub style: TextStyle,
+    /// Width to wrap (or truncate) lines at. Alignment is relative to this
+    /// width, or to the widest line when it is `None`.
+    pub max_width: Option<f32>,
+    pub max_lines: Option<usize>,
+    pub wrap: bool,
+    pub align: TextAlign,
+    /// Multiplier for the distance between lines.
+    pub line_spacing: f32,
+    /// End text cut off by `max_lines` or `max_width` with "…".
+    pub ellipsis: bool,
+    pub markup: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            style: TextStyle::default(),
+            max_width: None,
+            max_lines: None,
+            wrap: true,
+            align: TextAlign::Left,
+            line_spacing: 1.0,
+            ellipsis: false,
+            markup: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositionedGlyph {
+    pub c: char,
+    /// Top left of the glyph's line box, relative to the layout origin.
+    /// Glyphs of different sizes on one line share a baseline.
+    pub pos: Vf2d,
+    pub style: TextStyle,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextLine {
+    /// Indices into `TextLayout::glyphs`.
+    pub glyphs: std::ops::Range<usize>,
+    pub pos: Vf2d,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextLayout {
+    pub glyphs: Vec<PositionedGlyph>,
+    pub lines: Vec<TextLine>,
+    /// Top left of the bounding box. Only non zero on the x axis, when lines
+    /// are centred or right aligned without a `max_width`.
+    pub bounds_pos: Vf2d,
+    pub size: Vf2d,
+    /// Some of the text didn't fit in `max_lines` or `max_width`.
+    pub truncated: bool,
+}
+
+#[derive(Clone, Copy)]
+struct StyledChar {
+    c: char,
+    style: TextStyle,
+    advance: f32,
+}
+
+struct Line {
+    chars: Vec<StyledChar>,
+    paragraph_end: bool,
+}
+
+impl Line {
+    fn width(&self) -> f32 {
+        self.chars.iter().map(|c| c.advance).sum()
+    }
+
+    fn trim_end(&mut self) {
+        while self.chars.last().map(|c| c.c.is_whitespace()).unwrap_or(false) {
+            self.chars.pop();
+        }
+    }
+}
+
+impl TextLayout {
+    pub fn new(fonts: &FontSystem, text: &str, options: &TextOptions) -> Self {
+        let mut chars = parse(text, &options.style, options.markup);
+        measure(fonts, &mut chars);
+        let max_width = options.max_width.unwrap_or(f32::INFINITY);
+
+        let mut lines: Vec<Line> = vec![];
+        let mut truncated = false;
+        for paragraph in chars.split(|c| c.c == '\n') {
+            if options.wrap {
+                wrap(paragraph, max_width, &mut lines);
+            } else {
+                let mut line = Line {
+                    chars: paragraph.to_vec(),
+                    paragraph_end: true,
+                };
+                if line.width() > max_width {
+                    truncated = true;
+                    if options.ellipsis {
+                        add_ellipsis(fonts, &mut line, max_width, &options.style);
+                    } else {
+                        fit(&mut line, max_width);
+                    }
+                }
+                lines.insert(lines.len(), line);
+            }
+        }
+
+        if let Some(max_lines) = options.max_lines {
+            if lines.len() > max_lines {
+                truncated = true;
+                lines.truncate(max_lines);
+                if let (true, Some(last)) = (options.ellipsis, lines.last_mut()) {
+                    add_ellipsis(fonts, last, max_width, &options.style);
+                }
+            }
+        }
+
+        let widest = lines.iter().map(|l| l.width()).fold(0.0, f32::max);
+        let box_width = options.max_width.unwrap_or(widest);
+        let mut layout = TextLayout {
+            truncated,
+            ..Default::default()
+        };
+        let (mut top, mut min_x, mut max_x) = (0.0, f32::INFINITY, f32::NEG_INFINITY);
+        for line in &lines {
+            //Empty lines still take up the height of the base style
+            let styles: Vec<TextStyle> = if line.chars.is_empty() {
+                vec![options.style]
+            } else {
+                line.chars.iter().map(|c| c.style).collect()
+            };
+            let ascent = styles
+                .iter()
+                .map(|s| fonts.metrics(s.font, s.size).ascent * s.scale.y)
+                .fold(0.0, f32::max);
+            let height = styles
+                .iter()
+                .map(|s| fonts.metrics(s.font, s.size).line_height * s.scale.y)
+                .fold(0.0, f32::max);
+
+            let width = line.width();
+            let spaces = line.chars.iter().filter(|c| c.c == ' ').count();
+            let (x, space_extra) = match options.align {
+                TextAlign::Left => (0.0, 0.0),
+                TextAlign::Center => ((box_width - width) * 0.5, 0.0),
+                TextAlign::Right => (box_width - width, 0.0),
+                TextAlign::Justify if !line.paragraph_end && spaces > 0 && box_width.is_finite() => {
+                    (0.0, (box_width - width) / spaces as f32)
+                }
+                TextAlign::Justify => (0.0, 0.0),
+            };
+
+            let start = layout.glyphs.len();
+            let mut pen = x;
+            for c in &line.chars {
+                let glyph_ascent = fonts.metrics(c.style.font, c.style.size).ascent * c.style.scale.y;
+                layout.glyphs.insert(
+                    layout.glyphs.len(),
+                    PositionedGlyph {
+                        c: c.c,
+                        pos: Vf2d::new(pen, top + ascent - glyph_ascent),
+                        style: c.style,
+                    },
+                );
+                pen += c.advance;
+                if c.c == ' ' {
+                    pen += space_extra;
+                }
+            }
+            layout.lines.insert(
+                layout.lines.len(),
+                TextLine {
+                    glyphs: start..layout.glyphs.len(),
+                    pos: Vf2d::new(x, top),
+                    width: pen - x,
+                    height,
+                },
+            );
+            min_x = min_x.min(x);
+            max_x = max_x.max(pen);
+            top += height * options.line_spacing;
+        }
+        //The last line doesn't need the extra spacing below it
+        if let Some(last) = layout.lines.last() {
+            top = last.pos.y + last.height;
+        }
+        if layout.lines.is_empty() {
+            min_x = 0.0;
+            max_x = 0.0;
+        }
+        layout.bounds_pos = Vf2d::new(min_x, 0.0);
+        layout.size = Vf2d::new(max_x - min_x, top);
+        layout
+    }
+}
+
+fn parse(text: &str, base: &TextStyle, markup: bool) -> Vec<StyledChar> {
+    let styled = |c: char, style: TextStyle| StyledChar {
+        c,
+        style,
+        advance: 0.0,
+    };
+    if !markup {
+        return text.chars().map(|c| styled(c, *base)).collect();
+    }
+    let mut out = vec![];
+    let mut stack = vec![*base];
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '[' {
+            if let Some(after) = rest.strip_prefix("[[") {
+                out.insert(out.len(), styled('[', *stack.last().unwrap()));
+                rest = after;
+                continue;
+            }
+            if let Some(end) = rest.find(']') {
+                if apply_tag(&rest[1..end], &mut stack) {
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+        }
+        out.insert(out.len(), styled(c, *stack.last().unwrap()));
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+//Returns false for anything that isn't a tag so it is drawn as text
+fn apply_tag(tag: &str, stack: &mut Vec<TextStyle>) -> bool {
+    if tag.starts_with('/') {
+        if stack.len() > 1 {
+            stack.pop();
+        }
+        return true;
+    }
+    let (name, value) = match tag.split_once('=') {
+        Some(pair) => pair,
+        None => return false,
+    };
+    let mut style = *stack.last().unwrap();
+    match name.trim() {
+        "color" | "colour" => match parse_color(value.trim()) {
+            Some(color) => style.color = color,
+            None => return false,
+        },
+        "size" => match value.trim().parse::<f32>() {
+            Ok(size) => style.size = size,
+            Err(_) => return false,
+        },
+        "scale" => match value.trim().parse::<f32>() {
+            Ok(scale) => style.scale = Vf2d::new(scale, scale),
+            Err(_) => return false,
+        },
+        "font" => match value.trim().parse::<FontId>() {
+            Ok(font) => style.font = font,
+            Err(_) => return false,
+        },
+        _ => return false,
+    }
+    stack.insert(stack.len(), style);
+    true
+}
+
+pub fn parse_color(value: &str) -> Option<Pixel> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return match hex.len() {
+            6 => Some(Pixel::rgb(channel(0)?, channel(2)?, channel(4)?)),
+            8 => Some(Pixel::rgba(channel(0)?, channel(2)?, channel(4)?, channel(6)?)),
+            _ => None,
+        };
+    }
+    match value.to_lowercase().as_str() {
+        "white" => Some(Pixel::WHITE),
+        "black" => Some(Pixel::BLACK),
+        "grey" | "gray" => Some(Pixel::GREY),
+        "red" => Some(Pixel::RED),
+        "green" => Some(Pixel::GREEN),
+        "blue" => Some(Pixel::BLUE),
+        "yellow" => Some(Pixel::YELLOW),
+        "cyan" => Some(Pixel::CYAN),
+        "magenta" => Some(Pixel::MAGENTA),
+        _ => None,
+    }
+}
+
+fn measure(fonts: &FontSystem, chars: &mut [StyledChar]) {
+    for i in 0..chars.len() {
+        let c = chars[i];
+        //Only kern against the next character when it is drawn the same way
+        let next = chars
+            .get(i + 1)
+            .filter(|n| n.style.font == c.style.font && n.style.size == c.style.size)
+            .map(|n| n.c);
+        chars[i].advance = fonts.advance(c.style.font, c.c, next, c.style.size) * c.style.scale.x;
+    }
+}
+
+//Greedy word wrap. Words longer than a line are split between characters.
+fn wrap(paragraph: &[StyledChar], max_width: f32, lines: &mut Vec<Line>) {
+    let mut line = Line {
+        chars: vec![],
+        paragraph_end: false,
+    };
+    let mut width = 0.0;
+    let mut i = 0;
+    while i < paragraph.len() {
+        let is_space = paragraph[i].c.is_whitespace();
+        let end = paragraph[i..]
+            .iter()
+            .position(|c| c.c.is_whitespace() != is_space)
+            .map(|p| i + p)
+            .unwrap_or(paragraph.len());
+        let token = &paragraph[i..end];
+        let token_width: f32 = token.iter().map(|c| c.advance).sum();
+
+        if is_space {
+            //Spaces at the start of a wrapped line are dropped
+            if !(line.chars.is_empty() && !lines.is_empty() && !lines.last().unwrap().paragraph_end) {
+                line.chars.extend_from_slice(token);
+                width += token_width;
+            }
+        } else {
+            let trailing: f32 = line
+                .chars
+                .iter()
+                .rev()
+                .take_while(|c| c.c.is_whitespace())
+                .map(|c| c.advance)
+                .sum();
+            if width + token_width > max_width && width - trailing > 0.0 {
+                line.trim_end();
+                lines.insert(lines.len(), line);
+                line = Line {
+                    chars: vec![],
+                    paragraph_end: false,
+                };
+                width = 0.0;
+            }
+            for c in token {
+                if width + c.advance > max_width && !line.chars.is_empty() {
+                    lines.insert(lines.len(), line);
+                    line = Line {
+                        chars: vec![],
+                        paragraph_end: false,
+                    };
+                    width = 0.0;
+                }
+                line.chars.insert(line.chars.len(), *c);
+                width += c.advance;
+            }
+        }
+        i = end;
+    }
+    line.trim_end();
+    line.paragraph_end = true;
+    lines.insert(lines.len(), line);
+}
+
+//Drops characters until the line fits
+fn fit(line: &mut Line, max_width: f32) {
+    while line.width() > max_width && !line.chars.is_empty() {
+        line.chars.pop();
+    }
+}
+
+fn add_ellipsis(fonts: &FontSystem, line: &mut Line, max_width: f32, base: &TextStyle) {
+    let style = line.chars.last().map(|c| c.style).unwrap_or(*base);
+    let has_ellipsis = fonts
+        .get_font(fonts.resolve(style.font, '…'))
+        .map(|f| f.has_glyph('…'))
+        .unwrap_or(false);
+    let text = if has_ellipsis { "…" } else { "..." };
+    let mut ellipsis: Vec<StyledChar> = text
+        .chars()
+        .map(|c| StyledChar {
+            c,
+            style,
+            advance: 0.0,
+        })
+        .collect();
+    measure(fonts, &mut ellipsis);
+    let ellipsis_width: f32 = ellipsis.iter().map(|c| c.advance).sum();
+    fit(line, (max_width - ellipsis_width).max(0.0));
+    line.trim_end();
+    line.chars.extend(ellipsis);
+}
+
+/// `color` drawn over `dst` with coverage `alpha`, like `Pixel::alpha_blend`
+/// with `alpha` as the colour's alpha.
+pub fn blend_over(dst: Pixel, color: Pixel, alpha: u8) -> Pixel {
+    let a = alpha as f32 / 255.0;
+    let dst_a = dst.a() as f32 / 255.0 * (1.0 - a);
+    let out_a = a + dst_a;
+    if out_a <= 0.0 {
+        return Pixel::BLANK;
+    }
+    let mix = |s: u8, d: u8| ((s as f32 * a + d as f32 * dst_a) / out_a).round() as u8;
+    Pixel::rgba(
+        mix(color.r(), dst.r()),
+        mix(color.g(), dst.g()),
+        mix(color.b(), dst.b()),
+        (out_a * 255.0).round() as u8,
+    )
+}
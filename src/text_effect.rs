@@ -0,0 +1,231 @@
+use super::{
+    font::FontId,
+    pixel::Pixel,
+    text::TextLayout,
+    util::Vf2d,
+};
+
+/// Glyphs that can stand in for corrupted letters. All of them are in the
+/// default 8x8 font.
+pub const DEFAULT_GLYPHS: &str = "#%&@$*+=?~^<>{}|\\/";
+
+/// Small deterministic generator (splitmix64), so the same seed always
+/// corrupts the same letters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Prng {
+    state: u64,
+}
+
+impl Prng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix(self.state)
+    }
+
+    /// Uniform in [0, 1).
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    //Independent stream for one character, time step and purpose. Every
+    // input is hashed in on its own, so no two combinations share a stream
+    fn at(seed: u64, tick: u64, index: usize, salt: u64) -> Self {
+        let state = [tick, index as u64, salt]
+            .iter()
+            .fold(mix(seed), |state, input| mix(state ^ mix(*input)));
+        Prng::new(state)
+    }
+}
+
+//SplitMix64 finalizer
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Corrupts laid out text as `intensity` goes from 0 to 1: letters turn into
+/// glyphs, shake, ride a wave and fade towards `fade_color`.
+///
+/// Which letters are replaced only depends on `seed` and the letter's
+/// position, and raising the intensity only ever adds letters, so text
+/// degrades smoothly instead of flickering.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEffect {
+    pub intensity: f32,
+    pub seed: u64,
+    /// Characters to substitute letters with.
+    pub glyphs: Vec<char>,
+    /// Font to draw substituted glyphs with, the letter's own font if `None`.
+    pub glyph_font: Option<FontId>,
+    /// Fraction of letters replaced at full intensity.
+    pub substitution: f32,
+    /// How often per second a replaced letter picks a new glyph. 0 keeps it.
+    pub flicker_rate: f32,
+    /// Largest random offset in pixels at full intensity.
+    pub jitter: f32,
+    /// How often per second the jitter changes.
+    pub jitter_rate: f32,
+    pub wave_amplitude: f32,
+    /// Radians per letter.
+    pub wave_frequency: f32,
+    /// Radians per second.
+    pub wave_speed: f32,
+    pub fade_color: Pixel,
+    /// How far colors move towards `fade_color` at full intensity.
+    pub fade: f32,
+}
+
+impl Default for TextEffect {
+    fn default() -> Self {
+        Self {
+            intensity: 0.0,
+            seed: 0,
+            glyphs: DEFAULT_GLYPHS.chars().collect(),
+            glyph_font: None,
+            substitution: 1.0,
+            flicker_rate: 0.0,
+            jitter: 1.0,
+            jitter_rate: 12.0,
+            wave_amplitude: 2.0,
+            wave_frequency: 0.6,
+            wave_speed: 4.0,
+            fade_color: Pixel::rgba(0, 0, 0, 0),
+            fade: 0.5,
+        }
+    }
+}
+
+const SALT_SUBSTITUTE: u64 = 1;
+const SALT_GLYPH: u64 = 2;
+const SALT_JITTER: u64 = 3;
+
+impl TextEffect {
+    pub fn new(intensity: f32, seed: u64) -> Self {
+        Self {
+            intensity,
+            seed,
+            ..Default::default()
+        }
+    }
+
+    /// Is the letter at `index` replaced at the current intensity.
+    pub fn is_substituted(&self, index: usize) -> bool {
+        let threshold = Prng::at(self.seed, 0, index, SALT_SUBSTITUTE).next_f32();
+        threshold < self.intensity.clamp(0.0, 1.0) * self.substitution
+    }
+
+    /// Copy of `layout` with the effect applied at `time` seconds. Bounds and
+    /// lines are left as laid out.
+    pub fn apply(&self, layout: &TextLayout, time: f32) -> TextLayout {
+        let mut out = layout.clone();
+        let intensity = self.intensity.clamp(0.0, 1.0);
+        if intensity <= 0.0 {
+            return out;
+        }
+        let flicker_tick = (time * self.flicker_rate).floor().max(0.0) as u64;
+        let jitter_tick = (time * self.jitter_rate).floor().max(0.0) as u64;
+
+        for (i, g) in out.glyphs.iter_mut().enumerate() {
+            if g.c.is_whitespace() {
+                continue;
+            }
+            if !self.glyphs.is_empty() && self.is_substituted(i) {
+                let mut rng = Prng::at(self.seed, flicker_tick, i, SALT_GLYPH);
+                g.c = self.glyphs[rng.next_u64() as usize % self.glyphs.len()];
+                if let Some(font) = self.glyph_font {
+                    g.style.font = font;
+                }
+            }
+
+            let mut rng = Prng::at(self.seed, jitter_tick, i, SALT_JITTER);
+            let jitter = Vf2d::new(rng.next_f32() * 2.0 - 1.0, rng.next_f32() * 2.0 - 1.0)
+                * (self.jitter * intensity);
+            let wave = (time * self.wave_speed + i as f32 * self.wave_frequency).sin()
+                * self.wave_amplitude
+                * intensity;
+            g.pos = g.pos + jitter + Vf2d::new(0.0, wave);
+
+            g.style.color = lerp_color(g.style.color, self.fade_color, self.fade * intensity);
+        }
+        out
+    }
+}
+
+fn lerp_color(a: Pixel, b: Pixel, t: f32) -> Pixel {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Pixel::rgba(
+        mix(a.r(), b.r()),
+        mix(a.g(), b.g()),
+        mix(a.b(), b.b()),
+        mix(a.a(), b.a()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{PositionedGlyph, TextStyle};
+
+    fn layout(text: &str) -> TextLayout {
+        let glyphs: Vec<PositionedGlyph> = text
+            .chars()
+            .enumerate()
+            .map(|(i, c)| PositionedGlyph {
+                c,
+                pos: Vf2d::new(i as f32 * 8.0, 0.0),
+                style: TextStyle::default(),
+            })
+            .collect();
+        TextLayout {
+            size: Vf2d::new(glyphs.len() as f32 * 8.0, 8.0),
+            glyphs,
+            lines: vec![],
+            bounds_pos: Vf2d::new(0.0, 0.0),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_glyphs() {
+        let text = layout("The quick brown fox jumps over the lazy dog");
+        let a = TextEffect::new(0.7, 1234).apply(&text, 1.25);
+        let b = TextEffect::new(0.7, 1234).apply(&text, 1.25);
+        assert_eq!(a, b);
+        for (g, original) in a.glyphs.iter().zip(text.glyphs.iter()) {
+            if !original.c.is_whitespace() {
+                assert_ne!(g.style.color, original.style.color);
+            }
+        }
+    }
+
+    #[test]
+    fn different_seed_gives_different_glyphs() {
+        let text = layout("The quick brown fox jumps over the lazy dog");
+        let a = TextEffect::new(0.7, 1234).apply(&text, 1.25);
+        let b = TextEffect::new(0.7, 4321).apply(&text, 1.25);
+        let positions = |l: &TextLayout| l.glyphs.iter().map(|g| g.pos).collect::<Vec<Vf2d>>();
+        let chars = |l: &TextLayout| l.glyphs.iter().map(|g| g.c).collect::<String>();
+        assert_ne!(positions(&a), positions(&b));
+        assert_ne!(chars(&a), chars(&b));
+    }
+
+    #[test]
+    fn adjacent_seeds_dont_flicker_together() {
+        //Seed 0 one tick in used to equal seed 1 at the start
+        let mut a = Prng::at(0, 1, 3, SALT_GLYPH);
+        let mut b = Prng::at(1, 0, 3, SALT_GLYPH);
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn zero_intensity_leaves_text_alone() {
+        let text = layout("Hello");
+        assert_eq!(TextEffect::new(0.0, 99).apply(&text, 3.0), text);
+    }
+}
@@ -0,0 +1,167 @@
+use super::og_engine::Rcode;
+
+/// Something typed this frame, in the order it happened.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextEvent {
+    /// A printable character, already shifted and keyboard-layout mapped.
+    Char(char),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    /// Return was pressed.
+    Submit,
+    /// Escape was pressed.
+    Cancel,
+    /// Ctrl+V with text on the clipboard.
+    Paste(String),
+    /// Text an input method is still composing. Replaces the previous
+    /// preedit, an empty string clears it. `cursor` is a byte range.
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// Text an input method finished composing.
+    Commit(String),
+}
+
+/// Typed text for the current frame. The platform layer queues events as
+/// they arrive and `begin_frame` makes them visible to the game and the UI.
+#[derive(Default)]
+pub struct TextInput {
+    events: Vec<TextEvent>,
+    preedit: String,
+    preedit_cursor: Option<(usize, usize)>,
+    /// Set through `OGEngine::set_ime_allowed`.
+    pub ime_allowed: bool,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn begin_frame(&mut self, events: Vec<TextEvent>) {
+        for event in &events {
+            match event {
+                TextEvent::Preedit { text, cursor } => {
+                    self.preedit = text.clone();
+                    self.preedit_cursor = *cursor;
+                }
+                TextEvent::Commit(_) => {
+                    self.preedit.clear();
+                    self.preedit_cursor = None;
+                }
+                _ => {}
+            }
+        }
+        self.events = events;
+    }
+
+    pub fn events(&self) -> &[TextEvent] {
+        &self.events
+    }
+
+    /// Characters typed, pasted or committed this frame.
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+        for event in &self.events {
+            match event {
+                TextEvent::Char(c) => out.push(*c),
+                TextEvent::Paste(s) | TextEvent::Commit(s) => out.push_str(s),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    /// Text being composed by the input method, not part of the value yet.
+    pub fn preedit(&self) -> &str {
+        &self.preedit
+    }
+
+    pub fn preedit_cursor(&self) -> Option<(usize, usize)> {
+        self.preedit_cursor
+    }
+}
+
+/// What applying text events to a field did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextEdit {
+    pub changed: bool,
+    pub submitted: bool,
+    pub cancelled: bool,
+}
+
+/// Applies `events` to a single line `value`. `cursor` counts characters.
+/// Line breaks in pasted text become spaces and other control characters are
+/// dropped.
+pub fn edit_text(value: &mut String, cursor: &mut usize, events: &[TextEvent]) -> TextEdit {
+    let mut chars: Vec<char> = value.chars().collect();
+    let mut at = (*cursor).min(chars.len());
+    let mut edit = TextEdit::default();
+    for event in events {
+        match event {
+            TextEvent::Char(c) => insert(&mut chars, &mut at, c.encode_utf8(&mut [0; 4])),
+            TextEvent::Paste(s) | TextEvent::Commit(s) => insert(&mut chars, &mut at, s),
+            TextEvent::Backspace if at > 0 => {
+                at -= 1;
+                chars.remove(at);
+            }
+            TextEvent::Delete if at < chars.len() => {
+                chars.remove(at);
+            }
+            TextEvent::Left => at = at.saturating_sub(1),
+            TextEvent::Right => at = (at + 1).min(chars.len()),
+            TextEvent::Home => at = 0,
+            TextEvent::End => at = chars.len(),
+            TextEvent::Submit => edit.submitted = true,
+            TextEvent::Cancel => edit.cancelled = true,
+            _ => {}
+        }
+    }
+    let new_value: String = chars.into_iter().collect();
+    edit.changed = new_value != *value;
+    *value = new_value;
+    *cursor = at;
+    edit
+}
+
+fn insert(chars: &mut Vec<char>, at: &mut usize, text: &str) {
+    for c in text.chars() {
+        let c = if c == '\n' { ' ' } else { c };
+        if !c.is_control() {
+            chars.insert(*at, c);
+            *at += 1;
+        }
+    }
+}
+
+/// Text on the system clipboard. Needs the `clipboard` feature.
+pub fn clipboard_text() -> Option<String> {
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    {
+        arboard::Clipboard::new().ok()?.get_text().ok()
+    }
+    #[cfg(not(all(feature = "clipboard", not(target_arch = "wasm32"))))]
+    {
+        None
+    }
+}
+
+pub fn set_clipboard_text(text: &str) -> Rcode {
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    {
+        match arboard::Clipboard::new().and_then(|mut c| c.set_text(text.to_string())) {
+            Ok(()) => Rcode::Ok,
+            Err(_) => Rcode::Fail,
+        }
+    }
+    #[cfg(not(all(feature = "clipboard", not(target_arch = "wasm32"))))]
+    {
+        let _ = text;
+        Rcode::Fail
+    }
+}
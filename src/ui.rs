@@ -0,0 +1,825 @@
+use super::{
+    engine::OGEngine,
+    layer::Mask,
+    og_engine::OGData,
+    pixel::Pixel,
+    text::{TextAlign, TextLayout, TextOptions, TextStyle},
+    text_input::{edit_text, TextEvent},
+    util::{HWButton, Mouse, Vf2d},
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    ops::RangeInclusive,
+};
+
+pub type WidgetId = u64;
+
+//Widgets that aren't inside a window or panel
+const ROOT: WidgetId = 0;
+
+/// Input the UI used this frame. Set by `UiFrame::end` and cleared at the
+/// start of every frame, so build the UI before reading game input.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InputCapture {
+    pub mouse: bool,
+    pub keyboard: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub pos: Vf2d,
+    pub size: Vf2d,
+}
+
+impl Rect {
+    pub fn new(pos: Vf2d, size: Vf2d) -> Self {
+        Self { pos, size }
+    }
+
+    pub fn max(&self) -> Vf2d {
+        self.pos + self.size
+    }
+
+    pub fn contains(&self, p: Vf2d) -> bool {
+        p.x >= self.pos.x && p.y >= self.pos.y && p.x < self.pos.x + self.size.x && p.y < self.pos.y + self.size.y
+    }
+
+    /// Is `other` completely inside this rect.
+    pub fn encloses(&self, other: &Rect) -> bool {
+        other.pos.x >= self.pos.x
+            && other.pos.y >= self.pos.y
+            && other.max().x <= self.max().x
+            && other.max().y <= self.max().y
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UiStyle {
+    pub text: TextStyle,
+    pub text_disabled: Pixel,
+    pub window: Pixel,
+    pub title: Pixel,
+    pub title_active: Pixel,
+    pub panel: Pixel,
+    pub widget: Pixel,
+    pub widget_hovered: Pixel,
+    pub widget_active: Pixel,
+    pub accent: Pixel,
+    pub tooltip: Pixel,
+    pub padding: f32,
+    pub spacing: f32,
+    pub scrollbar_width: f32,
+}
+
+impl Default for UiStyle {
+    fn default() -> Self {
+        Self {
+            text: TextStyle::default(),
+            text_disabled: Pixel::GREY,
+            window: Pixel::rgba(24, 24, 32, 230),
+            title: Pixel::rgba(48, 48, 72, 255),
+            title_active: Pixel::rgba(72, 72, 120, 255),
+            panel: Pixel::rgba(32, 32, 40, 200),
+            widget: Pixel::rgba(64, 64, 80, 255),
+            widget_hovered: Pixel::rgba(88, 88, 112, 255),
+            widget_active: Pixel::rgba(112, 112, 160, 255),
+            accent: Pixel::rgba(200, 170, 90, 255),
+            tooltip: Pixel::rgba(16, 16, 16, 240),
+            padding: 3.0,
+            spacing: 2.0,
+            scrollbar_width: 4.0,
+        }
+    }
+}
+
+/// What happened to a widget this frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Response {
+    pub rect: Rect,
+    pub hovered: bool,
+    /// Mouse went down on the widget.
+    pub pressed: bool,
+    /// Mouse is being held after going down on the widget.
+    pub held: bool,
+    /// Mouse went down and back up on the widget.
+    pub clicked: bool,
+    /// The widget changed the value it edits.
+    pub changed: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct WindowState {
+    rect: Rect,
+    open: bool,
+    //Mouse position relative to the window when dragging started
+    drag: Vf2d,
+}
+
+/// Immediate mode UI state kept between frames: window positions, scroll
+/// offsets and which widget has the mouse or keyboard.
+///
+/// ```ignore
+/// let mut ui = ui_state.begin(engine);
+/// ui.window("Journal", Vf2d::new(8.0, 8.0), Vf2d::new(120.0, 80.0), |ui| {
+///     ui.text("[color=#c8aa5a]Day 3[/] The lighthouse keeper is gone.");
+///     if ui.button("Close") {
+///         show_journal = false;
+///     }
+/// });
+/// ui.end();
+/// ```
+#[derive(Default)]
+pub struct Ui {
+    pub style: UiStyle,
+    windows: HashMap<WidgetId, WindowState>,
+    //Back to front
+    order: Vec<WidgetId>,
+    //Window or panel rects from the last frame, used to find what is on top
+    last_rects: Vec<(WidgetId, Rect)>,
+    scroll: HashMap<WidgetId, f32>,
+    active: Option<WidgetId>,
+    focused: Option<WidgetId>,
+    text_cursor: usize,
+}
+
+impl Ui {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn begin<'a, D: OGData + 'static>(&'a mut self, engine: &'a mut OGEngine<D>) -> UiFrame<'a, D> {
+        let mouse = engine.get_mouse_pos();
+        //Topmost window under the mouse, windows are drawn after root widgets
+        let hovered_window = self
+            .order
+            .iter()
+            .rev()
+            .find(|id| self.last_rects.iter().any(|(w, r)| w == *id && r.contains(mouse)))
+            .copied()
+            .unwrap_or(ROOT);
+        let input = FrameInput::read(engine);
+        let screen = Vf2d::new(engine.pixels_w as f32, engine.pixels_h as f32);
+        UiFrame {
+            ui: self,
+            engine,
+            input,
+            mouse,
+            hovered_window,
+            layouts: vec![LayoutState::new(Rect::new(Vf2d::new(0.0, 0.0), screen), Direction::Vertical)],
+            id_stack: vec![ROOT],
+            window_stack: vec![ROOT],
+            draw_lists: HashMap::new(),
+            overlay: vec![],
+            rects: vec![],
+            last_item: Response::default(),
+            any_hovered: false,
+        }
+    }
+
+    /// Does the window named `title` exist and is it open.
+    pub fn is_open(&self, title: &str) -> bool {
+        self.windows.get(&hash_id(ROOT, title)).map(|w| w.open).unwrap_or(false)
+    }
+
+    pub fn set_open(&mut self, title: &str, open: bool) {
+        if let Some(window) = self.windows.get_mut(&hash_id(ROOT, title)) {
+            window.open = open;
+        }
+    }
+}
+
+fn hash_id(parent: WidgetId, label: &str) -> WidgetId {
+    //"Label##id" draws "Label" but hashes the whole string
+    let mut hasher = DefaultHasher::new();
+    parent.hash(&mut hasher);
+    label.hash(&mut hasher);
+    hasher.finish().max(1)
+}
+
+fn visible_label(label: &str) -> &str {
+    label.split("##").next().unwrap_or(label)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Vertical,
+    Horizontal,
+}
+
+#[derive(Clone, Copy)]
+struct LayoutState {
+    bounds: Rect,
+    clip: Rect,
+    cursor: Vf2d,
+    direction: Direction,
+    //Furthest point reached by a widget
+    extent: Vf2d,
+}
+
+impl LayoutState {
+    fn new(bounds: Rect, direction: Direction) -> Self {
+        Self {
+            bounds,
+            clip: bounds,
+            cursor: bounds.pos,
+            direction,
+            extent: bounds.pos,
+        }
+    }
+}
+
+enum DrawCmd {
+    Rect(Rect, Pixel),
+    Text(Vf2d, TextLayout),
+}
+
+//Raw input read once per frame, before the UI hides it from the game
+struct FrameInput {
+    left: HWButton,
+    wheel: i32,
+    text: Vec<TextEvent>,
+}
+
+impl FrameInput {
+    fn read<D: OGData + 'static>(engine: &OGEngine<D>) -> Self {
+        Self {
+            left: engine.get_raw_mouse(Mouse::Left),
+            wheel: engine.get_raw_mouse_wheel(),
+            text: engine.text_input.events().to_vec(),
+        }
+    }
+}
+
+/// One frame of UI. Widgets are laid out top to bottom (or left to right in
+/// `horizontal`) and drawn as decals on the `Mask::GUI` layer by `end`.
+pub struct UiFrame<'a, D: OGData + 'static> {
+    ui: &'a mut Ui,
+    pub engine: &'a mut OGEngine<D>,
+    input: FrameInput,
+    mouse: Vf2d,
+    hovered_window: WidgetId,
+    layouts: Vec<LayoutState>,
+    id_stack: Vec<WidgetId>,
+    window_stack: Vec<WidgetId>,
+    draw_lists: HashMap<WidgetId, Vec<DrawCmd>>,
+    overlay: Vec<DrawCmd>,
+    rects: Vec<(WidgetId, Rect)>,
+    last_item: Response,
+    any_hovered: bool,
+}
+
+impl<'a, D: OGData + 'static> UiFrame<'a, D> {
+    pub fn style(&self) -> UiStyle {
+        self.ui.style
+    }
+
+    fn layout(&mut self) -> &mut LayoutState {
+        self.layouts.last_mut().unwrap()
+    }
+
+    fn id(&self, label: &str) -> WidgetId {
+        hash_id(*self.id_stack.last().unwrap(), label)
+    }
+
+    fn current_window(&self) -> WidgetId {
+        *self.window_stack.last().unwrap()
+    }
+
+    /// Space left on the current row or column.
+    pub fn available_width(&self) -> f32 {
+        let layout = self.layouts.last().unwrap();
+        layout.bounds.max().x - layout.cursor.x
+    }
+
+    fn line_height(&self) -> f32 {
+        let text = self.ui.style.text;
+        self.engine.fonts.metrics(text.font, text.size).line_height * text.scale.y
+    }
+
+    fn layout_text(&self, text: &str, options: TextOptions) -> TextLayout {
+        self.engine.layout_text(text, &options)
+    }
+
+    fn text_options(&self) -> TextOptions {
+        TextOptions {
+            style: self.ui.style.text,
+            ..Default::default()
+        }
+    }
+
+    /// Reserves `size` at the cursor and moves the cursor past it.
+    pub fn allocate(&mut self, size: Vf2d) -> Rect {
+        let spacing = self.ui.style.spacing;
+        let layout = self.layout();
+        let rect = Rect::new(layout.cursor, size);
+        match layout.direction {
+            Direction::Vertical => layout.cursor.y += size.y + spacing,
+            Direction::Horizontal => layout.cursor.x += size.x + spacing,
+        }
+        layout.extent.x = layout.extent.x.max(rect.max().x);
+        layout.extent.y = layout.extent.y.max(rect.max().y);
+        rect
+    }
+
+    fn visible(&self, rect: &Rect) -> bool {
+        self.layouts.last().unwrap().clip.encloses(rect)
+    }
+
+    fn draw(&mut self, cmd: DrawCmd) {
+        let list = self.draw_lists.entry(self.current_window()).or_default();
+        list.insert(list.len(), cmd);
+    }
+
+    fn draw_rect(&mut self, rect: Rect, color: Pixel) {
+        if self.visible(&rect) {
+            self.draw(DrawCmd::Rect(rect, color));
+        }
+    }
+
+    fn draw_text(&mut self, pos: Vf2d, layout: TextLayout) {
+        let rect = Rect::new(pos, layout.size);
+        if self.visible(&rect) {
+            self.draw(DrawCmd::Text(pos, layout));
+        }
+    }
+
+    /// Mouse interaction for a widget at `rect`.
+    pub fn interact(&mut self, id: WidgetId, rect: Rect) -> Response {
+        let clip = self.layouts.last().unwrap().clip;
+        let hovered = rect.contains(self.mouse)
+            && clip.contains(self.mouse)
+            && self.current_window() == self.hovered_window
+            && self.ui.active.map(|a| a == id).unwrap_or(true);
+        let mut response = Response {
+            rect,
+            hovered,
+            ..Default::default()
+        };
+        if hovered && self.input.left.pressed {
+            self.ui.active = Some(id);
+            response.pressed = true;
+        }
+        if self.ui.active == Some(id) {
+            response.held = self.input.left.held;
+            response.clicked = hovered && self.input.left.released;
+        }
+        self.any_hovered |= hovered;
+        self.last_item = response;
+        response
+    }
+
+    fn widget_color(&self, response: &Response) -> Pixel {
+        let style = &self.ui.style;
+        if response.held {
+            style.widget_active
+        } else if response.hovered {
+            style.widget_hovered
+        } else {
+            style.widget
+        }
+    }
+
+    pub fn label(&mut self, text: &str) {
+        let layout = self.layout_text(text, self.text_options());
+        let rect = self.allocate(layout.size);
+        self.last_item = Response {
+            rect,
+            hovered: rect.contains(self.mouse) && self.current_window() == self.hovered_window,
+            ..Default::default()
+        };
+        self.draw_text(rect.pos, layout);
+    }
+
+    /// Marked up text wrapped to the available width.
+    pub fn text(&mut self, text: &str) {
+        let mut options = self.text_options();
+        options.max_width = Some(self.available_width());
+        options.markup = true;
+        let layout = self.layout_text(text, options);
+        let rect = self.allocate(Vf2d::new(self.available_width().max(layout.size.x), layout.size.y));
+        self.draw_text(rect.pos, layout);
+    }
+
+    pub fn separator(&mut self) {
+        let width = self.available_width();
+        let rect = self.allocate(Vf2d::new(width, 1.0));
+        self.draw_rect(rect, self.ui.style.widget);
+    }
+
+    pub fn spacing(&mut self, amount: f32) {
+        self.allocate(Vf2d::new(amount, amount));
+    }
+
+    pub fn button(&mut self, label: &str) -> bool {
+        let id = self.id(label);
+        let padding = self.ui.style.padding;
+        let layout = self.layout_text(visible_label(label), self.text_options());
+        let size = layout.size + Vf2d::new(padding * 2.0, padding * 2.0);
+        let rect = self.allocate(size);
+        let response = self.interact(id, rect);
+        self.draw_rect(rect, self.widget_color(&response));
+        self.draw_text(rect.pos + Vf2d::new(padding, padding), layout);
+        response.clicked
+    }
+
+    pub fn checkbox(&mut self, label: &str, value: &mut bool) -> bool {
+        let id = self.id(label);
+        let box_size = self.line_height();
+        let layout = self.layout_text(visible_label(label), self.text_options());
+        let spacing = self.ui.style.spacing;
+        let rect = self.allocate(Vf2d::new(box_size + spacing + layout.size.x, box_size.max(layout.size.y)));
+        let mut response = self.interact(id, rect);
+        if response.clicked {
+            *value = !*value;
+            response.changed = true;
+            self.last_item = response;
+        }
+        let check = Rect::new(rect.pos, Vf2d::new(box_size, box_size));
+        self.draw_rect(check, self.widget_color(&response));
+        if *value {
+            let inset = (box_size * 0.25).floor();
+            self.draw_rect(
+                Rect::new(check.pos + Vf2d::new(inset, inset), check.size - Vf2d::new(inset * 2.0, inset * 2.0)),
+                self.ui.style.accent,
+            );
+        }
+        self.draw_text(rect.pos + Vf2d::new(box_size + spacing, 0.0), layout);
+        response.changed
+    }
+
+    /// Horizontal slider filling the available width, dragged with the mouse.
+    pub fn slider(&mut self, label: &str, value: &mut f32, range: RangeInclusive<f32>) -> bool {
+        let id = self.id(label);
+        let (min, max) = (*range.start(), *range.end());
+        let height = self.line_height() + self.ui.style.padding * 2.0;
+        let width = self.available_width();
+        let rect = self.allocate(Vf2d::new(width, height));
+        let mut response = self.interact(id, rect);
+        if response.held && max > min {
+            let t = ((self.mouse.x - rect.pos.x) / rect.size.x).clamp(0.0, 1.0);
+            let new_value = min + (max - min) * t;
+            if new_value != *value {
+                *value = new_value;
+                response.changed = true;
+                self.last_item = response;
+            }
+        }
+        self.draw_rect(rect, self.widget_color(&response));
+        let t = if max > min { ((*value - min) / (max - min)).clamp(0.0, 1.0) } else { 0.0 };
+        let handle_width = 3.0;
+        self.draw_rect(
+            Rect::new(
+                Vf2d::new(rect.pos.x + (rect.size.x - handle_width) * t, rect.pos.y),
+                Vf2d::new(handle_width, rect.size.y),
+            ),
+            self.ui.style.accent,
+        );
+        let mut options = self.text_options();
+        options.max_width = Some(rect.size.x);
+        options.align = TextAlign::Center;
+        options.wrap = false;
+        let text = format!("{}: {:.2}", visible_label(label), value);
+        let layout = self.layout_text(&text, options);
+        self.draw_text(rect.pos + Vf2d::new(0.0, self.ui.style.padding), layout);
+        response.changed
+    }
+
+    /// Single line text field. Click to focus, Return or Escape to unfocus.
+    pub fn text_input(&mut self, label: &str, value: &mut String) -> bool {
+        let id = self.id(label);
+        let padding = self.ui.style.padding;
+        let height = self.line_height() + padding * 2.0;
+        let width = self.available_width();
+        let rect = self.allocate(Vf2d::new(width, height));
+        let mut response = self.interact(id, rect);
+        if response.pressed {
+            self.ui.focused = Some(id);
+            self.ui.text_cursor = value.chars().count();
+        } else if self.input.left.pressed && !response.hovered && self.ui.focused == Some(id) {
+            self.ui.focused = None;
+        }
+
+        if self.ui.focused == Some(id) {
+            let mut cursor = self.ui.text_cursor;
+            let edit = edit_text(value, &mut cursor, &self.input.text);
+            if edit.submitted || edit.cancelled {
+                self.ui.focused = None;
+            }
+            if edit.changed {
+                response.changed = true;
+                self.last_item = response;
+            }
+            self.ui.text_cursor = cursor;
+        }
+
+        let focused = self.ui.focused == Some(id);
+        let color = if focused { self.ui.style.widget_active } else { self.widget_color(&response) };
+        self.draw_rect(rect, color);
+        let mut options = self.text_options();
+        options.wrap = false;
+        //Text the input method is composing shows at the cursor until committed
+        let preedit = if focused { self.engine.text_input.preedit().to_string() } else { String::new() };
+        let (text, placeholder) = if value.is_empty() && preedit.is_empty() && !focused {
+            (visible_label(label).to_string(), true)
+        } else {
+            let mut text: String = value.chars().take(self.ui.text_cursor).collect();
+            text.push_str(&preedit);
+            text.extend(value.chars().skip(self.ui.text_cursor));
+            (text, false)
+        };
+        if placeholder {
+            options.style.color = self.ui.style.text_disabled;
+        }
+        //Scroll long text so the cursor stays in view
+        let mut before: String = value.chars().take(self.ui.text_cursor).collect();
+        if let Some((_, end)) = self.engine.text_input.preedit_cursor().filter(|_| focused) {
+            before.push_str(preedit.get(..end).unwrap_or(&preedit));
+        }
+        let cursor_x = self.layout_text(&before, options).size.x;
+        let inner = rect.size.x - padding * 2.0;
+        let scroll = if focused { (cursor_x - inner + 1.0).max(0.0) } else { 0.0 };
+        let mut layout = self.layout_text(&text, options);
+        layout.glyphs.retain(|g| g.pos.x >= scroll && g.pos.x - scroll < inner);
+        for g in &mut layout.glyphs {
+            g.pos.x -= scroll;
+        }
+        layout.size.x = layout.size.x.min(inner);
+        self.draw_text(rect.pos + Vf2d::new(padding, padding), layout);
+        if focused {
+            let line_height = self.line_height();
+            let cursor_pos = rect.pos + Vf2d::new(padding + cursor_x - scroll, padding);
+            self.draw_rect(Rect::new(cursor_pos, Vf2d::new(1.0, line_height)), self.ui.style.accent);
+            self.engine.set_ime_position(cursor_pos + Vf2d::new(0.0, line_height));
+        }
+        response.changed
+    }
+
+    /// Selectable list of `items` in a scroll area `height` pixels tall.
+    pub fn list<S: AsRef<str>>(&mut self, label: &str, items: &[S], selected: &mut Option<usize>, height: f32) -> bool {
+        let mut changed = false;
+        self.scroll_area(label, height, |ui| {
+            let width = ui.available_width();
+            let padding = ui.ui.style.padding;
+            for (i, item) in items.iter().enumerate() {
+                let id = ui.id(&format!("{}#{}", label, i));
+                let layout = ui.layout_text(item.as_ref(), ui.text_options());
+                let rect = ui.allocate(Vf2d::new(width, layout.size.y + padding));
+                let response = ui.interact(id, rect);
+                if response.clicked && *selected != Some(i) {
+                    *selected = Some(i);
+                    changed = true;
+                }
+                if *selected == Some(i) {
+                    ui.draw_rect(rect, ui.ui.style.widget_active);
+                } else if response.hovered {
+                    ui.draw_rect(rect, ui.ui.style.widget_hovered);
+                }
+                ui.draw_text(rect.pos + Vf2d::new(padding, padding * 0.5), layout);
+            }
+        });
+        changed
+    }
+
+    /// Draws `text` next to the mouse when the last widget is hovered.
+    pub fn tooltip(&mut self, text: &str) {
+        if !self.last_item.hovered {
+            return;
+        }
+        let padding = self.ui.style.padding;
+        let mut options = self.text_options();
+        options.markup = true;
+        options.max_width = Some(self.engine.pixels_w as f32 / 2.0);
+        let layout = self.layout_text(text, options);
+        let size = layout.size + Vf2d::new(padding * 2.0, padding * 2.0);
+        let screen = Vf2d::new(self.engine.pixels_w as f32, self.engine.pixels_h as f32);
+        //Keep the tooltip on screen
+        let pos = Vf2d::new(
+            (self.mouse.x + 8.0).min(screen.x - size.x).max(0.0),
+            (self.mouse.y + 8.0).min(screen.y - size.y).max(0.0),
+        );
+        self.overlay.insert(self.overlay.len(), DrawCmd::Rect(Rect::new(pos, size), self.ui.style.tooltip));
+        self.overlay.insert(self.overlay.len(), DrawCmd::Text(pos + Vf2d::new(padding, padding), layout));
+    }
+
+    fn with_layout(&mut self, bounds: Rect, direction: Direction, add_contents: impl FnOnce(&mut Self)) -> Rect {
+        let clip = self.layouts.last().unwrap().clip;
+        let mut layout = LayoutState::new(bounds, direction);
+        layout.clip = clip;
+        self.layouts.insert(self.layouts.len(), layout);
+        add_contents(self);
+        let layout = self.layouts.pop().unwrap();
+        Rect::new(bounds.pos, layout.extent - bounds.pos)
+    }
+
+    /// Lays out the widgets added by `add_contents` left to right.
+    pub fn horizontal(&mut self, add_contents: impl FnOnce(&mut Self)) {
+        let layout = *self.layouts.last().unwrap();
+        let bounds = Rect::new(layout.cursor, layout.bounds.max() - layout.cursor);
+        let used = self.with_layout(bounds, Direction::Horizontal, add_contents);
+        self.allocate(used.size);
+    }
+
+    /// Lays out the widgets added by `add_contents` top to bottom.
+    pub fn vertical(&mut self, add_contents: impl FnOnce(&mut Self)) {
+        let layout = *self.layouts.last().unwrap();
+        let bounds = Rect::new(layout.cursor, layout.bounds.max() - layout.cursor);
+        let used = self.with_layout(bounds, Direction::Vertical, add_contents);
+        self.allocate(used.size);
+    }
+
+    pub fn indent(&mut self, amount: f32, add_contents: impl FnOnce(&mut Self)) {
+        let layout = *self.layouts.last().unwrap();
+        let pos = layout.cursor + Vf2d::new(amount, 0.0);
+        let bounds = Rect::new(pos, layout.bounds.max() - pos);
+        let used = self.with_layout(bounds, Direction::Vertical, add_contents);
+        self.allocate(used.size + Vf2d::new(amount, 0.0));
+    }
+
+    /// Clips its contents to `height` pixels and scrolls them with the mouse
+    /// wheel. Widgets only partly inside are hidden.
+    pub fn scroll_area(&mut self, label: &str, height: f32, add_contents: impl FnOnce(&mut Self)) {
+        let id = self.id(label);
+        let width = self.available_width();
+        let rect = self.allocate(Vf2d::new(width, height));
+        let bar = self.ui.style.scrollbar_width;
+        let mut scroll = self.ui.scroll.get(&id).copied().unwrap_or(0.0);
+
+        let parent_clip = self.layouts.last().unwrap().clip;
+        let content = Rect::new(rect.pos - Vf2d::new(0.0, scroll), Vf2d::new(width - bar, f32::INFINITY));
+        let mut layout = LayoutState::new(content, Direction::Vertical);
+        layout.clip = intersect(&parent_clip, &Rect::new(rect.pos, Vf2d::new(width - bar, height)));
+        self.layouts.insert(self.layouts.len(), layout);
+        self.id_stack.insert(self.id_stack.len(), id);
+        add_contents(self);
+        self.id_stack.pop();
+        let layout = self.layouts.pop().unwrap();
+
+        let content_height = layout.extent.y - content.pos.y;
+        let max_scroll = (content_height - height).max(0.0);
+        if rect.contains(self.mouse) && self.current_window() == self.hovered_window {
+            scroll -= self.input.wheel.signum() as f32 * self.line_height() * 3.0;
+            self.any_hovered = true;
+        }
+        scroll = scroll.clamp(0.0, max_scroll);
+        self.ui.scroll.insert(id, scroll);
+
+        if max_scroll > 0.0 {
+            let track = Rect::new(rect.pos + Vf2d::new(width - bar, 0.0), Vf2d::new(bar, height));
+            let thumb_height = (height * height / content_height).max(bar);
+            let thumb_y = (height - thumb_height) * scroll / max_scroll;
+            self.draw_rect(track, self.ui.style.widget);
+            self.draw_rect(
+                Rect::new(track.pos + Vf2d::new(0.0, thumb_y), Vf2d::new(bar, thumb_height)),
+                self.ui.style.accent,
+            );
+        }
+    }
+
+    /// Fixed panel for HUDs and menus.
+    pub fn panel(&mut self, label: &str, pos: Vf2d, size: Vf2d, add_contents: impl FnOnce(&mut Self)) {
+        let id = hash_id(ROOT, label);
+        let rect = Rect::new(pos, size);
+        self.container(id, rect, self.ui.style.panel, add_contents);
+    }
+
+    /// Movable window with a title bar and close button. `pos` and `size` are
+    /// only used the first time. Returns false once the window is closed.
+    pub fn window(&mut self, title: &str, pos: Vf2d, size: Vf2d, add_contents: impl FnOnce(&mut Self)) -> bool {
+        let id = hash_id(ROOT, title);
+        let mut state = *self.ui.windows.entry(id).or_insert(WindowState {
+            rect: Rect::new(pos, size),
+            open: true,
+            drag: Vf2d::new(0.0, 0.0),
+        });
+        if !state.open {
+            return false;
+        }
+        if !self.ui.order.contains(&id) {
+            self.ui.order.insert(self.ui.order.len(), id);
+        }
+
+        let padding = self.ui.style.padding;
+        let title_height = self.line_height() + padding * 2.0;
+        let title_rect = Rect::new(state.rect.pos, Vf2d::new(state.rect.size.x, title_height));
+        let close_rect = Rect::new(
+            title_rect.pos + Vf2d::new(title_rect.size.x - title_height, 0.0),
+            Vf2d::new(title_height, title_height),
+        );
+
+        self.window_stack.insert(self.window_stack.len(), id);
+        let close = self.interact(hash_id(id, "##close"), close_rect);
+        let drag = self.interact(hash_id(id, "##title"), title_rect);
+        if close.clicked {
+            state.open = false;
+        }
+        if drag.pressed {
+            state.drag = self.mouse - state.rect.pos;
+        }
+        if drag.held && !close.held {
+            state.rect.pos = self.mouse - state.drag;
+        }
+        let title_color = if self.ui.order.last() == Some(&id) {
+            self.ui.style.title_active
+        } else {
+            self.ui.style.title
+        };
+        let title_rect = Rect::new(state.rect.pos, title_rect.size);
+        self.draw(DrawCmd::Rect(title_rect, title_color));
+        let mut options = self.text_options();
+        options.wrap = false;
+        options.ellipsis = true;
+        options.max_width = Some((title_rect.size.x - title_height - padding).max(0.0));
+        let layout = self.layout_text(visible_label(title), options);
+        self.draw(DrawCmd::Text(title_rect.pos + Vf2d::new(padding, padding), layout));
+        let x = self.layout_text("x", self.text_options());
+        let close_pos = title_rect.pos + Vf2d::new(title_rect.size.x - title_height + padding, padding);
+        let close_color = if close.hovered { self.ui.style.widget_hovered } else { title_color };
+        self.draw(DrawCmd::Rect(Rect::new(close_pos - Vf2d::new(padding, padding), close_rect.size), close_color));
+        self.draw(DrawCmd::Text(close_pos, x));
+        self.window_stack.pop();
+
+        let body = Rect::new(
+            state.rect.pos + Vf2d::new(0.0, title_height),
+            state.rect.size - Vf2d::new(0.0, title_height),
+        );
+        self.container(id, body, self.ui.style.window, add_contents);
+        //The title bar is part of the window for hovering and raising
+        self.rects.insert(self.rects.len(), (id, Rect::new(state.rect.pos, state.rect.size)));
+        self.ui.windows.insert(id, state);
+        state.open
+    }
+
+    fn container(&mut self, id: WidgetId, rect: Rect, background: Pixel, add_contents: impl FnOnce(&mut Self)) {
+        if !self.ui.order.contains(&id) {
+            self.ui.order.insert(self.ui.order.len(), id);
+        }
+        let padding = self.ui.style.padding;
+        self.window_stack.insert(self.window_stack.len(), id);
+        self.id_stack.insert(self.id_stack.len(), id);
+        self.draw(DrawCmd::Rect(rect, background));
+
+        let inner = Rect::new(
+            rect.pos + Vf2d::new(padding, padding),
+            rect.size - Vf2d::new(padding * 2.0, padding * 2.0),
+        );
+        let mut layout = LayoutState::new(inner, Direction::Vertical);
+        layout.clip = inner;
+        let saved = std::mem::replace(&mut self.layouts, vec![layout]);
+        add_contents(self);
+        self.layouts = saved;
+
+        self.id_stack.pop();
+        self.window_stack.pop();
+        self.rects.insert(self.rects.len(), (id, rect));
+        if rect.contains(self.mouse) && self.hovered_window == id {
+            self.any_hovered = true;
+            //Clicking anywhere in a window brings it to the front
+            if self.input.left.pressed {
+                self.ui.order.retain(|w| *w != id);
+                self.ui.order.insert(self.ui.order.len(), id);
+            }
+        }
+    }
+
+    /// Draws the UI and hides the input it used from the game.
+    pub fn end(mut self) {
+        if !self.input.left.held {
+            self.ui.active = None;
+        }
+        //Forget windows and panels that weren't built this frame
+        let rects = std::mem::take(&mut self.rects);
+        self.ui.order.retain(|id| rects.iter().any(|(w, _)| w == id));
+
+        self.engine.capture_input(InputCapture {
+            mouse: self.any_hovered || self.ui.active.is_some(),
+            keyboard: self.ui.focused.is_some(),
+        });
+        self.engine.set_ime_allowed(self.ui.focused.is_some());
+
+        let target = self.engine.draw_target;
+        if let Some(layer) = self.engine.get_layer_by_mask(Mask::GUI) {
+            self.engine.draw_target = layer;
+        }
+        let mut lists = std::mem::take(&mut self.draw_lists);
+        let mut commands = lists.remove(&ROOT).unwrap_or_default();
+        for id in &self.ui.order {
+            commands.extend(lists.remove(id).unwrap_or_default());
+        }
+        commands.extend(std::mem::take(&mut self.overlay));
+        for cmd in commands {
+            match cmd {
+                DrawCmd::Rect(rect, color) => self.engine.fill_rect_decal(rect.pos, rect.size, color),
+                DrawCmd::Text(pos, layout) => self.engine.draw_text_decal(pos, &layout),
+            }
+        }
+        self.engine.draw_target = target;
+        self.ui.last_rects = rects;
+    }
+}
+
+fn intersect(a: &Rect, b: &Rect) -> Rect {
+    let min = Vf2d::new(a.pos.x.max(b.pos.x), a.pos.y.max(b.pos.y));
+    let max = Vf2d::new(a.max().x.min(b.max().x), a.max().y.min(b.max().y));
+    Rect::new(min, Vf2d::new((max.x - min.x).max(0.0), (max.y - min.y).max(0.0)))
+}
@@ -1,4 +1,7 @@
 use super::{
+    game_object::GameObject,
+    geometry::Mesh,
+    gltf_ext::try_get_game_objects,
     og_engine::Rcode,
     pixel::Pixel,
     sprite::Sprite,
@@ -280,7 +283,7 @@ impl HWButton {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Mouse {
     Left,
     Right,
@@ -306,101 +309,631 @@ pub trait ImageLoader {
 
 pub struct BMPLoader;
 pub struct PNGLoader;
+pub struct TGALoader;
+pub struct JPEGLoader;
+pub struct QOILoader;
+/// Picks the format from the file contents, falling back to the extension.
+/// Saves by extension, defaulting to PNG.
+pub struct AutoLoader;
 
-pub struct ResourceBuffer {}
+/// Image file formats the engine can read and write.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFileFormat {
+    Png,
+    Bmp,
+    Tga,
+    Jpeg,
+    Qoi,
+}
 
-pub struct ResourcePack {}
+impl ImageFileFormat {
+    /// Guesses the format from the first bytes of a file.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if bytes.starts_with(b"BM") {
+            Some(Self::Bmp)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if bytes.starts_with(b"qoif") {
+            Some(Self::Qoi)
+        } else if is_tga(bytes) {
+            Some(Self::Tga)
+        } else {
+            None
+        }
+    }
 
-impl ImageLoader for BMPLoader {
-    fn load_image_resource(image_file: &str) -> Result<Sprite,Rcode> {
-        let image_path = std::path::Path::new(image_file);
-        if !image_path.exists() {
-            return Err(Rcode::NoFile);
-        };
-        let img = bmp::open(image_path).unwrap_or_else(|e| bmp::Image::new(0, 0));
-        if img.get_width() == 0 || img.get_height() == 0 {
-            return Err(Rcode::Fail);
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = std::path::Path::new(path).extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "png" => Some(Self::Png),
+            "bmp" | "dib" => Some(Self::Bmp),
+            "tga" | "targa" => Some(Self::Tga),
+            "jpg" | "jpeg" | "jpe" | "jfif" => Some(Self::Jpeg),
+            "qoi" => Some(Self::Qoi),
+            _ => None,
         }
-        let mut spr = Sprite::new(
-        img.get_width(),
-        img.get_height());
-        //No Alpha for now because BMP is a dumb format
-        spr.col_data = vec![Pixel::rgb(0, 0, 0); (spr.width * spr.height) as usize];
-        for y in 0..spr.height {
-            for x in 0..spr.width {
-                let p = img.get_pixel(x, y);
-                spr.set_pixel(x, y, Pixel::rgb(p.r, p.g, p.b));
+    }
+}
+
+//TGA has no magic number. Look for the v2 footer, then sanity check the header
+fn is_tga(bytes: &[u8]) -> bool {
+    let len = bytes.len();
+    if len >= 44 && &bytes[len - 18..len - 2] == b"TRUEVISION-XFILE" {
+        return true;
+    }
+    len >= 18
+        && bytes[1] <= 1
+        && matches!(bytes[2], 1 | 2 | 3 | 9 | 10 | 11)
+        && matches!(bytes[16], 8 | 15 | 16 | 24 | 32)
+}
+
+/// Builds a sprite from tightly packed 8 bit RGBA rows.
+pub fn rgba_to_sprite(width: u32, height: u32, rgba: &[u8]) -> Sprite {
+    let mut spr = Sprite::new(width, height);
+    spr.set_data(rgba, 4);
+    spr
+}
+
+pub fn sprite_to_rgba(spr: &Sprite) -> Vec<u8> {
+    spr.col_data
+        .iter()
+        .flat_map(|p| [p.r(), p.g(), p.b(), p.a()])
+        .collect()
+}
+
+pub fn decode_image(bytes: &[u8], format: ImageFileFormat) -> Result<Sprite, Rcode> {
+    let image_format = match format {
+        ImageFileFormat::Qoi => return decode_qoi(bytes),
+        ImageFileFormat::Png => image::ImageFormat::Png,
+        ImageFileFormat::Bmp => image::ImageFormat::Bmp,
+        ImageFileFormat::Tga => image::ImageFormat::Tga,
+        ImageFileFormat::Jpeg => image::ImageFormat::Jpeg,
+    };
+    let img = image::load_from_memory_with_format(bytes, image_format)
+        .map_err(|_| Rcode::Fail)?
+        .into_rgba();
+    Ok(rgba_to_sprite(img.width(), img.height(), &img.into_raw()))
+}
+
+pub fn encode_image(spr: &Sprite, format: ImageFileFormat) -> Result<Vec<u8>, Rcode> {
+    let output_format = match format {
+        ImageFileFormat::Qoi => return Ok(encode_qoi(spr)),
+        ImageFileFormat::Png => image::ImageOutputFormat::Png,
+        //Written as 32 bit so alpha survives
+        ImageFileFormat::Bmp => image::ImageOutputFormat::Bmp,
+        ImageFileFormat::Tga => image::ImageOutputFormat::Tga,
+        ImageFileFormat::Jpeg => image::ImageOutputFormat::Jpeg(90),
+    };
+    let buffer = image::RgbaImage::from_raw(spr.width, spr.height, sprite_to_rgba(spr))
+        .ok_or(Rcode::Fail)?;
+    let img = if format == ImageFileFormat::Jpeg {
+        //JPEG has no alpha channel
+        image::DynamicImage::ImageRgb8(image::DynamicImage::ImageRgba8(buffer).into_rgb())
+    } else {
+        image::DynamicImage::ImageRgba8(buffer)
+    };
+    let mut out: Vec<u8> = vec![];
+    img.write_to(&mut out, output_format).map_err(|_| Rcode::Fail)?;
+    Ok(out)
+}
+
+fn read_image_file(image_file: &str) -> Result<Vec<u8>, Rcode> {
+    std::fs::read(image_file).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => Rcode::NoFile,
+        _ => Rcode::Fail,
+    })
+}
+
+fn write_image_file(spr: &Sprite, image_file: &str, format: ImageFileFormat) -> Result<(), Rcode> {
+    std::fs::write(image_file, encode_image(spr, format)?).map_err(|_| Rcode::Fail)
+}
+
+macro_rules! image_loader {
+    ($loader:ty, $format:expr) => {
+        impl ImageLoader for $loader {
+            fn load_image_resource(image_file: &str) -> Result<Sprite, Rcode> {
+                decode_image(&read_image_file(image_file)?, $format)
+            }
+
+            fn load_image_from_bytes(bytes: &[u8]) -> Result<Sprite, Rcode> {
+                decode_image(bytes, $format)
+            }
+
+            fn save_image_resource(spr: Sprite, image_file: &str) -> Result<(), Rcode> {
+                write_image_file(&spr, image_file, $format)
             }
         }
-        Ok(spr)
+    };
+}
+
+image_loader!(BMPLoader, ImageFileFormat::Bmp);
+image_loader!(PNGLoader, ImageFileFormat::Png);
+image_loader!(TGALoader, ImageFileFormat::Tga);
+image_loader!(JPEGLoader, ImageFileFormat::Jpeg);
+image_loader!(QOILoader, ImageFileFormat::Qoi);
+
+impl ImageLoader for AutoLoader {
+    fn load_image_resource(image_file: &str) -> Result<Sprite, Rcode> {
+        let bytes = read_image_file(image_file)?;
+        let format = ImageFileFormat::from_bytes(&bytes)
+            .or_else(|| ImageFileFormat::from_path(image_file))
+            .ok_or(Rcode::Fail)?;
+        decode_image(&bytes, format)
     }
 
-    fn load_image_from_bytes(bytes: &[u8]) -> Result<Sprite, Rcode>{
-        Err(Rcode::Fail)
+    fn load_image_from_bytes(bytes: &[u8]) -> Result<Sprite, Rcode> {
+        decode_image(bytes, ImageFileFormat::from_bytes(bytes).ok_or(Rcode::Fail)?)
     }
 
     fn save_image_resource(spr: Sprite, image_file: &str) -> Result<(), Rcode> {
-        Ok(())
+        let format = ImageFileFormat::from_path(image_file).unwrap_or(ImageFileFormat::Png);
+        write_image_file(&spr, image_file, format)
     }
 }
 
+//QOI, the "Quite OK Image" format. https://qoiformat.org/qoi-specification.pdf
+const QOI_OP_INDEX: u8 = 0x00;
+const QOI_OP_DIFF: u8 = 0x40;
+const QOI_OP_LUMA: u8 = 0x80;
+const QOI_OP_RUN: u8 = 0xC0;
+const QOI_OP_RGB: u8 = 0xFE;
+const QOI_OP_RGBA: u8 = 0xFF;
+const QOI_END: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
 
-impl ImageLoader for PNGLoader {
-    fn load_image_resource(image_file: &str) -> Result<Sprite, Rcode> {
-        use image::io::Reader as ImageReader;
-        let image_path = std::path::Path::new(image_file);
-        if !image_path.exists() {
-            return Err(Rcode::NoFile);
+fn qoi_hash(px: [u8; 4]) -> usize {
+    (px[0] as usize * 3 + px[1] as usize * 5 + px[2] as usize * 7 + px[3] as usize * 11) % 64
+}
+
+fn decode_qoi(bytes: &[u8]) -> Result<Sprite, Rcode> {
+    if bytes.len() < 14 + QOI_END.len() || &bytes[0..4] != b"qoif" {
+        return Err(Rcode::Fail);
+    }
+    let width = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    let height = u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+    let count = (width as usize)
+        .checked_mul(height as usize)
+        .filter(|c| *c <= 400_000_000)
+        .ok_or(Rcode::Fail)?;
+
+    let data = &bytes[14..bytes.len() - QOI_END.len()];
+    let take = |p: &mut usize, n: usize| -> Result<&[u8], Rcode> {
+        let out = data.get(*p..*p + n).ok_or(Rcode::Fail)?;
+        *p += n;
+        Ok(out)
+    };
+    let mut index = [[0_u8; 4]; 64];
+    let mut px = [0_u8, 0, 0, 255];
+    let mut out: Vec<u8> = Vec::with_capacity(count * 4);
+    let mut p = 0;
+    let mut run = 0;
+    while out.len() < count * 4 {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let b1 = take(&mut p, 1)?[0];
+            if b1 == QOI_OP_RGB {
+                px[0..3].copy_from_slice(take(&mut p, 3)?);
+            } else if b1 == QOI_OP_RGBA {
+                px.copy_from_slice(take(&mut p, 4)?);
+            } else {
+                match b1 & 0xC0 {
+                    QOI_OP_INDEX => px = index[b1 as usize],
+                    QOI_OP_DIFF => {
+                        px[0] = px[0].wrapping_add(((b1 >> 4) & 0x03).wrapping_sub(2));
+                        px[1] = px[1].wrapping_add(((b1 >> 2) & 0x03).wrapping_sub(2));
+                        px[2] = px[2].wrapping_add((b1 & 0x03).wrapping_sub(2));
+                    }
+                    QOI_OP_LUMA => {
+                        let b2 = take(&mut p, 1)?[0];
+                        let vg = (b1 & 0x3F).wrapping_sub(32);
+                        px[0] = px[0].wrapping_add(vg.wrapping_sub(8).wrapping_add(b2 >> 4));
+                        px[1] = px[1].wrapping_add(vg);
+                        px[2] = px[2].wrapping_add(vg.wrapping_sub(8).wrapping_add(b2 & 0x0F));
+                    }
+                    _ => run = b1 & 0x3F,
+                }
+            }
+            index[qoi_hash(px)] = px;
         }
+        out.extend_from_slice(&px);
+    }
+    Ok(rgba_to_sprite(width, height, &out))
+}
 
-        let mut img = if let Ok(reader) = ImageReader::open(image_path){
-            reader.decode().expect("Can't decode the image").into_rgba()
-        } else{
-            return Err(Rcode::Fail);
+fn encode_qoi(spr: &Sprite) -> Vec<u8> {
+    let rgba = sprite_to_rgba(spr);
+    let mut out: Vec<u8> = Vec::with_capacity(14 + rgba.len() / 2);
+    out.extend_from_slice(b"qoif");
+    out.extend_from_slice(&spr.width.to_be_bytes());
+    out.extend_from_slice(&spr.height.to_be_bytes());
+    //4 channels, sRGB with linear alpha
+    out.extend_from_slice(&[4, 0]);
+
+    let mut index = [[0_u8; 4]; 64];
+    let mut prev = [0_u8, 0, 0, 255];
+    let mut run: u8 = 0;
+    let last = rgba.len() / 4;
+    for (i, c) in rgba.chunks_exact(4).enumerate() {
+        let px = [c[0], c[1], c[2], c[3]];
+        if px == prev {
+            run += 1;
+            if run == 62 || i + 1 == last {
+                out.push(QOI_OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            out.push(QOI_OP_RUN | (run - 1));
+            run = 0;
+        }
+        let hash = qoi_hash(px);
+        if index[hash] == px {
+            out.push(QOI_OP_INDEX | hash as u8);
+        } else {
+            index[hash] = px;
+            if px[3] == prev[3] {
+                let vr = px[0].wrapping_sub(prev[0]) as i8;
+                let vg = px[1].wrapping_sub(prev[1]) as i8;
+                let vb = px[2].wrapping_sub(prev[2]) as i8;
+                let vg_r = vr.wrapping_sub(vg);
+                let vg_b = vb.wrapping_sub(vg);
+                if (-2..=1).contains(&vr) && (-2..=1).contains(&vg) && (-2..=1).contains(&vb) {
+                    out.push(
+                        QOI_OP_DIFF
+                            | ((vr + 2) as u8) << 4
+                            | ((vg + 2) as u8) << 2
+                            | (vb + 2) as u8,
+                    );
+                } else if (-8..=7).contains(&vg_r)
+                    && (-32..=31).contains(&vg)
+                    && (-8..=7).contains(&vg_b)
+                {
+                    out.push(QOI_OP_LUMA | (vg + 32) as u8);
+                    out.push(((vg_r + 8) as u8) << 4 | (vg_b + 8) as u8);
+                } else {
+                    out.extend_from_slice(&[QOI_OP_RGB, px[0], px[1], px[2]]);
+                }
+            } else {
+                out.extend_from_slice(&[QOI_OP_RGBA, px[0], px[1], px[2], px[3]]);
+            }
+        }
+        prev = px;
+    }
+    out.extend_from_slice(&QOI_END);
+    out
+}
+
+/// A single file read out of a `ResourcePack`.
+pub struct ResourceBuffer {
+    cursor: std::io::Cursor<Vec<u8>>,
+}
+
+impl ResourceBuffer {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            cursor: std::io::Cursor::new(data),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.cursor.get_ref()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.cursor.into_inner()
+    }
+}
+
+impl std::io::Read for ResourceBuffer {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.cursor.read(buf)
+    }
+}
+
+impl std::io::BufRead for ResourceBuffer {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.cursor.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.cursor.consume(amt)
+    }
+}
+
+impl std::io::Seek for ResourceBuffer {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.cursor.seek(pos)
+    }
+}
+
+const PACK_MAGIC: &[u8; 4] = b"OGRP";
+const PACK_VERSION: u32 = 1;
+const PACK_COMPRESSED: u8 = 0b1;
+
+#[derive(Clone, Copy, Debug)]
+struct ResourceFile {
+    offset: u32,
+    stored_size: u32,
+    size: u32,
+}
+
+impl ResourceFile {
+    //Where the stored bytes are in the pack data, None if it overflows
+    fn range(&self) -> Option<std::ops::Range<usize>> {
+        let start = self.offset as usize;
+        Some(start..start.checked_add(self.stored_size as usize)?)
+    }
+}
+
+/// Bundles many files into a single archive.
+///
+/// Layout (little endian): `"OGRP"`, version `u32`, flags `u8`, index length
+/// `u32`, index, file data. The index is a `u32` count followed by
+/// `path length u32, path, offset u32, stored size u32, size u32` for every
+/// file. When a key is given, the index and the data are XORed with it.
+/// When compressed, every file is zlib compressed on its own so files can be
+/// read without unpacking the whole archive.
+#[derive(Default)]
+pub struct ResourcePack {
+    files: std::collections::BTreeMap<String, ResourceFile>,
+    data: Vec<u8>,
+    key: Vec<u8>,
+    compressed: bool,
+}
+
+impl ResourcePack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn loaded(&self) -> bool {
+        !self.files.is_empty()
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.files.contains_key(&pack_path(path))
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(|k| k.as_str())
+    }
+
+    /// Adds a file from memory, replacing any file already stored at `path`.
+    pub fn add_bytes(&mut self, path: &str, bytes: &[u8]) {
+        //Files added to a loaded pack are kept in the clear until the pack is saved
+        self.unpack();
+        let offset = self.data.len() as u32;
+        self.data.extend_from_slice(bytes);
+        self.files.insert(
+            pack_path(path),
+            ResourceFile {
+                offset,
+                stored_size: bytes.len() as u32,
+                size: bytes.len() as u32,
+            },
+        );
+    }
+
+    pub fn add_file(&mut self, path: &str) -> Result<(), Rcode> {
+        let bytes = std::fs::read(path).map_err(|_| Rcode::NoFile)?;
+        self.add_bytes(path, &bytes);
+        Ok(())
+    }
+
+    /// Adds every file below `dir`, stored relative to `dir`.
+    pub fn add_directory(&mut self, dir: &str) -> Result<(), Rcode> {
+        let root = std::path::Path::new(dir);
+        let mut dirs = vec![root.to_path_buf()];
+        while let Some(current) = dirs.pop() {
+            for entry in std::fs::read_dir(&current).map_err(|_| Rcode::NoFile)? {
+                let path = entry.map_err(|_| Rcode::Fail)?.path();
+                if path.is_dir() {
+                    dirs.insert(dirs.len(), path);
+                } else {
+                    let bytes = std::fs::read(&path).map_err(|_| Rcode::Fail)?;
+                    let name = path.strip_prefix(root).unwrap_or(&path);
+                    self.add_bytes(&name.to_string_lossy(), &bytes);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save_pack(&self, file: &str, key: &str, compress: bool) -> Result<(), Rcode> {
+        std::fs::write(file, self.to_bytes(key, compress)?).map_err(|_| Rcode::Fail)
+    }
+
+    pub fn to_bytes(&self, key: &str, compress: bool) -> Result<Vec<u8>, Rcode> {
+        use std::io::Write;
+        let mut files: Vec<(&String, ResourceFile)> = vec![];
+        let mut data: Vec<u8> = vec![];
+        for (path, file) in &self.files {
+            let bytes = self.read_file(file)?;
+            let stored = if compress {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(vec![], flate2::Compression::default());
+                encoder.write_all(&bytes).map_err(|_| Rcode::Fail)?;
+                encoder.finish().map_err(|_| Rcode::Fail)?
+            } else {
+                bytes
+            };
+            files.insert(
+                files.len(),
+                (
+                    path,
+                    ResourceFile {
+                        offset: data.len() as u32,
+                        stored_size: stored.len() as u32,
+                        size: file.size,
+                    },
+                ),
+            );
+            data.extend_from_slice(&stored);
+        }
+
+        let mut index: Vec<u8> = vec![];
+        index.extend_from_slice(&(files.len() as u32).to_le_bytes());
+        for (path, file) in files {
+            index.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            index.extend_from_slice(path.as_bytes());
+            index.extend_from_slice(&file.offset.to_le_bytes());
+            index.extend_from_slice(&file.stored_size.to_le_bytes());
+            index.extend_from_slice(&file.size.to_le_bytes());
+        }
+        scramble(&mut index, key.as_bytes(), 0);
+        scramble(&mut data, key.as_bytes(), 0);
+
+        let mut out: Vec<u8> = Vec::with_capacity(13 + index.len() + data.len());
+        out.extend_from_slice(PACK_MAGIC);
+        out.extend_from_slice(&PACK_VERSION.to_le_bytes());
+        out.push(if compress { PACK_COMPRESSED } else { 0 });
+        out.extend_from_slice(&(index.len() as u32).to_le_bytes());
+        out.extend_from_slice(&index);
+        out.extend_from_slice(&data);
+        Ok(out)
+    }
+
+    pub fn load_pack(file: &str, key: &str) -> Result<Self, Rcode> {
+        let bytes = std::fs::read(file).map_err(|_| Rcode::NoFile)?;
+        Self::from_bytes(bytes, key)
+    }
+
+    /// Loads a pack with a single read, or a single fetch on wasm.
+    pub async fn load_pack_async(path: &str, key: &str) -> Result<Self, Rcode> {
+        Self::from_bytes(get_file_as_u8(path).await?, key)
+    }
+
+    pub fn from_bytes(mut bytes: Vec<u8>, key: &str) -> Result<Self, Rcode> {
+        //Sums are checked, usize is only 32 bits on wasm
+        let read_u32 = |b: &[u8], at: usize| -> Result<u32, Rcode> {
+            b.get(at..at.checked_add(4).ok_or(Rcode::Fail)?)
+                .map(|s| u32::from_le_bytes([s[0], s[1], s[2], s[3]]))
+                .ok_or(Rcode::Fail)
         };
-        let mut spr = Sprite::new(
-        img.width(),
-        img.height());
-        spr.col_data = vec![Pixel::BLANK; (spr.width * spr.height) as usize];
-        for y in 0..spr.height as usize {
-            for x in 0..spr.width as usize {
-                let c = img.get_pixel(x as u32, y as u32);
-                spr.set_pixel(
-                    x as u32,
-                    y as u32,
-                    Pixel::rgba(c[0], c[1], c[2], c[3]),
-                );
+        if bytes.len() < 13 || &bytes[0..4] != PACK_MAGIC || read_u32(&bytes, 4)? != PACK_VERSION {
+            return Err(Rcode::Fail);
+        }
+        let compressed = bytes[8] & PACK_COMPRESSED != 0;
+        let index_len = read_u32(&bytes, 9)? as usize;
+        let index_end = index_len.checked_add(13).ok_or(Rcode::Fail)?;
+        let data = bytes.split_off(index_end.min(bytes.len()));
+        let mut index = bytes.split_off(13);
+        if index.len() != index_len {
+            return Err(Rcode::Fail);
+        }
+        scramble(&mut index, key.as_bytes(), 0);
+
+        let mut files = std::collections::BTreeMap::new();
+        let mut at = 4;
+        for _ in 0..read_u32(&index, 0)? {
+            let path_len = read_u32(&index, at)? as usize;
+            let path_end = path_len.checked_add(at + 4).ok_or(Rcode::Fail)?;
+            let path = index
+                .get(at + 4..path_end)
+                .and_then(|p| std::str::from_utf8(p).ok())
+                .ok_or(Rcode::Fail)?
+                .to_string();
+            at = path_end;
+            let file = ResourceFile {
+                offset: read_u32(&index, at)?,
+                stored_size: read_u32(&index, at + 4)?,
+                size: read_u32(&index, at + 8)?,
+            };
+            at += 12;
+            if file.range().map_or(true, |range| range.end > data.len()) {
+                return Err(Rcode::Fail);
             }
+            files.insert(path, file);
+        }
+
+        Ok(Self {
+            files,
+            data,
+            key: key.as_bytes().to_vec(),
+            compressed,
+        })
+    }
+
+    pub fn get_file_buffer(&self, path: &str) -> Result<ResourceBuffer, Rcode> {
+        let file = self.files.get(&pack_path(path)).ok_or(Rcode::NoFile)?;
+        Ok(ResourceBuffer::new(self.read_file(file)?))
+    }
+
+    pub fn load_image<T: ImageLoader>(&self, path: &str) -> Result<Sprite, Rcode> {
+        T::load_image_from_bytes(self.get_file_buffer(path)?.as_bytes())
+    }
+
+    pub fn load_mesh(&self, path: &str, has_texture: bool) -> Result<Mesh, Rcode> {
+        let buffer = self.get_file_buffer(path)?;
+        let text = std::str::from_utf8(buffer.as_bytes()).map_err(|_| Rcode::Fail)?;
+        Ok(Mesh::load_from_string(text, has_texture))
+    }
+
+    /// Loads a .glb, or a .gltf with its buffers and images embedded.
+    pub fn load_gltf(&self, path: &str) -> Result<(Vec<GameObject>, Vec<gltf::image::Data>), Rcode> {
+        try_get_game_objects(self.get_file_buffer(path)?.as_bytes())
+    }
+
+    fn read_file(&self, file: &ResourceFile) -> Result<Vec<u8>, Rcode> {
+        use std::io::Read;
+        let range = file.range().ok_or(Rcode::Fail)?;
+        let start = range.start;
+        let mut stored = self.data.get(range).ok_or(Rcode::Fail)?.to_vec();
+        scramble(&mut stored, &self.key, start);
+        if !self.compressed {
+            return Ok(stored);
         }
-        Ok(spr)
-    }
-
-    fn load_image_from_bytes(bytes: &[u8]) -> Result<Sprite, Rcode>{
-        use image::io::Reader as ImageReader;
-
-        let mut reader = ImageReader::new(std::io::Cursor::new(bytes));
-        reader.set_format(image::ImageFormat::Png);
-        let image = reader.decode();
-        let mut img = image.expect("NAAAAAA").into_rgba();
-        let mut spr = Sprite::new(
-        img.width(),
-        img.height());
-        spr.col_data = vec![Pixel::BLANK; (spr.width * spr.height) as usize];
-        for y in 0..spr.height as usize {
-            for x in 0..spr.width as usize {
-                let c = img.get_pixel(x as u32, y as u32);
-                spr.set_pixel(
-                    x as u32,
-                    y as u32,
-                    Pixel::rgba(c[0], c[1], c[2], c[3]),
+        let mut out = Vec::with_capacity(file.size as usize);
+        flate2::read::ZlibDecoder::new(stored.as_slice())
+            .read_to_end(&mut out)
+            .map_err(|_| Rcode::Fail)?;
+        Ok(out)
+    }
+
+    //Turns a loaded pack back into plain, uncompressed data so files can be added
+    fn unpack(&mut self) {
+        if self.key.is_empty() && !self.compressed {
+            return;
+        }
+        let mut data: Vec<u8> = vec![];
+        let mut files = std::collections::BTreeMap::new();
+        for (path, file) in &self.files {
+            if let Ok(bytes) = self.read_file(file) {
+                files.insert(
+                    path.clone(),
+                    ResourceFile {
+                        offset: data.len() as u32,
+                        stored_size: bytes.len() as u32,
+                        size: bytes.len() as u32,
+                    },
                 );
+                data.extend_from_slice(&bytes);
             }
         }
-        Ok(spr)
+        self.files = files;
+        self.data = data;
+        self.key = vec![];
+        self.compressed = false;
     }
+}
 
-    fn save_image_resource(spr: Sprite, image_file: &str) -> Result<(), Rcode> {
-        Ok(())
+//Paths are stored with forward slashes and no leading "./"
+fn pack_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.trim_start_matches("./").trim_start_matches('/').to_string()
+}
+
+//XOR obfuscation. `offset` is the position of `data` in the stream so any
+// slice can be unscrambled on its own.
+fn scramble(data: &mut [u8], key: &[u8], offset: usize) {
+    if key.is_empty() {
+        return;
+    }
+    for (i, b) in data.iter_mut().enumerate() {
+        *b ^= key[(offset + i) % key.len()];
     }
 }
 
@@ -421,25 +954,81 @@ impl RoundTo<f32> for f32 {
 }
 
 #[cfg(target_arch = "wasm32")]
-pub async fn get_file_as_u8(path: &str) -> Vec<u8> {
+pub async fn get_file_as_u8(path: &str) -> Result<Vec<u8>, Rcode> {
         use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let request = Request::new_with_str(path).unwrap();
+        let window = web_sys::window().ok_or(Rcode::Fail)?;
+        let request = Request::new_with_str(path).map_err(|_| Rcode::Fail)?;
         let resp_value = JsFuture::from(window.fetch_with_request(&request))
             .await
-            .expect("No Future");
-        let resp: Response = resp_value.dyn_into().unwrap();
-        let buffer: js_sys::ArrayBuffer = JsFuture::from(resp.array_buffer().unwrap())
+            .map_err(|_| Rcode::Fail)?;
+        let resp: Response = resp_value.dyn_into().map_err(|_| Rcode::Fail)?;
+        if !resp.ok() {
+            return Err(Rcode::NoFile);
+        }
+        let buffer: js_sys::ArrayBuffer = JsFuture::from(resp.array_buffer().map_err(|_| Rcode::Fail)?)
             .await
-            .unwrap()
+            .map_err(|_| Rcode::Fail)?
             .dyn_into()
-            .unwrap();
-        js_sys::Uint8Array::new_with_byte_offset_and_length(&buffer, 0, buffer.byte_length() as u32)
-            .to_vec()
+            .map_err(|_| Rcode::Fail)?;
+        Ok(js_sys::Uint8Array::new_with_byte_offset_and_length(&buffer, 0, buffer.byte_length() as u32)
+            .to_vec())
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn get_file_as_u8(path: &str) -> Vec<u8> {
-    std::fs::read(path).expect("File does not exist")
+pub async fn get_file_as_u8(path: &str) -> Result<Vec<u8>, Rcode> {
+    std::fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => Rcode::NoFile,
+        _ => Rcode::Fail,
+    })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack() -> ResourcePack {
+        let mut pack = ResourcePack::new();
+        pack.add_bytes("./levels\\one.txt", b"first level");
+        pack.add_bytes("empty", b"");
+        pack.add_bytes("big.bin", &[7; 1000]);
+        pack
+    }
+
+    fn round_trip(key: &str, compress: bool) {
+        let bytes = pack().to_bytes(key, compress).unwrap();
+        let loaded = ResourcePack::from_bytes(bytes, key).unwrap();
+        assert_eq!(loaded.files().collect::<Vec<_>>(), vec!["big.bin", "empty", "levels/one.txt"]);
+        assert_eq!(loaded.get_file_buffer("levels/one.txt").unwrap().as_bytes(), b"first level");
+        assert!(loaded.get_file_buffer("empty").unwrap().as_bytes().is_empty());
+        assert_eq!(loaded.get_file_buffer("big.bin").unwrap().as_bytes(), &[7; 1000][..]);
+    }
+
+    #[test]
+    fn compressed_pack_round_trips() {
+        round_trip("", true);
+    }
+
+    #[test]
+    fn keyed_pack_round_trips() {
+        round_trip("secret", false);
+        round_trip("secret", true);
+        //The wrong key can't read the index
+        let bytes = pack().to_bytes("secret", false).unwrap();
+        assert!(ResourcePack::from_bytes(bytes, "other").is_err());
+    }
+
+    #[test]
+    fn corrupt_pack_fails() {
+        let mut bytes = pack().to_bytes("", false).unwrap();
+        //Index length past the end
+        bytes[9..13].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ResourcePack::from_bytes(bytes, "").is_err());
+
+        //A file offset that overflows
+        let mut bytes = pack().to_bytes("", false).unwrap();
+        let offset_at = 13 + 4 + 4 + "big.bin".len();
+        bytes[offset_at..offset_at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ResourcePack::from_bytes(bytes, "").is_err());
+        assert!(ResourcePack::from_bytes(b"OGRP".to_vec(), "").is_err());
+    }
+}